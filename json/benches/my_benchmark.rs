@@ -1,18 +1,92 @@
+use std::time::{Duration, Instant};
+
 // use serde::Deserialize;
 // use serde_json::Value;
 
+/// Corpora from https://github.com/serde-rs/json-benchmark,
+/// put them next to this file to include them in the run.
+const FILES: [&str; 3] = [
+    "./benches/citm_catalog.json",
+    "./benches/canada.json",
+    "./benches/twitter.json",
+];
+
+const ITERATIONS: u32 = 10;
+
 fn main() {
-    let string = std::fs::read_to_string("./benches/citm_catalog.json").unwrap();
+    let mut corpora = vec![];
+    for path in FILES {
+        match std::fs::read_to_string(path) {
+            Ok(string) => corpora.push((path.to_owned(), string)),
+            Err(error) => println!("Skipping {path}: {error}"),
+        }
+    }
+    corpora.push(("generated: numbers".to_owned(), numbers()));
+    corpora.push(("generated: strings".to_owned(), strings()));
+    corpora.push(("generated: pretty objects".to_owned(), pretty_objects()));
+
+    for (name, string) in &corpora {
+        println!("{name} ({:.1} MB)", megabytes(string));
 
-    let now = std::time::Instant::now();
-    json::parse(&string).unwrap();
-    println!("My json parse: {:?}", now.elapsed());
+        let elapsed = best_of(|| {
+            json::parse(string).unwrap();
+        });
+        println!(
+            "    My json parse:    {:>8.1} MB/s",
+            megabytes(string) / elapsed.as_secs_f64()
+        );
 
-    let now = std::time::Instant::now();
-    serde_json::from_str::<serde_json::Value>(&string).unwrap();
-    println!("Serde json parse: {:?}", now.elapsed());
+        let elapsed = best_of(|| {
+            serde_json::from_str::<serde_json::Value>(string).unwrap();
+        });
+        println!(
+            "    Serde json parse: {:>8.1} MB/s",
+            megabytes(string) / elapsed.as_secs_f64()
+        );
+    }
 
     // let now = std::time::Instant::now();
     // serde_json::from_str::<Root>(&string).unwrap();
     // println!("Serde json strongly typed parse: {:?}", now.elapsed());
 }
+
+fn best_of(mut parse: impl FnMut()) -> Duration {
+    (0..ITERATIONS)
+        .map(|_| {
+            let now = Instant::now();
+            parse();
+            now.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn megabytes(string: &str) -> f64 {
+    string.len() as f64 / 1_000_000.0
+}
+
+fn numbers() -> String {
+    let numbers: Vec<_> = (0..200_000)
+        .map(|i| format!("[{i}, -{i}.{i}, {i}e-{}]", i % 300))
+        .collect();
+    format!("[{}]", numbers.join(","))
+}
+
+fn strings() -> String {
+    let strings: Vec<_> = (0..100_000)
+        .map(|i| format!(r#""Rarity #{i} says \"darling\" to {i} ponies, unescaped mostly""#))
+        .collect();
+    format!("[{}]", strings.join(","))
+}
+
+fn pretty_objects() -> String {
+    let objects: Vec<_> = (0..50_000)
+        .map(|i| {
+            format!(
+                "    {{\n        \"id\": {i},\n        \"name\": \"mare {i}\",\n        \"tags\": [\"pony\", \"safe\"],\n        \"score\": {}.5\n    }}",
+                i % 97
+            )
+        })
+        .collect();
+    format!("[\n{}\n]\n", objects.join(",\n"))
+}
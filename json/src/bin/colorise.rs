@@ -0,0 +1,27 @@
+//! Prints the JSON read from stdin with its tokens highlighted.
+
+use json::{Lexer, TokenKind};
+use nu_ansi_term::{Color, Style};
+use std::io::{self, Write};
+
+fn main() -> io::Result<()> {
+    let stdin = io::read_to_string(io::stdin())?;
+    let mut stdout = io::stdout().lock();
+
+    for token in Lexer::new(&stdin) {
+        let style = match token.kind {
+            TokenKind::String(_) => Style::new().fg(Color::Blue).bold(),
+            TokenKind::Number(_) => Style::new().bold(),
+            TokenKind::True => Style::new().fg(Color::Green),
+            TokenKind::False => Style::new().fg(Color::Red),
+            TokenKind::Null => Style::new().fg(Color::LightGray).italic(),
+            TokenKind::Invalid(_) => Style::new().fg(Color::Magenta).dimmed(),
+            TokenKind::Whitespace => Style::new(),
+            TokenKind::Comment => Style::new().fg(Color::DarkGray),
+            _ => Style::new().fg(Color::White).bold(),
+        };
+        let text = &stdin[token.offset..token.offset + token.len];
+        write!(stdout, "{}", style.paint(text))?;
+    }
+    stdout.flush()
+}
@@ -0,0 +1,442 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::hash::BuildHasher;
+
+use crate::value::Kind;
+use crate::{Map, Number, Value};
+
+impl From<()> for Value<'_> {
+    fn from((): ()) -> Self {
+        Value::Null
+    }
+}
+
+impl From<bool> for Value<'_> {
+    fn from(bool: bool) -> Self {
+        Value::Bool(bool)
+    }
+}
+
+impl From<Number> for Value<'_> {
+    fn from(number: Number) -> Self {
+        Value::Number(number)
+    }
+}
+
+macro_rules! from_integers {
+    ($($into:ty: $($ty:ty)*;)*) => {
+        $($(
+            impl From<$ty> for Value<'_> {
+                fn from(num: $ty) -> Self {
+                    Value::Number(Number::from(num as $into))
+                }
+            }
+        )*)*
+    };
+}
+
+from_integers! {
+    i64: i8 i16 i32 i64 isize;
+    u64: u8 u16 u32 u64 usize;
+}
+
+/// NaN and infinities have no JSON form, they become `Null`.
+impl From<f64> for Value<'_> {
+    fn from(float: f64) -> Self {
+        Number::from_f64(float).map_or(Value::Null, Value::Number)
+    }
+}
+
+/// NaN and infinities have no JSON form, they become `Null`.
+impl From<f32> for Value<'_> {
+    fn from(float: f32) -> Self {
+        Value::from(f64::from(float))
+    }
+}
+
+impl<'a> From<&'a str> for Value<'a> {
+    fn from(string: &'a str) -> Self {
+        Value::String(Cow::Borrowed(string))
+    }
+}
+
+impl From<String> for Value<'_> {
+    fn from(string: String) -> Self {
+        Value::String(Cow::Owned(string))
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for Value<'a> {
+    fn from(string: Cow<'a, str>) -> Self {
+        Value::String(string)
+    }
+}
+
+impl From<char> for Value<'_> {
+    fn from(char: char) -> Self {
+        Value::String(Cow::Owned(char.to_string()))
+    }
+}
+
+impl<'a, T: Into<Value<'a>>> From<Vec<T>> for Value<'a> {
+    fn from(values: Vec<T>) -> Self {
+        Value::Array(values.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<'a, T: Into<Value<'a>>, const N: usize> From<[T; N]> for Value<'a> {
+    fn from(values: [T; N]) -> Self {
+        Value::Array(values.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<'a> From<Map<'a>> for Value<'a> {
+    fn from(object: Map<'a>) -> Self {
+        Value::Object(object)
+    }
+}
+
+impl<'a, K: Into<Cow<'a, str>>, V: Into<Value<'a>>> From<BTreeMap<K, V>> for Value<'a> {
+    fn from(object: BTreeMap<K, V>) -> Self {
+        object.into_iter().collect()
+    }
+}
+
+/// The members are in the iteration order of the `HashMap`, which is arbitrary.
+impl<'a, K: Into<Cow<'a, str>>, V: Into<Value<'a>>, S> From<HashMap<K, V, S>> for Value<'a> {
+    fn from(object: HashMap<K, V, S>) -> Self {
+        object.into_iter().collect()
+    }
+}
+
+/// `None` becomes `Null`.
+impl<'a, T: Into<Value<'a>>> From<Option<T>> for Value<'a> {
+    fn from(option: Option<T>) -> Self {
+        option.map_or(Value::Null, Into::into)
+    }
+}
+
+/// Collects values into an array.
+impl<'a, T: Into<Value<'a>>> FromIterator<T> for Value<'a> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Value::Array(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Collects members into an object.
+impl<'a, K: Into<Cow<'a, str>>, V: Into<Value<'a>>> FromIterator<(K, V)> for Value<'a> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let object = iter
+            .into_iter()
+            .map(|(key, value)| (key, value.into()))
+            .collect();
+        Value::Object(object)
+    }
+}
+
+/// A [`Value`] couldn't be converted with `TryFrom` because it's
+/// of another kind, or a number doesn't fit in the type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FromValueError {
+    expected: &'static str,
+    found: String,
+    /// Where in the value the error is, like `[2].name`, empty at the root.
+    path: String,
+}
+
+impl FromValueError {
+    fn new(expected: &'static str, value: &Value<'_>) -> FromValueError {
+        let found = match value {
+            Value::Number(number) => format!("number {number}"),
+            _ => Kind(value).to_string(),
+        };
+        FromValueError {
+            expected,
+            found,
+            path: String::new(),
+        }
+    }
+
+    /// Prepends a step to the path, on the way out of a container.
+    fn within(mut self, step: fmt::Arguments<'_>) -> FromValueError {
+        self.path.insert_str(0, &step.to_string());
+        self
+    }
+}
+
+impl fmt::Display for FromValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Expected {}, found {}", self.expected, self.found)?;
+        if !self.path.is_empty() {
+            write!(f, " at {}", self.path)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for FromValueError {}
+
+impl TryFrom<Value<'_>> for bool {
+    type Error = FromValueError;
+
+    fn try_from(value: Value<'_>) -> Result<Self, Self::Error> {
+        value
+            .as_bool()
+            .ok_or_else(|| FromValueError::new("a boolean", &value))
+    }
+}
+
+impl TryFrom<Value<'_>> for Number {
+    type Error = FromValueError;
+
+    fn try_from(value: Value<'_>) -> Result<Self, Self::Error> {
+        match value {
+            Value::Number(number) => Ok(number),
+            _ => Err(FromValueError::new("a number", &value)),
+        }
+    }
+}
+
+macro_rules! try_from_integers {
+    ($($as:ident: $($ty:ident)*;)*) => {
+        $($(
+            impl TryFrom<Value<'_>> for $ty {
+                type Error = FromValueError;
+
+                fn try_from(value: Value<'_>) -> Result<Self, Self::Error> {
+                    value
+                        .$as()
+                        .and_then(|num| $ty::try_from(num).ok())
+                        .ok_or_else(|| FromValueError::new(stringify!($ty), &value))
+                }
+            }
+        )*)*
+    };
+}
+
+try_from_integers! {
+    as_i64: i8 i16 i32 i64 isize;
+    as_u64: u8 u16 u32 u64 usize;
+}
+
+/// Any number, integers above 2^53 lose precision.
+impl TryFrom<Value<'_>> for f64 {
+    type Error = FromValueError;
+
+    fn try_from(value: Value<'_>) -> Result<Self, Self::Error> {
+        value
+            .as_f64()
+            .ok_or_else(|| FromValueError::new("a number", &value))
+    }
+}
+
+impl<'a> TryFrom<Value<'a>> for Cow<'a, str> {
+    type Error = FromValueError;
+
+    fn try_from(value: Value<'a>) -> Result<Self, Self::Error> {
+        match value {
+            Value::String(string) => Ok(string),
+            _ => Err(FromValueError::new("a string", &value)),
+        }
+    }
+}
+
+impl TryFrom<Value<'_>> for String {
+    type Error = FromValueError;
+
+    fn try_from(value: Value<'_>) -> Result<Self, Self::Error> {
+        Cow::try_from(value).map(Cow::into_owned)
+    }
+}
+
+impl<'a, T> TryFrom<Value<'a>> for Vec<T>
+where
+    T: TryFrom<Value<'a>, Error = FromValueError>,
+{
+    type Error = FromValueError;
+
+    fn try_from(value: Value<'a>) -> Result<Self, Self::Error> {
+        let Value::Array(values) = value else {
+            return Err(FromValueError::new("an array", &value));
+        };
+        values
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| {
+                T::try_from(value).map_err(|error| error.within(format_args!("[{index}]")))
+            })
+            .collect()
+    }
+}
+
+impl<'a> TryFrom<Value<'a>> for Map<'a> {
+    type Error = FromValueError;
+
+    fn try_from(value: Value<'a>) -> Result<Self, Self::Error> {
+        match value {
+            Value::Object(object) => Ok(object),
+            _ => Err(FromValueError::new("an object", &value)),
+        }
+    }
+}
+
+/// Converts the members of an object.
+fn try_from_members<'a, T, C>(value: Value<'a>) -> Result<C, FromValueError>
+where
+    T: TryFrom<Value<'a>, Error = FromValueError>,
+    C: FromIterator<(String, T)>,
+{
+    let object = Map::try_from(value)?;
+    object
+        .into_iter()
+        .map(|(key, value)| match T::try_from(value) {
+            Ok(value) => Ok((key.into_owned(), value)),
+            Err(error) => Err(error.within(format_args!(".{key}"))),
+        })
+        .collect()
+}
+
+impl<'a, T> TryFrom<Value<'a>> for BTreeMap<String, T>
+where
+    T: TryFrom<Value<'a>, Error = FromValueError>,
+{
+    type Error = FromValueError;
+
+    fn try_from(value: Value<'a>) -> Result<Self, Self::Error> {
+        try_from_members(value)
+    }
+}
+
+impl<'a, T, S> TryFrom<Value<'a>> for HashMap<String, T, S>
+where
+    T: TryFrom<Value<'a>, Error = FromValueError>,
+    S: BuildHasher + Default,
+{
+    type Error = FromValueError;
+
+    fn try_from(value: Value<'a>) -> Result<Self, Self::Error> {
+        try_from_members(value)
+    }
+}
+
+/// `Null` becomes `None`.
+impl<'a, T> TryFrom<Value<'a>> for Option<T>
+where
+    T: TryFrom<Value<'a>, Error = FromValueError>,
+{
+    type Error = FromValueError;
+
+    fn try_from(value: Value<'a>) -> Result<Self, Self::Error> {
+        match value {
+            Value::Null => Ok(None),
+            _ => T::try_from(value).map(Some),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use std::collections::{BTreeMap, HashMap};
+
+    use crate::{json, Map, Value};
+
+    #[test]
+    fn into_value() {
+        assert_eq!(Value::from(()), Value::Null);
+        assert_eq!(Value::from(true), true);
+        assert_eq!(Value::from(-3i8), -3);
+        assert_eq!(Value::from(u64::MAX), u64::MAX);
+        assert_eq!(Value::from(7usize).to_string(), "7");
+        assert_eq!(Value::from(2.5f32), 2.5);
+        assert_eq!(Value::from(f64::NAN), Value::Null);
+        assert_eq!(Value::from('🦄'), "🦄");
+        assert!(matches!(
+            Value::from("pony"),
+            Value::String(Cow::Borrowed("pony"))
+        ));
+        assert!(matches!(
+            Value::from(String::from("pony")),
+            Value::String(Cow::Owned(_))
+        ));
+        assert_eq!(Value::from(None::<i32>), Value::Null);
+        assert_eq!(Value::from(Some("mare")), "mare");
+        assert_eq!(Value::from(vec![1, 2]).to_string(), "[1,2]");
+        assert_eq!(Value::from([Some(1), None]).to_string(), "[1,null]");
+
+        let map = BTreeMap::from([("b", 2), ("a", 1)]);
+        assert_eq!(Value::from(map).to_string(), r#"{"a":1,"b":2}"#);
+        let map = HashMap::from([(String::from("a"), vec![true])]);
+        assert_eq!(Value::from(map).to_string(), r#"{"a":[true]}"#);
+
+        let value: Value = (1..4).collect();
+        assert_eq!(value.to_string(), "[1,2,3]");
+        let value: Value = [("x", 1), ("y", 2)].into_iter().collect();
+        assert_eq!(value.to_string(), r#"{"x":1,"y":2}"#);
+    }
+
+    #[test]
+    fn from_value() {
+        assert_eq!(bool::try_from(json!(true)), Ok(true));
+        assert_eq!(u8::try_from(json!(255)), Ok(255));
+        assert_eq!(i64::try_from(json!(-1)), Ok(-1));
+        assert_eq!(f64::try_from(json!(3)), Ok(3.0));
+        assert_eq!(String::try_from(json!("pony")).as_deref(), Ok("pony"));
+        assert_eq!(Option::<u32>::try_from(json!(null)), Ok(None));
+        assert_eq!(Option::<u32>::try_from(json!(5)), Ok(Some(5)));
+        assert_eq!(Vec::<i32>::try_from(json!([1, 2, 3])), Ok(vec![1, 2, 3]));
+        assert_eq!(
+            BTreeMap::<String, Vec<bool>>::try_from(json!({"a": [true], "b": []})),
+            Ok(BTreeMap::from([
+                ("a".to_owned(), vec![true]),
+                ("b".to_owned(), vec![])
+            ]))
+        );
+        let map = HashMap::<String, String>::try_from(json!({"name": "Rarity"})).unwrap();
+        assert_eq!(map["name"], "Rarity");
+        assert_eq!(Map::try_from(json!({})).map(|map| map.len()), Ok(0));
+
+        let error = u8::try_from(json!(256)).unwrap_err();
+        assert_eq!(error.to_string(), "Expected u8, found number 256");
+        let error = u64::try_from(json!(-1)).unwrap_err();
+        assert_eq!(error.to_string(), "Expected u64, found number -1");
+        let error = i32::try_from(json!(1.5)).unwrap_err();
+        assert_eq!(error.to_string(), "Expected i32, found number 1.5");
+        let error = bool::try_from(json!("true")).unwrap_err();
+        assert_eq!(error.to_string(), "Expected a boolean, found a string");
+        let error = String::try_from(json!(null)).unwrap_err();
+        assert_eq!(error.to_string(), "Expected a string, found null");
+        let error =
+            Vec::<BTreeMap<String, u8>>::try_from(json!([{"a": 1}, {"b": []}])).unwrap_err();
+        assert_eq!(error.to_string(), "Expected u8, found an array at [1].b");
+    }
+
+    #[test]
+    fn macro_syntax() {
+        let name = "Rarity";
+        let friends = vec!["Twilight", "Applejack"];
+        let key = String::from("dynamic");
+        let value = json!({
+            "name": name,
+            "age": 18 + 1,
+            "height": -1.62,
+            "friends": friends,
+            "pet": {"name": "Opal", "alive": true, "owner": null},
+            "nested": [[], {}, [null, false, [1, {"deep": name.len()}]]],
+            (key): Some("value"),
+            ("literal".to_owned()): [name.to_uppercase(),],
+        });
+        assert_eq!(
+            value.to_string(),
+            r#"{"name":"Rarity","age":19,"height":-1.62,"friends":["Twilight","Applejack"],"pet":{"name":"Opal","alive":true,"owner":null},"nested":[[],{},[null,false,[1,{"deep":6}]]],"dynamic":"value","literal":["RARITY"]}"#
+        );
+
+        assert_eq!(json!(null), Value::Null);
+        assert_eq!(json!([true, false, null,]).to_string(), "[true,false,null]");
+        assert_eq!(json!(1 + 1), 2);
+        assert_eq!(json!(name.is_empty() || name.len() > 10), false);
+        assert_eq!(json!([-1, 2 * 3]).to_string(), "[-1,6]");
+    }
+}
@@ -0,0 +1,962 @@
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::iter::Peekable;
+use std::ops::Range;
+
+use serde::de::{self, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+
+use crate::lexer::{self, Cursor, RawToken, TokenKind};
+#[cfg(feature = "arbitrary_precision")]
+use crate::number;
+use crate::parser::{json_path, Segment};
+use crate::{DuplicateKeys, Error, Map, Number, ParseOptions, Value};
+
+/// Deserializes a `T` from JSON text.
+///
+/// Strings without escapes can be borrowed from `input`,
+/// with `&'de str` or `Cow<'de, str>` fields.
+///
+/// ```
+/// #[derive(Debug, serde::Deserialize)]
+/// struct Pony<'a> {
+///     name: &'a str,
+///     age: u8,
+/// }
+///
+/// let pony: Pony = json::from_str(r#"{"name": "Rarity", "age": 19}"#).unwrap();
+/// assert_eq!((pony.name, pony.age), ("Rarity", 19));
+///
+/// let error = json::from_str::<Pony>(r#"{"name": "Rarity", "age": -1}"#).unwrap_err();
+/// assert_eq!(
+///     error.to_string(),
+///     "invalid value: integer `-1`, expected u8 at line 1, column 28, in $.age"
+/// );
+/// ```
+pub fn from_str<'de, T: de::Deserialize<'de>>(input: &'de str) -> Result<T, Error> {
+    from_str_with(input, &ParseOptions::default())
+}
+
+/// Deserializes a `T` from JSON text with the given settings.
+///
+/// Every member of an object is handed to `T`, so [`sort_keys`](ParseOptions::sort_keys)
+/// doesn't apply, nor do the [`duplicate_keys`](ParseOptions::duplicate_keys)
+/// policies which keep one of the values: derived structs reject duplicate
+/// fields, maps keep the last value. [`DuplicateKeys::Error`] does apply,
+/// a repeated key fails as it does with [`parse_with`](crate::parse_with).
+pub fn from_str_with<'de, T: de::Deserialize<'de>>(
+    input: &'de str,
+    options: &ParseOptions,
+) -> Result<T, Error> {
+    let mut deserializer = Deserializer::new(input, options);
+    if input.len() > options.max_document_len {
+        return Err(deserializer.syntax_error());
+    }
+    let value = T::deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(value)
+}
+
+/// Drives `Deserialize` implementations straight from the tokens,
+/// without building a [`Value`] first.
+struct Deserializer<'de> {
+    input: &'de str,
+    options: ParseOptions,
+    tokens: Peekable<Cursor<'de>>,
+    /// Span of the last token taken, where data errors point.
+    span: Range<usize>,
+    /// Keys and indices from the root to the value being deserialized.
+    path: Vec<PathSegment<'de>>,
+    /// Arrays and objects entered and not left yet.
+    depth: usize,
+}
+
+enum PathSegment<'de> {
+    Key(Cow<'de, str>),
+    Index(usize),
+}
+
+impl<'de> Deserializer<'de> {
+    fn new(input: &'de str, options: &ParseOptions) -> Deserializer<'de> {
+        Deserializer {
+            input,
+            options: *options,
+            tokens: lexer::tokenize(input, options).peekable(),
+            span: 0..0,
+            path: Vec::new(),
+            depth: 0,
+        }
+    }
+
+    fn peek_token(&mut self) -> Option<&TokenKind<'de>> {
+        self.tokens.peek().map(|token| &token.kind)
+    }
+
+    fn next_token(&mut self) -> Option<TokenKind<'de>> {
+        let RawToken { kind, span } = self.tokens.next()?;
+        self.span = span;
+        Some(kind)
+    }
+
+    /// Takes a string token. One over `max_string_len` comes
+    /// as [`TokenizeError::StringTooLong`](crate::TokenizeError::StringTooLong).
+    fn next_string(&mut self) -> Result<Cow<'de, str>, Error> {
+        match self.next_token() {
+            Some(TokenKind::String(string)) => Ok(string),
+            _ => Err(self.syntax_error()),
+        }
+    }
+
+    /// Reports malformed text. The deserializer only knows that the text
+    /// went wrong, so the parser goes over it again to tell what and where,
+    /// which gives exactly the error [`parse_with`](crate::parse_with) gives.
+    #[cold]
+    fn syntax_error(&self) -> Error {
+        self.syntax_error_or(de::Error::custom("unexpected token"))
+    }
+
+    /// Like [`syntax_error`](Self::syntax_error), for a token which is
+    /// valid JSON but not what the type wants: unless the parser finds
+    /// something wrong with the text, the error is `data_error`.
+    #[cold]
+    fn syntax_error_or(&self, data_error: Error) -> Error {
+        match crate::parse_with(self.input, &self.options) {
+            Err(error) => Error::syntax(error),
+            Ok(_) => self.fix_position(data_error),
+        }
+    }
+
+    /// Gives a data error the position of the last token and the current path.
+    fn fix_position(&self, error: Error) -> Error {
+        let position = lexer::line_column(self.input, self.span.end);
+        error.at(position, || {
+            if self.path.is_empty() {
+                return None;
+            }
+            Some(json_path(self.path.iter().map(|segment| match segment {
+                PathSegment::Key(key) => Segment::Key(key),
+                PathSegment::Index(index) => Segment::Index(*index),
+            })))
+        })
+    }
+
+    /// Checks that nothing but whitespace follows the value.
+    fn end(&mut self) -> Result<(), Error> {
+        match self.peek_token() {
+            None => Ok(()),
+            Some(_) => Err(self.syntax_error()),
+        }
+    }
+
+    fn enter(&mut self) -> Result<(), Error> {
+        if self.depth >= self.options.max_depth {
+            return Err(self.syntax_error());
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Hands a number which isn't a 64-bit integer to `visitor` with its digits,
+    /// as a map with one member named [`number::TOKEN`]. Anything else goes
+    /// to `deserialize_any`.
+    #[cfg(feature = "arbitrary_precision")]
+    fn deserialize_digits<V: Visitor<'de>>(&mut self, visitor: V) -> Result<V::Value, Error> {
+        match self.peek_token() {
+            Some(TokenKind::Number(number)) if number.is_f64() => {
+                let digits = number.as_str().to_owned();
+                self.next_token();
+                let member = std::iter::once((number::TOKEN, digits));
+                visitor
+                    .visit_map(de::value::MapDeserializer::new(member))
+                    .map_err(|error| self.fix_position(error))
+            }
+            _ => de::Deserializer::deserialize_any(self, visitor),
+        }
+    }
+}
+
+fn visit_number<'de, V: Visitor<'de>>(number: Number, visitor: V) -> Result<V::Value, Error> {
+    if let Some(num) = number.as_u64() {
+        visitor.visit_u64(num)
+    } else if let Some(num) = number.as_i64() {
+        visitor.visit_i64(num)
+    } else {
+        visitor.visit_f64(number.as_f64())
+    }
+}
+
+fn visit_string<'de, V: Visitor<'de>>(
+    string: Cow<'de, str>,
+    visitor: V,
+) -> Result<V::Value, Error> {
+    match string {
+        Cow::Borrowed(string) => visitor.visit_borrowed_str(string),
+        Cow::Owned(string) => visitor.visit_string(string),
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let value = match self.peek_token() {
+            Some(TokenKind::String(_)) => {
+                let string = self.next_string()?;
+                visit_string(string, visitor)
+            }
+            Some(TokenKind::Number(_)) => {
+                let Some(TokenKind::Number(number)) = self.next_token() else {
+                    unreachable!();
+                };
+                visit_number(number, visitor)
+            }
+            Some(TokenKind::True) => {
+                self.next_token();
+                visitor.visit_bool(true)
+            }
+            Some(TokenKind::False) => {
+                self.next_token();
+                visitor.visit_bool(false)
+            }
+            Some(TokenKind::Null) => {
+                self.next_token();
+                visitor.visit_unit()
+            }
+            Some(TokenKind::OpenBracket) => {
+                self.enter()?;
+                self.next_token();
+                let mut access = SeqAccess::new(self);
+                let value = visitor.visit_seq(&mut access).and_then(|value| {
+                    // the visitor may stop before the end, like a tuple does
+                    match access.at_end()? {
+                        true => Ok(value),
+                        false => Err(de::Error::custom(
+                            "more elements in the array than expected",
+                        )),
+                    }
+                });
+                self.depth -= 1;
+                value
+            }
+            Some(TokenKind::OpenCurly) => {
+                self.enter()?;
+                self.next_token();
+                let mut access = MapAccess::new(self);
+                let value = visitor.visit_map(&mut access).and_then(|value| {
+                    // the visitor may stop before the end, like a tuple does
+                    match access.at_end()? {
+                        true => Ok(value),
+                        false => Err(de::Error::custom(
+                            "more members in the object than expected",
+                        )),
+                    }
+                });
+                self.depth -= 1;
+                value
+            }
+            _ => return Err(self.syntax_error()),
+        };
+        value.map_err(|error| self.fix_position(error))
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if let Some(TokenKind::Null) = self.peek_token() {
+            self.next_token();
+            return visitor
+                .visit_none()
+                .map_err(|error| self.fix_position(error));
+        }
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        #[cfg(feature = "arbitrary_precision")]
+        if _name == number::TOKEN {
+            return self.deserialize_digits(visitor);
+        }
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// A unit variant is a string, the others are objects with one member:
+    /// the name of the variant and its content.
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let value = match self.peek_token() {
+            Some(TokenKind::String(_)) => {
+                let variant = self.next_string()?;
+                visitor.visit_enum(variant.into_deserializer())
+            }
+            Some(TokenKind::OpenCurly) => {
+                self.enter()?;
+                self.next_token();
+                let value = visitor.visit_enum(&mut *self);
+                if value.is_ok() {
+                    self.path.pop();
+                    match self.next_token() {
+                        Some(TokenKind::ClosedCurly) => {}
+                        Some(TokenKind::Comma) => {
+                            let error = de::Error::custom("expected an object with one member");
+                            return Err(self.syntax_error_or(error));
+                        }
+                        _ => return Err(self.syntax_error()),
+                    }
+                }
+                self.depth -= 1;
+                value
+            }
+            _ => return self.deserialize_any(visitor),
+        };
+        value.map_err(|error| self.fix_position(error))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+struct SeqAccess<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    len: usize,
+    /// The closing bracket is taken.
+    done: bool,
+}
+
+impl<'a, 'de> SeqAccess<'a, 'de> {
+    fn new(de: &'a mut Deserializer<'de>) -> SeqAccess<'a, 'de> {
+        SeqAccess {
+            de,
+            len: 0,
+            done: false,
+        }
+    }
+}
+
+impl SeqAccess<'_, '_> {
+    /// Takes the comma before the next element, or the closing bracket.
+    /// Returns whether the array is over.
+    fn at_end(&mut self) -> Result<bool, Error> {
+        if self.done {
+            return Ok(true);
+        }
+        if self.len > 0 {
+            match self.de.next_token() {
+                Some(TokenKind::Comma) => {}
+                Some(TokenKind::ClosedBracket) => {
+                    self.done = true;
+                    return Ok(true);
+                }
+                _ => return Err(self.de.syntax_error()),
+            }
+        }
+        // an empty array, or `[1, 2,]`
+        if let Some(TokenKind::ClosedBracket) = self.de.peek_token() {
+            if self.len == 0 || self.de.options.trailing_commas {
+                self.de.next_token();
+                self.done = true;
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for &mut SeqAccess<'_, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        if self.at_end()? {
+            return Ok(None);
+        }
+        self.de.path.push(PathSegment::Index(self.len));
+        let value = seed.deserialize(&mut *self.de)?;
+        self.de.path.pop();
+        self.len += 1;
+        Ok(Some(value))
+    }
+}
+
+struct MapAccess<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    first: bool,
+    /// The closing curly is taken.
+    done: bool,
+    /// Keys seen so far, for [`DuplicateKeys::Error`].
+    keys: BTreeSet<Cow<'de, str>>,
+}
+
+impl<'a, 'de> MapAccess<'a, 'de> {
+    fn new(de: &'a mut Deserializer<'de>) -> MapAccess<'a, 'de> {
+        MapAccess {
+            de,
+            first: true,
+            done: false,
+            keys: BTreeSet::new(),
+        }
+    }
+}
+
+impl MapAccess<'_, '_> {
+    /// Takes the comma before the next member, or the closing curly.
+    /// Returns whether the object is over.
+    fn at_end(&mut self) -> Result<bool, Error> {
+        if self.done {
+            return Ok(true);
+        }
+        if !self.first {
+            match self.de.next_token() {
+                Some(TokenKind::Comma) => {}
+                Some(TokenKind::ClosedCurly) => {
+                    self.done = true;
+                    return Ok(true);
+                }
+                _ => return Err(self.de.syntax_error()),
+            }
+        }
+        // an empty object, or `{"a": 1,}`
+        if let Some(TokenKind::ClosedCurly) = self.de.peek_token() {
+            if self.first || self.de.options.trailing_commas {
+                self.de.next_token();
+                self.done = true;
+                return Ok(true);
+            }
+        }
+        self.first = false;
+        Ok(false)
+    }
+}
+
+impl<'de> de::MapAccess<'de> for &mut MapAccess<'_, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        if self.at_end()? {
+            return Ok(None);
+        }
+        let key = self.de.next_key()?;
+        if self.de.options.duplicate_keys == DuplicateKeys::Error && !self.keys.insert(key.clone())
+        {
+            return Err(self.de.syntax_error());
+        }
+        seed.deserialize(MapKey { key }).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let value = seed.deserialize(&mut *self.de)?;
+        self.de.path.pop();
+        Ok(value)
+    }
+}
+
+impl<'de> Deserializer<'de> {
+    /// Takes a key with its colon and steps into the member.
+    /// The key is handed back to deserialize it.
+    fn next_key(&mut self) -> Result<Cow<'de, str>, Error> {
+        let key = self.next_string()?;
+        let span = self.span.clone();
+        match self.next_token() {
+            Some(TokenKind::Colon) => {}
+            _ => return Err(self.syntax_error()),
+        }
+        // the errors of the key point at the key
+        self.span = span;
+        self.path.push(PathSegment::Key(key.clone()));
+        Ok(key)
+    }
+}
+
+/// An object with one member, for a variant with content.
+impl<'de> de::EnumAccess<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Error> {
+        if let Some(TokenKind::ClosedCurly) = self.peek_token() {
+            return Err(de::Error::invalid_length(0, &"an object with one member"));
+        }
+        let key = self.next_key()?;
+        let variant = seed.deserialize(MapKey { key })?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        de::Deserialize::deserialize(self)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
+
+/// Object key, which is always a string but may stand for a number,
+/// like the keys of a `HashMap<u32, T>`.
+struct MapKey<'de> {
+    key: Cow<'de, str>,
+}
+
+macro_rules! deserialize_parsed_key {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                match self.key.parse() {
+                    Ok(num) => visitor.$visit(num),
+                    Err(_) => Err(de::Error::invalid_value(de::Unexpected::Str(&self.key), &visitor)),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for MapKey<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visit_string(self.key, visitor)
+    }
+
+    deserialize_parsed_key! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(self.key.into_deserializer())
+    }
+
+    forward_to_deserialize_any! {
+        bool f32 f64 char str string bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+impl<'de> de::Deserialize<'de> for Value<'de> {
+    /// Strings are borrowed when the deserializer lends them,
+    /// as [`from_str`] does for strings without escapes.
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Value<'de>, D::Error> {
+        #[cfg(feature = "arbitrary_precision")]
+        return deserializer.deserialize_newtype_struct(number::TOKEN, ValueVisitor);
+        #[cfg(not(feature = "arbitrary_precision"))]
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value<'de>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("any JSON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<Value<'de>, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<Value<'de>, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<Value<'de>, D::Error> {
+        de::Deserialize::deserialize(deserializer)
+    }
+
+    /// Deserializers which know nothing of the digits of numbers.
+    fn visit_newtype_struct<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Value<'de>, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_bool<E>(self, bool: bool) -> Result<Value<'de>, E> {
+        Ok(Value::Bool(bool))
+    }
+
+    fn visit_i64<E>(self, num: i64) -> Result<Value<'de>, E> {
+        Ok(Value::from(num))
+    }
+
+    fn visit_u64<E>(self, num: u64) -> Result<Value<'de>, E> {
+        Ok(Value::from(num))
+    }
+
+    /// Non-finite floats become `Null`, as with `Value::from`.
+    fn visit_f64<E>(self, num: f64) -> Result<Value<'de>, E> {
+        Ok(Value::from(num))
+    }
+
+    fn visit_borrowed_str<E>(self, string: &'de str) -> Result<Value<'de>, E> {
+        Ok(Value::String(Cow::Borrowed(string)))
+    }
+
+    fn visit_str<E>(self, string: &str) -> Result<Value<'de>, E> {
+        Ok(Value::String(Cow::Owned(string.to_owned())))
+    }
+
+    fn visit_string<E>(self, string: String) -> Result<Value<'de>, E> {
+        Ok(Value::String(Cow::Owned(string)))
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Value<'de>, A::Error> {
+        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(Value::Array(values))
+    }
+
+    /// A repeated key keeps the last value.
+    fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Value<'de>, A::Error> {
+        let mut object = Map::new();
+        while let Some(key) = map.next_key_seed(KeySeed)? {
+            #[cfg(feature = "arbitrary_precision")]
+            if object.is_empty() && key == number::TOKEN {
+                let digits: String = map.next_value()?;
+                return match number::from_digits(&digits) {
+                    Some(number) => Ok(Value::Number(number)),
+                    None => Err(de::Error::invalid_value(
+                        de::Unexpected::Str(&digits),
+                        &"the digits of a JSON number",
+                    )),
+                };
+            }
+            object.insert(key, map.next_value()?);
+        }
+        Ok(Value::Object(object))
+    }
+}
+
+/// Deserializes an object key, borrowed when possible,
+/// which `Cow<str>` itself never is.
+struct KeySeed;
+
+impl<'de> DeserializeSeed<'de> for KeySeed {
+    type Value = Cow<'de, str>;
+
+    fn deserialize<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Cow<'de, str>, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de> Visitor<'de> for KeySeed {
+    type Value = Cow<'de, str>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a string")
+    }
+
+    fn visit_borrowed_str<E>(self, string: &'de str) -> Result<Cow<'de, str>, E> {
+        Ok(Cow::Borrowed(string))
+    }
+
+    fn visit_str<E>(self, string: &str) -> Result<Cow<'de, str>, E> {
+        Ok(Cow::Owned(string.to_owned()))
+    }
+
+    fn visit_string<E>(self, string: String) -> Result<Cow<'de, str>, E> {
+        Ok(Cow::Owned(string))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use std::collections::{BTreeMap, HashMap};
+
+    use serde::Deserialize;
+
+    use crate::{
+        from_str, from_str_with, json, DuplicateKeys, ParseOptions, ParsingErrorKind, Value,
+    };
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Response {
+        images: Vec<Image>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Image {
+        tags: Vec<String>,
+        size: i32,
+        view_url: String,
+        animated: bool,
+        score: i32,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum Shape {
+        Point,
+        Circle(f64),
+        Line(i32, i32),
+        Rect { width: u32, height: u32 },
+    }
+
+    #[test]
+    fn derived() {
+        let text = r#"{"total": 1, "images": [
+            {"tags": ["safe", "pony"], "size": 1024, "view_url": "https:\/\/derpicdn.net/1.png",
+             "animated": false, "score": -3, "faves": {"ignored": [1, {}]}}
+        ]}"#;
+        let response: Response = from_str(text).unwrap();
+        assert_eq!(
+            response,
+            Response {
+                images: vec![Image {
+                    tags: vec!["safe".to_owned(), "pony".to_owned()],
+                    size: 1024,
+                    view_url: "https://derpicdn.net/1.png".to_owned(),
+                    animated: false,
+                    score: -3,
+                }]
+            }
+        );
+
+        let shapes: Vec<Shape> = from_str(
+            r#"["Point", {"Circle": 0.5}, {"Line": [1, -1]}, {"Rect": {"width": 2, "height": 3}}]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            shapes,
+            [
+                Shape::Point,
+                Shape::Circle(0.5),
+                Shape::Line(1, -1),
+                Shape::Rect {
+                    width: 2,
+                    height: 3
+                }
+            ]
+        );
+
+        let map: BTreeMap<u32, Option<bool>> = from_str(r#"{"2": null, "1": true}"#).unwrap();
+        assert_eq!(map, BTreeMap::from([(1, Some(true)), (2, None)]));
+        let tuple: (u8, char, ()) = from_str(r#"[1, "x", null]"#).unwrap();
+        assert_eq!(tuple, (1, 'x', ()));
+    }
+
+    #[test]
+    fn borrowed() {
+        #[derive(Deserialize)]
+        struct Pet<'a> {
+            name: &'a str,
+            #[serde(borrow)]
+            owner: Cow<'a, str>,
+            #[serde(borrow)]
+            home: Cow<'a, str>,
+        }
+        let text = r#"{"name": "Opal", "owner": "Rarity", "home": "Carousel\nBoutique"}"#;
+        let pet: Pet = from_str(text).unwrap();
+        assert_eq!(pet.name, "Opal");
+        assert!(matches!(pet.owner, Cow::Borrowed("Rarity")));
+        assert!(matches!(pet.home, Cow::Owned(_)));
+        let names: HashMap<&str, u8> = from_str(r#"{"Opal": 1, "Spike": 2}"#).unwrap();
+        assert_eq!(names["Spike"], 2);
+
+        let text = r#"{"name": "Rarity", "friends": ["Spike", null], "age": 19, "magic": 9.5}"#;
+        let value: Value = from_str(text).unwrap();
+        assert_eq!(value, crate::parse(text).unwrap());
+        assert!(matches!(
+            &value["friends"][0],
+            Value::String(Cow::Borrowed(_))
+        ));
+        let keys: Vec<_> = value.as_object().unwrap().keys().collect();
+        assert!(matches!(keys[0], Cow::Borrowed("name")));
+    }
+
+    #[test]
+    fn data_errors() {
+        let text = "{\"images\": [\n  {\"tags\": [], \"size\": \"big\"}\n]}";
+        let error = from_str::<Response>(text).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            r#"invalid type: string "big", expected i32 at line 2, column 28, in $.images[0].size"#
+        );
+        assert_eq!((error.line(), error.column()), (Some(2), Some(28)));
+        assert_eq!(error.path().as_deref(), Some("$.images[0].size"));
+        assert!(error.parsing_error().is_none());
+
+        let error = from_str::<Response>(r#"{"images": [{"tags": ["a", 1]}]}"#).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid type: integer `1`, expected a string at line 1, column 28, in $.images[0].tags[1]"
+        );
+
+        let error = from_str::<Response>(r#"{"images": [{"tags": []}]}"#).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "missing field `size` at line 1, column 24, in $.images[0]"
+        );
+
+        let error = from_str::<Shape>(r#"{"Square": 1}"#).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "unknown variant `Square`, expected one of `Point`, `Circle`, `Line`, `Rect` at line 1, column 9, in $.Square"
+        );
+
+        // valid JSON, but not one member
+        let error = from_str::<Shape>("{}").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid length 0, expected an object with one member at line 1, column 1"
+        );
+        assert!(error.parsing_error().is_none());
+        let error = from_str::<Shape>(r#"{"Circle": 0.5, "x": 1}"#).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "expected an object with one member at line 1, column 15"
+        );
+        assert!(error.parsing_error().is_none());
+        // malformed text is still a syntax error
+        let error = from_str::<Shape>(r#"{"Circle": 0.5, ]"#).unwrap_err();
+        assert!(error.parsing_error().is_some());
+
+        let error = from_str::<Vec<u8>>("[1, 2, 256]").unwrap_err();
+        assert_eq!(error.path().as_deref(), Some("$[2]"));
+        let error = from_str::<(u8,)>("[1, 2]").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "more elements in the array than expected at line 1, column 3"
+        );
+        let error = from_str::<bool>("null").unwrap_err();
+        assert_eq!(error.path().as_deref(), Some("$"));
+    }
+
+    #[test]
+    fn syntax_errors_match_the_parser() {
+        let texts = [
+            "",
+            "[1, 2,]",
+            r#"{"images": [{"tags": ["a" "b"]}]}"#,
+            r#"{"images": [{"tags": ["a"], "size": tru}]}"#,
+            r#"{"images": [], }"#,
+            r#"{"images" []}"#,
+            r#"{"images": []} []"#,
+            "[[[[1]]]]",
+        ];
+        let options = ParseOptions::new().max_depth(3);
+        for text in texts {
+            let error = from_str_with::<Value>(text, &options).unwrap_err();
+            let expected = crate::parse_with(text, &options).unwrap_err();
+            assert_eq!(error.to_string(), expected.to_string(), "{text}");
+            assert_eq!(error.line(), Some(expected.line()));
+            assert_eq!(error.path(), Some(expected.path()));
+        }
+
+        let error = from_str::<Response>(r#"{"images": [{"tags": ["a" "b"]}]}"#).unwrap_err();
+        let parsing_error = error.parsing_error().unwrap();
+        assert_eq!(
+            parsing_error.kind(),
+            ParsingErrorKind::ExpectedCommaOrClosedBracket
+        );
+        assert_eq!(parsing_error.path(), "$.images[0].tags");
+    }
+
+    #[test]
+    fn options() {
+        let options = ParseOptions::new()
+            .allow_comments(true)
+            .allow_trailing_commas(true);
+        let value: Value = from_str_with("[1, /* two */ {\"a\": 2,},]", &options).unwrap();
+        assert_eq!(value, json!([1, {"a": 2}]));
+
+        let options = ParseOptions::new().duplicate_keys(DuplicateKeys::Error);
+        let text = r#"{"a": 1, "b": {"a": 2}, "a": 3}"#;
+        let error = from_str_with::<Value>(text, &options).unwrap_err();
+        let expected = crate::parse_with(text, &options).unwrap_err();
+        assert_eq!(error.to_string(), expected.to_string());
+        assert_eq!(
+            error.parsing_error().map(|error| error.kind()),
+            Some(ParsingErrorKind::DuplicateKey)
+        );
+        let error =
+            from_str_with::<HashMap<String, u8>>(r#"{"a": 1, "a": 3}"#, &options).unwrap_err();
+        assert!(error.parsing_error().is_some());
+        let value: Value = from_str_with(r#"{"a": {"a": 1}, "b": [{"a": 2}]}"#, &options).unwrap();
+        assert_eq!(value, json!({"a": {"a": 1}, "b": [{"a": 2}]}));
+
+        let options = ParseOptions::new().max_string_len(3);
+        let error = from_str_with::<Vec<String>>(r#"["abc", "abcd"]"#, &options).unwrap_err();
+        assert_eq!(
+            error.parsing_error().map(|error| error.kind()),
+            Some(ParsingErrorKind::StringTooLong)
+        );
+    }
+
+    #[cfg(feature = "arbitrary_precision")]
+    #[test]
+    fn exact_numbers() {
+        let text =
+            "[3.14159265358979323846264338327950288,123456789012345678901234567890,-0,1E400,7]";
+        let value: Value = from_str(text).unwrap();
+        assert_eq!(value.to_string(), text);
+        assert_eq!(crate::to_string(&value).unwrap(), text);
+        assert_eq!(crate::to_value(&value).unwrap().to_string(), text);
+        let nested: Value = from_str(r#"{"price": {"amount": 19.990}}"#).unwrap();
+        assert_eq!(nested.to_string(), r#"{"price":{"amount":19.990}}"#);
+
+        // other types still get floats
+        let floats: Vec<f64> = from_str("[0.1, 1e2]").unwrap();
+        assert_eq!(floats, [0.1, 100.0]);
+    }
+}
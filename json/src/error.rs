@@ -0,0 +1,162 @@
+use std::fmt;
+use std::io;
+
+use crate::ParsingError;
+
+/// Why [`from_str`](crate::from_str), [`from_reader`](crate::from_reader)
+/// or one of the serializing functions failed.
+///
+/// Malformed text gives the same [`ParsingError`] as [`parse`](crate::parse).
+/// Well-formed text which doesn't fit the Rust type is reported
+/// at the value which didn't fit, with its line, column and path.
+#[derive(Debug)]
+pub struct Error {
+    // Boxed to keep `Result<T, Error>` small.
+    inner: Box<ErrorInner>,
+}
+
+#[derive(Debug)]
+enum ErrorInner {
+    Syntax(ParsingError),
+    /// From `Deserialize` and `Serialize` implementations.
+    Data {
+        message: String,
+        /// Line and column of the last character of the value, once known.
+        position: Option<(usize, usize)>,
+        /// `None` at the root.
+        path: Option<String>,
+    },
+    Io(io::Error),
+}
+
+impl Error {
+    pub(crate) fn syntax(error: ParsingError) -> Error {
+        Error {
+            inner: Box::new(ErrorInner::Syntax(error)),
+        }
+    }
+
+    pub(crate) fn data(message: String) -> Error {
+        Error {
+            inner: Box::new(ErrorInner::Data {
+                message,
+                position: None,
+                path: None,
+            }),
+        }
+    }
+
+    pub(crate) fn io(error: io::Error) -> Error {
+        Error {
+            inner: Box::new(ErrorInner::Io(error)),
+        }
+    }
+
+    /// Places a data error which doesn't know where it happened yet.
+    pub(crate) fn at(
+        mut self,
+        position: (usize, usize),
+        path: impl FnOnce() -> Option<String>,
+    ) -> Error {
+        if let ErrorInner::Data {
+            position: unknown @ None,
+            path: path_slot,
+            ..
+        } = &mut *self.inner
+        {
+            *unknown = Some(position);
+            *path_slot = path();
+        }
+        self
+    }
+
+    /// The error of the parser, if the text isn't valid JSON.
+    pub fn parsing_error(&self) -> Option<&ParsingError> {
+        match &*self.inner {
+            ErrorInner::Syntax(error) => Some(error),
+            ErrorInner::Data { .. } | ErrorInner::Io(_) => None,
+        }
+    }
+
+    /// Whether reading or writing the text failed.
+    /// Reading text which isn't UTF-8 is an I/O error as well.
+    pub fn is_io(&self) -> bool {
+        matches!(*self.inner, ErrorInner::Io(_))
+    }
+
+    /// Line of the error, counted like [`ParsingError::line`].
+    /// `None` when serializing or writing.
+    pub fn line(&self) -> Option<usize> {
+        self.position().map(|(line, _)| line)
+    }
+
+    /// Column of the error, counted like [`ParsingError::column`].
+    /// `None` when serializing or writing.
+    pub fn column(&self) -> Option<usize> {
+        self.position().map(|(_, column)| column)
+    }
+
+    fn position(&self) -> Option<(usize, usize)> {
+        match &*self.inner {
+            ErrorInner::Syntax(error) => Some((error.line(), error.column())),
+            ErrorInner::Data { position, .. } => *position,
+            ErrorInner::Io(_) => None,
+        }
+    }
+
+    /// Location of the error as a JSONPath, like `$.images[3].size`.
+    /// `None` when serializing or writing.
+    pub fn path(&self) -> Option<String> {
+        match &*self.inner {
+            ErrorInner::Syntax(error) => Some(error.path()),
+            ErrorInner::Data { position: None, .. } | ErrorInner::Io(_) => None,
+            ErrorInner::Data { path, .. } => Some(path.clone().unwrap_or_else(|| "$".to_owned())),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &*self.inner {
+            ErrorInner::Syntax(error) => Some(error),
+            ErrorInner::Data { .. } => None,
+            ErrorInner::Io(error) => Some(error),
+        }
+    }
+}
+
+/// Reads like a [`ParsingError`]: what went wrong, then where.
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &*self.inner {
+            ErrorInner::Syntax(error) => fmt::Display::fmt(error, f),
+            ErrorInner::Data {
+                message,
+                position,
+                path,
+            } => {
+                f.write_str(message)?;
+                if let Some((line, column)) = position {
+                    write!(f, " at line {line}, column {column}")?;
+                }
+                if let Some(path) = path {
+                    write!(f, ", in {path}")?;
+                }
+                Ok(())
+            }
+            ErrorInner::Io(error) => write!(f, "I/O error: {error}"),
+        }
+    }
+}
+
+impl serde::de::Error for Error {
+    fn custom<T: fmt::Display>(message: T) -> Error {
+        Error::data(message.to_string())
+    }
+}
+
+impl serde::ser::Error for Error {
+    fn custom<T: fmt::Display>(message: T) -> Error {
+        Error::data(message.to_string())
+    }
+}
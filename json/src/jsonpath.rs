@@ -0,0 +1,290 @@
+//! JSONPath queries (RFC 9535) over [`Value`]s.
+
+mod eval;
+mod parse;
+
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+use regex::Regex;
+
+use crate::Value;
+
+/// A parsed JSONPath query, like `$.images[?@.score > 100].tags[0]`.
+///
+/// Everything in RFC 9535 is supported: name, wildcard, index, slice and
+/// filter selectors, child (`.name`, `[...]`) and descendant (`..name`,
+/// `..[...]`) segments, and filters with `&&`, `||`, `!`, comparisons,
+/// existence tests and the functions `length`, `count`, `match`,
+/// `search` and `value`. Queries are type checked when they're parsed,
+/// a query the RFC calls not well-typed is an error.
+///
+/// ```
+/// use json::{json, JsonPath};
+///
+/// let response = json!({"images": [
+///     {"id": 1, "score": 120, "tags": ["safe", "pony"]},
+///     {"id": 2, "score": 30, "tags": ["pony"]},
+///     {"id": 3, "score": 250, "tags": ["safe"]},
+/// ]});
+///
+/// let path = JsonPath::parse("$.images[?@.tags[?@ == 'safe'] && @.score > 100].id").unwrap();
+/// let nodes = path.query(&response);
+/// let ids: Vec<_> = nodes.iter().map(|node| node.value()).collect();
+/// assert_eq!(ids, [&json!(1), &json!(3)]);
+/// assert_eq!(nodes[1].path().to_string(), "$['images'][2]['id']");
+/// ```
+#[derive(Debug)]
+pub struct JsonPath {
+    query: Query,
+}
+
+impl JsonPath {
+    pub fn parse(query: &str) -> Result<JsonPath, JsonPathError> {
+        parse::parse(query).map(|query| JsonPath { query })
+    }
+
+    /// Returns the nodes the query selects, in the order of RFC 9535:
+    /// the order of the selectors, and then the order of the document.
+    /// A node selected twice is returned twice.
+    pub fn query<'v, 'a>(&self, value: &'v Value<'a>) -> Vec<Node<'v, 'a>> {
+        eval::query(&self.query, value)
+    }
+}
+
+impl FromStr for JsonPath {
+    type Err = JsonPathError;
+
+    fn from_str(query: &str) -> Result<JsonPath, JsonPathError> {
+        JsonPath::parse(query)
+    }
+}
+
+impl<'a> Value<'a> {
+    /// Parses the JSONPath `query` and runs it on the value,
+    /// see [`JsonPath`]. Parse it once to run it many times.
+    pub fn query(&self, query: &str) -> Result<Vec<Node<'_, 'a>>, JsonPathError> {
+        Ok(JsonPath::parse(query)?.query(self))
+    }
+}
+
+/// Why a JSONPath query couldn't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonPathError {
+    message: String,
+    offset: usize,
+}
+
+impl JsonPathError {
+    /// Byte offset in the query where the error was found.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl fmt::Display for JsonPathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte {}", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for JsonPathError {}
+
+/// A value a query selected, with where it is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node<'v, 'a> {
+    value: &'v Value<'a>,
+    path: NormalizedPath<'v>,
+}
+
+impl<'v, 'a> Node<'v, 'a> {
+    pub fn value(&self) -> &'v Value<'a> {
+        self.value
+    }
+
+    pub fn path(&self) -> &NormalizedPath<'v> {
+        &self.path
+    }
+}
+
+/// The location of a node, as the keys and indices leading to it from the
+/// root. Displays as a normalized path of RFC 9535, like `$['images'][0]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct NormalizedPath<'v> {
+    elements: Vec<PathElement<'v>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PathElement<'v> {
+    Name(&'v str),
+    Index(usize),
+}
+
+impl<'v> NormalizedPath<'v> {
+    pub fn elements(&self) -> &[PathElement<'v>] {
+        &self.elements
+    }
+
+    /// The same location as a JSON Pointer, for
+    /// [`Value::pointer_mut`] and the other pointer methods.
+    pub fn to_pointer(&self) -> String {
+        let mut pointer = String::new();
+        for element in &self.elements {
+            pointer.push('/');
+            match element {
+                PathElement::Name(name) => {
+                    pointer.push_str(&name.replace('~', "~0").replace('/', "~1"));
+                }
+                PathElement::Index(index) => {
+                    let _ = write!(pointer, "{index}");
+                }
+            }
+        }
+        pointer
+    }
+
+    fn child(&self, element: PathElement<'v>) -> NormalizedPath<'v> {
+        let mut elements = Vec::with_capacity(self.elements.len() + 1);
+        elements.extend_from_slice(&self.elements);
+        elements.push(element);
+        NormalizedPath { elements }
+    }
+}
+
+impl fmt::Display for NormalizedPath<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('$')?;
+        for element in &self.elements {
+            match element {
+                PathElement::Name(name) => {
+                    f.write_str("['")?;
+                    for char in name.chars() {
+                        match char {
+                            '\'' => f.write_str("\\'")?,
+                            '\\' => f.write_str("\\\\")?,
+                            '\u{8}' => f.write_str("\\b")?,
+                            '\u{c}' => f.write_str("\\f")?,
+                            '\n' => f.write_str("\\n")?,
+                            '\r' => f.write_str("\\r")?,
+                            '\t' => f.write_str("\\t")?,
+                            '\0'..='\u{1f}' => write!(f, "\\u{:04x}", char as u32)?,
+                            char => f.write_char(char)?,
+                        }
+                    }
+                    f.write_str("']")?;
+                }
+                PathElement::Index(index) => write!(f, "[{index}]")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `$` or `@` followed by segments.
+#[derive(Debug)]
+struct Query {
+    /// Starts at the root (`$`) rather than at the current node (`@`).
+    absolute: bool,
+    segments: Vec<Segment>,
+}
+
+impl Query {
+    /// Selects at most one node: only names and indices, one per segment,
+    /// and no descendant segments.
+    fn is_singular(&self) -> bool {
+        self.segments.iter().all(|segment| {
+            !segment.descendant
+                && matches!(
+                    segment.selectors[..],
+                    [Selector::Name(_)] | [Selector::Index(_)]
+                )
+        })
+    }
+}
+
+#[derive(Debug)]
+struct Segment {
+    /// `..`: the selectors apply to the node and all of its descendants.
+    descendant: bool,
+    selectors: Vec<Selector>,
+}
+
+#[derive(Debug)]
+enum Selector {
+    Name(String),
+    Wildcard,
+    /// Negative indices count from the end.
+    Index(i64),
+    Slice {
+        start: Option<i64>,
+        end: Option<i64>,
+        step: Option<i64>,
+    },
+    Filter(Logical),
+}
+
+/// A filter expression, true or false for each node.
+#[derive(Debug)]
+enum Logical {
+    Or(Vec<Logical>),
+    And(Vec<Logical>),
+    Not(Box<Logical>),
+    Compare(Box<Comparable>, Comparison, Box<Comparable>),
+    /// True if the query selects anything.
+    Exists(Query),
+    /// A function returning a logical value.
+    Call(Call),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// What can be compared: one value, or nothing.
+#[derive(Debug)]
+enum Comparable {
+    Literal(Value<'static>),
+    /// A singular query.
+    Query(Query),
+    /// A function returning a value.
+    Call(Call),
+}
+
+#[derive(Debug)]
+struct Call {
+    function: Function,
+    arguments: Vec<Argument>,
+}
+
+#[derive(Debug)]
+enum Argument {
+    Value(Comparable),
+    Nodes(Query),
+}
+
+#[derive(Debug)]
+enum Function {
+    Length,
+    Count,
+    Match(Pattern),
+    Search(Pattern),
+    Value,
+}
+
+/// The regular expression of `match` or `search`.
+#[derive(Debug)]
+enum Pattern {
+    /// Compiled with the query, `None` if it isn't a valid I-Regexp.
+    Literal(Option<Regex>),
+    /// Comes from the document, compiled for each node.
+    Dynamic,
+}
+
+#[cfg(test)]
+mod tests;
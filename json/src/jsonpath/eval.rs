@@ -0,0 +1,370 @@
+use std::cmp::Ordering;
+
+use regex::Regex;
+
+use crate::jsonpath::{
+    Argument, Call, Comparable, Comparison, Function, Logical, Node, NormalizedPath, PathElement,
+    Pattern, Query, Segment, Selector,
+};
+use crate::{Number, Value};
+
+pub(super) fn query<'v, 'a>(query: &Query, root: &'v Value<'a>) -> Vec<Node<'v, 'a>> {
+    let start = vec![(root, NormalizedPath::default())];
+    select(&query.segments, start, root)
+        .into_iter()
+        .map(|(value, path)| Node { value, path })
+        .collect()
+}
+
+/// Where a selected value is. Queries inside filters don't need to know, so
+/// their locations are `()` and they don't build paths for nothing.
+trait Location<'v> {
+    fn child(&self, element: PathElement<'v>) -> Self;
+}
+
+impl<'v> Location<'v> for NormalizedPath<'v> {
+    fn child(&self, element: PathElement<'v>) -> Self {
+        NormalizedPath::child(self, element)
+    }
+}
+
+impl<'v> Location<'v> for () {
+    fn child(&self, _: PathElement<'v>) {}
+}
+
+fn select<'v, 'a, L: Location<'v>>(
+    segments: &[Segment],
+    start: Vec<(&'v Value<'a>, L)>,
+    root: &'v Value<'a>,
+) -> Vec<(&'v Value<'a>, L)> {
+    segments.iter().fold(start, |nodes, segment| {
+        let mut selected = Vec::new();
+        for (value, location) in nodes {
+            if segment.descendant {
+                descend(value, location, &mut |value, location| {
+                    apply(&segment.selectors, value, location, root, &mut selected);
+                });
+            } else {
+                apply(&segment.selectors, value, &location, root, &mut selected);
+            }
+        }
+        selected
+    })
+}
+
+/// Visits `value` and everything in it, each node before its children.
+fn descend<'v, 'a, L: Location<'v>>(
+    value: &'v Value<'a>,
+    location: L,
+    visit: &mut impl FnMut(&'v Value<'a>, &L),
+) {
+    visit(value, &location);
+    match value {
+        Value::Object(map) => {
+            for (key, member) in map.iter() {
+                descend(member, location.child(PathElement::Name(key)), visit);
+            }
+        }
+        Value::Array(array) => {
+            for (index, element) in array.iter().enumerate() {
+                descend(element, location.child(PathElement::Index(index)), visit);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn apply<'v, 'a, L: Location<'v>>(
+    selectors: &[Selector],
+    value: &'v Value<'a>,
+    location: &L,
+    root: &'v Value<'a>,
+    selected: &mut Vec<(&'v Value<'a>, L)>,
+) {
+    let element = |index: usize, array: &'v Vec<Value<'a>>| {
+        (&array[index], location.child(PathElement::Index(index)))
+    };
+    for selector in selectors {
+        match (selector, value) {
+            (Selector::Name(name), Value::Object(map)) => {
+                if let Some((key, member)) = map.get_key_value(name) {
+                    selected.push((member, location.child(PathElement::Name(key))));
+                }
+            }
+            (Selector::Index(index), Value::Array(array)) => {
+                if let Some(index) = normalize(*index, array.len()) {
+                    selected.push(element(index, array));
+                }
+            }
+            (&Selector::Slice { start, end, step }, Value::Array(array)) => {
+                let indices = slice(start, end, step, array.len());
+                selected.extend(indices.map(|index| element(index, array)));
+            }
+            (Selector::Wildcard | Selector::Filter(_), Value::Object(map)) => {
+                for (key, member) in map.iter() {
+                    if let Selector::Filter(filter) = selector {
+                        if !test(filter, member, root) {
+                            continue;
+                        }
+                    }
+                    selected.push((member, location.child(PathElement::Name(key))));
+                }
+            }
+            (Selector::Wildcard | Selector::Filter(_), Value::Array(array)) => {
+                for (index, member) in array.iter().enumerate() {
+                    if let Selector::Filter(filter) = selector {
+                        if !test(filter, member, root) {
+                            continue;
+                        }
+                    }
+                    selected.push(element(index, array));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The index of an array of `len` elements, counting from the end if it's negative.
+fn normalize(index: i64, len: usize) -> Option<usize> {
+    let index = if index < 0 {
+        len.checked_sub(index.unsigned_abs() as usize)?
+    } else {
+        index as usize
+    };
+    (index < len).then_some(index)
+}
+
+/// The indices of a slice, as RFC 9535 computes them.
+fn slice(
+    start: Option<i64>,
+    end: Option<i64>,
+    step: Option<i64>,
+    len: usize,
+) -> impl Iterator<Item = usize> {
+    let len = len as i64;
+    let step = step.unwrap_or(1);
+    let bound = |index: i64| if index < 0 { len + index } else { index };
+    let (mut index, stop) = if step >= 0 {
+        let lower = bound(start.unwrap_or(0)).clamp(0, len);
+        let upper = bound(end.unwrap_or(len)).clamp(0, len);
+        (lower, upper)
+    } else {
+        let upper = bound(start.unwrap_or(len - 1)).clamp(-1, len - 1);
+        let lower = bound(end.unwrap_or(-len - 1)).clamp(-1, len - 1);
+        (upper, lower)
+    };
+    std::iter::from_fn(move || {
+        let inside = match step.cmp(&0) {
+            Ordering::Greater => index < stop,
+            Ordering::Less => stop < index,
+            Ordering::Equal => false,
+        };
+        let current = index;
+        index += step;
+        inside.then_some(current as usize)
+    })
+}
+
+/// Whether `current` passes the filter.
+fn test<'v, 'a>(logical: &Logical, current: &'v Value<'a>, root: &'v Value<'a>) -> bool {
+    match logical {
+        Logical::Or(operands) => operands.iter().any(|operand| test(operand, current, root)),
+        Logical::And(operands) => operands.iter().all(|operand| test(operand, current, root)),
+        Logical::Not(operand) => !test(operand, current, root),
+        Logical::Compare(left, comparison, right) => {
+            let left = operand(left, current, root);
+            let right = operand(right, current, root);
+            compare(left.value(), *comparison, right.value())
+        }
+        Logical::Exists(query) => !nodes(query, current, root).is_empty(),
+        Logical::Call(call) => call_logical(call, current, root),
+    }
+}
+
+fn nodes<'v, 'a>(query: &Query, current: &'v Value<'a>, root: &'v Value<'a>) -> Vec<&'v Value<'a>> {
+    let start = if query.absolute { root } else { current };
+    select(&query.segments, vec![(start, ())], root)
+        .into_iter()
+        .map(|(value, ())| value)
+        .collect()
+}
+
+/// A side of a comparison, or an argument of a function.
+enum Operand<'r> {
+    Nothing,
+    Value(&'r Value<'r>),
+    /// Computed by a function.
+    Number(Value<'static>),
+}
+
+impl Operand<'_> {
+    fn value(&self) -> Option<&Value<'_>> {
+        match self {
+            Operand::Nothing => None,
+            Operand::Value(value) => Some(value),
+            Operand::Number(value) => Some(value),
+        }
+    }
+}
+
+fn operand<'r>(
+    comparable: &'r Comparable,
+    current: &'r Value<'r>,
+    root: &'r Value<'r>,
+) -> Operand<'r> {
+    match comparable {
+        Comparable::Literal(value) => Operand::Value(value),
+        Comparable::Query(query) => match nodes(query, current, root)[..] {
+            [value] => Operand::Value(value),
+            _ => Operand::Nothing,
+        },
+        Comparable::Call(call) => call_value(call, current, root),
+    }
+}
+
+fn call_value<'r>(call: &'r Call, current: &'r Value<'r>, root: &'r Value<'r>) -> Operand<'r> {
+    let count = |len: usize| Operand::Number(Value::Number(Number::from(len as u64)));
+    match (&call.function, &call.arguments[..]) {
+        (Function::Length, [Argument::Value(argument)]) => {
+            match operand(argument, current, root).value() {
+                Some(Value::String(string)) => count(string.chars().count()),
+                Some(Value::Array(array)) => count(array.len()),
+                Some(Value::Object(map)) => count(map.len()),
+                _ => Operand::Nothing,
+            }
+        }
+        (Function::Count, [Argument::Nodes(query)]) => count(nodes(query, current, root).len()),
+        (Function::Value, [Argument::Nodes(query)]) => match nodes(query, current, root)[..] {
+            [value] => Operand::Value(value),
+            _ => Operand::Nothing,
+        },
+        _ => unreachable!("the parser checks what functions return and take"),
+    }
+}
+
+fn call_logical<'r>(call: &'r Call, current: &'r Value<'r>, root: &'r Value<'r>) -> bool {
+    let (pattern, anchored) = match &call.function {
+        Function::Match(pattern) => (pattern, true),
+        Function::Search(pattern) => (pattern, false),
+        _ => unreachable!("the parser checks what functions return"),
+    };
+    let [Argument::Value(string), Argument::Value(regex)] = &call.arguments[..] else {
+        unreachable!("the parser checks what functions take");
+    };
+    let string = operand(string, current, root);
+    let Some(Value::String(string)) = string.value() else {
+        return false;
+    };
+    match pattern {
+        Pattern::Literal(regex) => regex.as_ref().is_some_and(|regex| regex.is_match(string)),
+        Pattern::Dynamic => match operand(regex, current, root).value() {
+            Some(Value::String(regex)) => {
+                compile(regex, anchored).is_some_and(|regex| regex.is_match(string))
+            }
+            _ => false,
+        },
+    }
+}
+
+/// Compiles an I-Regexp (RFC 9485), `None` if it's invalid. `match` is
+/// `anchored` to the whole string, `search` looks for it anywhere.
+pub(super) fn compile(pattern: &str, anchored: bool) -> Option<Regex> {
+    // the dot of I-Regexp doesn't match either line break
+    let mut translated = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+    let mut in_class = false;
+    while let Some(char) = chars.next() {
+        match char {
+            '\\' => {
+                translated.push(char);
+                translated.extend(chars.next());
+                continue;
+            }
+            '[' => in_class = true,
+            ']' => in_class = false,
+            '.' if !in_class => {
+                translated.push_str("[^\\n\\r]");
+                continue;
+            }
+            _ => {}
+        }
+        translated.push(char);
+    }
+    let translated = if anchored {
+        format!("\\A(?:{translated})\\z")
+    } else {
+        translated
+    };
+    Regex::new(&translated).ok()
+}
+
+fn compare(left: Option<&Value<'_>>, comparison: Comparison, right: Option<&Value<'_>>) -> bool {
+    match comparison {
+        Comparison::Eq => equal(left, right),
+        Comparison::Ne => !equal(left, right),
+        Comparison::Lt => less(left, right),
+        Comparison::Le => less(left, right) || equal(left, right),
+        Comparison::Gt => less(right, left),
+        Comparison::Ge => less(right, left) || equal(left, right),
+    }
+}
+
+/// Nothing equals only nothing.
+fn equal(left: Option<&Value<'_>>, right: Option<&Value<'_>>) -> bool {
+    match (left, right) {
+        (None, None) => true,
+        (Some(left), Some(right)) => equal_values(left, right),
+        _ => false,
+    }
+}
+
+fn equal_values(left: &Value<'_>, right: &Value<'_>) -> bool {
+    match (left, right) {
+        (Value::Null, Value::Null) => true,
+        (Value::Bool(left), Value::Bool(right)) => left == right,
+        (Value::Number(left), Value::Number(right)) => {
+            compare_numbers(left, right) == Some(Ordering::Equal)
+        }
+        (Value::String(left), Value::String(right)) => left == right,
+        (Value::Array(left), Value::Array(right)) => {
+            left.len() == right.len()
+                && left
+                    .iter()
+                    .zip(right)
+                    .all(|(left, right)| equal_values(left, right))
+        }
+        (Value::Object(left), Value::Object(right)) => {
+            left.len() == right.len()
+                && left.iter().all(|(key, left)| {
+                    right
+                        .get(key)
+                        .is_some_and(|right| equal_values(left, right))
+                })
+        }
+        _ => false,
+    }
+}
+
+/// Only numbers and strings are ordered, each among themselves.
+fn less(left: Option<&Value<'_>>, right: Option<&Value<'_>>) -> bool {
+    match (left, right) {
+        (Some(Value::Number(left)), Some(Value::Number(right))) => {
+            compare_numbers(left, right) == Some(Ordering::Less)
+        }
+        // UTF-8 bytes sort like the code points
+        (Some(Value::String(left)), Some(Value::String(right))) => left < right,
+        _ => false,
+    }
+}
+
+/// Integers compare exactly, anything else as `f64`.
+fn compare_numbers(left: &Number, right: &Number) -> Option<Ordering> {
+    if let (Some(left), Some(right)) = (left.as_i64(), right.as_i64()) {
+        return Some(left.cmp(&right));
+    }
+    if let (Some(left), Some(right)) = (left.as_u64(), right.as_u64()) {
+        return Some(left.cmp(&right));
+    }
+    left.as_f64().partial_cmp(&right.as_f64())
+}
@@ -0,0 +1,594 @@
+use std::borrow::Cow;
+
+use crate::jsonpath::eval;
+use crate::jsonpath::{
+    Argument, Call, Comparable, Comparison, Function, JsonPathError, Logical, Pattern, Query,
+    Segment, Selector,
+};
+use crate::number::Number;
+use crate::Value;
+
+/// How deep filters and parentheses may nest, to keep the recursion bounded.
+const MAX_NESTING: usize = 64;
+
+/// Indices and slice bounds must be exact in a double, like in I-JSON.
+const MAX_INDEX: i64 = (1 << 53) - 1;
+
+pub(super) fn parse(input: &str) -> Result<Query, JsonPathError> {
+    let mut parser = Parser {
+        input,
+        offset: 0,
+        depth: 0,
+    };
+    if !parser.eat('$') {
+        return Err(parser.unexpected("'$'"));
+    }
+    let segments = parser.segments()?;
+    if parser.offset < input.len() {
+        return Err(parser.unexpected("'.', '..' or '['"));
+    }
+    Ok(Query {
+        absolute: true,
+        segments,
+    })
+}
+
+struct Parser<'q> {
+    input: &'q str,
+    offset: usize,
+    /// Filters and parentheses open around the current position.
+    depth: usize,
+}
+
+/// What the type of a function's parameter or result is.
+#[derive(Clone, Copy)]
+enum Type {
+    Value,
+    Logical,
+    Nodes,
+}
+
+/// A part of a filter expression, before it's known where it's used.
+enum Expr {
+    Logical(Logical),
+    /// In parentheses, which can only be a logical expression.
+    Paren(Logical),
+    Literal(Value<'static>),
+    Query(Query),
+    Call(Call, Type),
+}
+
+impl<'q> Parser<'q> {
+    fn peek(&self) -> Option<char> {
+        self.input[self.offset..].chars().next()
+    }
+
+    fn eat(&mut self, char: char) -> bool {
+        self.eat_str(char.encode_utf8(&mut [0; 4]))
+    }
+
+    fn eat_str(&mut self, expected: &str) -> bool {
+        let found = self.input[self.offset..].starts_with(expected);
+        if found {
+            self.offset += expected.len();
+        }
+        found
+    }
+
+    fn expect(&mut self, char: char) -> Result<(), JsonPathError> {
+        if self.eat(char) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("{char:?}")))
+        }
+    }
+
+    fn skip_blank(&mut self) {
+        while let Some(' ' | '\t' | '\n' | '\r') = self.peek() {
+            self.offset += 1;
+        }
+    }
+
+    /// Eats `operator` with the blank space around it, or nothing.
+    fn eat_operator(&mut self, operator: &str) -> bool {
+        let before = self.offset;
+        self.skip_blank();
+        if self.eat_str(operator) {
+            self.skip_blank();
+            true
+        } else {
+            self.offset = before;
+            false
+        }
+    }
+
+    fn error_at(&self, offset: usize, message: String) -> JsonPathError {
+        JsonPathError { message, offset }
+    }
+
+    fn unexpected(&self, expected: &str) -> JsonPathError {
+        let message = match self.peek() {
+            Some(char) => format!("Expected {expected}, found {char:?}"),
+            None => format!("Expected {expected}, found the end of the query"),
+        };
+        self.error_at(self.offset, message)
+    }
+
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, JsonPathError>,
+    ) -> Result<T, JsonPathError> {
+        if self.depth == MAX_NESTING {
+            let message = format!("Filters are nested more than {MAX_NESTING} levels deep");
+            return Err(self.error_at(self.offset, message));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    /// Segments, each after optional blank space.
+    fn segments(&mut self) -> Result<Vec<Segment>, JsonPathError> {
+        let mut segments = Vec::new();
+        loop {
+            let before = self.offset;
+            self.skip_blank();
+            match self.peek() {
+                Some('.' | '[') => segments.push(self.segment()?),
+                _ => {
+                    self.offset = before;
+                    return Ok(segments);
+                }
+            }
+        }
+    }
+
+    fn segment(&mut self) -> Result<Segment, JsonPathError> {
+        let descendant = self.eat_str("..");
+        let selectors = if descendant || self.eat('.') {
+            match self.peek() {
+                Some('[') if descendant => self.bracketed()?,
+                Some('*') => {
+                    self.offset += 1;
+                    vec![Selector::Wildcard]
+                }
+                _ => vec![Selector::Name(self.member_name()?)],
+            }
+        } else {
+            self.bracketed()?
+        };
+        Ok(Segment {
+            descendant,
+            selectors,
+        })
+    }
+
+    /// The name after a dot, without quotes.
+    fn member_name(&mut self) -> Result<String, JsonPathError> {
+        let name_first = |char: char| char.is_ascii_alphabetic() || char == '_' || char >= '\u{80}';
+        if !self.peek().is_some_and(name_first) {
+            return Err(self.unexpected("a member name"));
+        }
+        let start = self.offset;
+        while let Some(char) = self.peek() {
+            if !name_first(char) && !char.is_ascii_digit() {
+                break;
+            }
+            self.offset += char.len_utf8();
+        }
+        Ok(self.input[start..self.offset].to_owned())
+    }
+
+    fn bracketed(&mut self) -> Result<Vec<Selector>, JsonPathError> {
+        self.expect('[')?;
+        let mut selectors = Vec::new();
+        loop {
+            self.skip_blank();
+            selectors.push(self.selector()?);
+            self.skip_blank();
+            if self.eat(']') {
+                return Ok(selectors);
+            }
+            if !self.eat(',') {
+                return Err(self.unexpected("',' or ']'"));
+            }
+        }
+    }
+
+    fn selector(&mut self) -> Result<Selector, JsonPathError> {
+        match self.peek() {
+            Some('\'' | '"') => Ok(Selector::Name(self.string()?)),
+            Some('*') => {
+                self.offset += 1;
+                Ok(Selector::Wildcard)
+            }
+            Some('?') => {
+                self.offset += 1;
+                self.skip_blank();
+                let at = self.offset;
+                let expr = self.nested(Self::logical_or)?;
+                Ok(Selector::Filter(self.to_logical(expr, at)?))
+            }
+            Some('-' | '0'..='9' | ':') => self.index_or_slice(),
+            _ => Err(self.unexpected("a selector")),
+        }
+    }
+
+    fn index_or_slice(&mut self) -> Result<Selector, JsonPathError> {
+        let starts_integer = |parser: &Self| matches!(parser.peek(), Some('-' | '0'..='9'));
+        let start = if self.peek() == Some(':') {
+            None
+        } else {
+            let index = self.integer()?;
+            let before = self.offset;
+            self.skip_blank();
+            if self.peek() != Some(':') {
+                self.offset = before;
+                return Ok(Selector::Index(index));
+            }
+            Some(index)
+        };
+        self.expect(':')?;
+        self.skip_blank();
+        let end = if starts_integer(self) {
+            let end = self.integer()?;
+            self.skip_blank();
+            Some(end)
+        } else {
+            None
+        };
+        let mut step = None;
+        if self.eat(':') {
+            self.skip_blank();
+            if starts_integer(self) {
+                step = Some(self.integer()?);
+            }
+        }
+        Ok(Selector::Slice { start, end, step })
+    }
+
+    /// An index or a bound of a slice: no leading zeros, no `-0`.
+    fn integer(&mut self) -> Result<i64, JsonPathError> {
+        let start = self.offset;
+        let negative = self.eat('-');
+        if self.eat('0') {
+            if negative || self.peek().is_some_and(|char| char.is_ascii_digit()) {
+                let message = "Indices can't have leading zeros or be -0".to_owned();
+                return Err(self.error_at(start, message));
+            }
+            return Ok(0);
+        }
+        if !self.peek().is_some_and(|char| char.is_ascii_digit()) {
+            return Err(self.unexpected("a digit"));
+        }
+        while self.peek().is_some_and(|char| char.is_ascii_digit()) {
+            self.offset += 1;
+        }
+        match self.input[start..self.offset].parse::<i64>() {
+            Ok(integer) if (-MAX_INDEX..=MAX_INDEX).contains(&integer) => Ok(integer),
+            _ => {
+                let message = format!("Indices must be within ±{MAX_INDEX}");
+                Err(self.error_at(start, message))
+            }
+        }
+    }
+
+    /// A string literal in single or double quotes.
+    fn string(&mut self) -> Result<String, JsonPathError> {
+        let start = self.offset;
+        let Some(quote) = self.peek() else {
+            return Err(self.unexpected("a string"));
+        };
+        self.offset += 1;
+        let mut string = String::new();
+        loop {
+            let Some(char) = self.peek() else {
+                let message = "The string doesn't end".to_owned();
+                return Err(self.error_at(start, message));
+            };
+            match char {
+                '\\' => {
+                    self.offset += 1;
+                    string.push(self.escape(quote)?);
+                    continue;
+                }
+                '\0'..='\u{1f}' => {
+                    let message = format!("Control character {char:?} must be escaped");
+                    return Err(self.error_at(self.offset, message));
+                }
+                _ => {}
+            }
+            self.offset += char.len_utf8();
+            if char == quote {
+                return Ok(string);
+            }
+            string.push(char);
+        }
+    }
+
+    /// The character of an escape, after its backslash.
+    fn escape(&mut self, quote: char) -> Result<char, JsonPathError> {
+        let start = self.offset - 1;
+        let Some(char) = self.peek() else {
+            return Err(self.unexpected("an escape"));
+        };
+        self.offset += char.len_utf8();
+        let unescaped = match char {
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            '/' | '\\' => char,
+            'u' => return self.unicode_escape(start),
+            char if char == quote => char,
+            char => {
+                let message = format!("Unknown escape '\\{char}'");
+                return Err(self.error_at(start, message));
+            }
+        };
+        Ok(unescaped)
+    }
+
+    /// The rest of `\uXXXX`, with the low surrogate after a high one.
+    fn unicode_escape(&mut self, start: usize) -> Result<char, JsonPathError> {
+        let unpaired = |parser: &Self| {
+            let message = "Unpaired surrogate in a \\u escape".to_owned();
+            parser.error_at(start, message)
+        };
+        let code = match self.hex4()? {
+            high @ 0xD800..=0xDBFF => {
+                if !self.eat_str("\\u") {
+                    return Err(unpaired(self));
+                }
+                match self.hex4()? {
+                    low @ 0xDC00..=0xDFFF => 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00),
+                    _ => return Err(unpaired(self)),
+                }
+            }
+            0xDC00..=0xDFFF => return Err(unpaired(self)),
+            code => code,
+        };
+        Ok(char::from_u32(code).expect("surrogates are paired"))
+    }
+
+    fn hex4(&mut self) -> Result<u32, JsonPathError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let Some(digit) = self.peek().and_then(|char| char.to_digit(16)) else {
+                return Err(self.unexpected("a hexadecimal digit"));
+            };
+            self.offset += 1;
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    fn logical_or(&mut self) -> Result<Expr, JsonPathError> {
+        let start = self.offset;
+        let first = self.logical_and()?;
+        let mut rest = Vec::new();
+        while self.eat_operator("||") {
+            let at = self.offset;
+            let expr = self.logical_and()?;
+            rest.push(self.to_logical(expr, at)?);
+        }
+        if rest.is_empty() {
+            return Ok(first);
+        }
+        rest.insert(0, self.to_logical(first, start)?);
+        Ok(Expr::Logical(Logical::Or(rest)))
+    }
+
+    fn logical_and(&mut self) -> Result<Expr, JsonPathError> {
+        let start = self.offset;
+        let first = self.unary()?;
+        let mut rest = Vec::new();
+        while self.eat_operator("&&") {
+            let at = self.offset;
+            let expr = self.unary()?;
+            rest.push(self.to_logical(expr, at)?);
+        }
+        if rest.is_empty() {
+            return Ok(first);
+        }
+        rest.insert(0, self.to_logical(first, start)?);
+        Ok(Expr::Logical(Logical::And(rest)))
+    }
+
+    /// A negation, a comparison, or a single operand.
+    fn unary(&mut self) -> Result<Expr, JsonPathError> {
+        if self.eat('!') {
+            self.skip_blank();
+            let at = self.offset;
+            let expr = self.primary()?;
+            let negated = Logical::Not(Box::new(self.to_logical(expr, at)?));
+            return Ok(Expr::Logical(negated));
+        }
+        let left_at = self.offset;
+        let left = self.primary()?;
+        let comparisons = [
+            ("==", Comparison::Eq),
+            ("!=", Comparison::Ne),
+            ("<=", Comparison::Le),
+            (">=", Comparison::Ge),
+            ("<", Comparison::Lt),
+            (">", Comparison::Gt),
+        ];
+        let Some(comparison) = comparisons
+            .into_iter()
+            .find_map(|(operator, comparison)| self.eat_operator(operator).then_some(comparison))
+        else {
+            return Ok(left);
+        };
+        let right_at = self.offset;
+        let right = self.primary()?;
+        let left = self.to_comparable(left, left_at)?;
+        let right = self.to_comparable(right, right_at)?;
+        let compare = Logical::Compare(Box::new(left), comparison, Box::new(right));
+        Ok(Expr::Logical(compare))
+    }
+
+    fn primary(&mut self) -> Result<Expr, JsonPathError> {
+        match self.peek() {
+            Some('(') => self.nested(|parser| {
+                parser.offset += 1;
+                parser.skip_blank();
+                let at = parser.offset;
+                let expr = parser.logical_or()?;
+                parser.skip_blank();
+                parser.expect(')')?;
+                Ok(Expr::Paren(parser.to_logical(expr, at)?))
+            }),
+            Some(root @ ('@' | '$')) => {
+                self.offset += 1;
+                let segments = self.nested(Self::segments)?;
+                Ok(Expr::Query(Query {
+                    absolute: root == '$',
+                    segments,
+                }))
+            }
+            Some('\'' | '"') => {
+                let string = self.string()?;
+                Ok(Expr::Literal(Value::String(Cow::Owned(string))))
+            }
+            Some('-' | '0'..='9') => self.number(),
+            Some('a'..='z') => {
+                let start = self.offset;
+                while let Some('a'..='z' | '0'..='9' | '_') = self.peek() {
+                    self.offset += 1;
+                }
+                match &self.input[start..self.offset] {
+                    "true" => Ok(Expr::Literal(Value::Bool(true))),
+                    "false" => Ok(Expr::Literal(Value::Bool(false))),
+                    "null" => Ok(Expr::Literal(Value::Null)),
+                    name => self.call(name, start),
+                }
+            }
+            _ => Err(self.unexpected("a literal, a query or a function")),
+        }
+    }
+
+    fn number(&mut self) -> Result<Expr, JsonPathError> {
+        let start = self.offset;
+        let digits = |parser: &mut Self| {
+            if !parser.peek().is_some_and(|char| char.is_ascii_digit()) {
+                return Err(parser.unexpected("a digit"));
+            }
+            while parser.peek().is_some_and(|char| char.is_ascii_digit()) {
+                parser.offset += 1;
+            }
+            Ok(())
+        };
+        self.eat('-');
+        if !self.eat('0') {
+            digits(self)?;
+        } else if self.peek().is_some_and(|char| char.is_ascii_digit()) {
+            let message = "Numbers can't have leading zeros".to_owned();
+            return Err(self.error_at(start, message));
+        }
+        let mut is_float = false;
+        if self.eat('.') {
+            is_float = true;
+            digits(self)?;
+        }
+        if self.eat('e') || self.eat('E') {
+            is_float = true;
+            let _ = self.eat('+') || self.eat('-');
+            digits(self)?;
+        }
+        let lexeme = &self.input[start..self.offset];
+        match Number::from_lexeme(lexeme, is_float) {
+            Some(number) => Ok(Expr::Literal(Value::Number(number))),
+            None => Err(self.error_at(start, format!("Number {lexeme} is out of range"))),
+        }
+    }
+
+    fn call(&mut self, name: &str, start: usize) -> Result<Expr, JsonPathError> {
+        let (parameters, result): (&[Type], Type) = match name {
+            "length" => (&[Type::Value], Type::Value),
+            "count" | "value" => (&[Type::Nodes], Type::Value),
+            "match" | "search" => (&[Type::Value, Type::Value], Type::Logical),
+            _ => return Err(self.error_at(start, format!("Unknown function {name}()"))),
+        };
+        let count = parameters.len();
+        let s = if count == 1 { "" } else { "s" };
+        let wrong_count = format!("{name}() takes {count} argument{s}");
+        self.expect('(')?;
+        self.skip_blank();
+        let mut arguments = Vec::new();
+        if !self.eat(')') {
+            loop {
+                let at = self.offset;
+                let expr = self.nested(Self::logical_or)?;
+                let argument = match parameters.get(arguments.len()) {
+                    Some(Type::Value) => Argument::Value(self.to_comparable(expr, at)?),
+                    Some(Type::Nodes) => match expr {
+                        Expr::Query(query) => Argument::Nodes(query),
+                        _ => {
+                            let message = format!("Expected a query as the argument of {name}()");
+                            return Err(self.error_at(at, message));
+                        }
+                    },
+                    Some(Type::Logical) => unreachable!("no function takes a logical argument"),
+                    None => return Err(self.error_at(at, wrong_count)),
+                };
+                arguments.push(argument);
+                self.skip_blank();
+                if self.eat(')') {
+                    break;
+                }
+                self.expect(',')?;
+                self.skip_blank();
+            }
+        }
+        if arguments.len() != count {
+            return Err(self.error_at(start, wrong_count));
+        }
+
+        let pattern = |anchored| match &arguments[1] {
+            Argument::Value(Comparable::Literal(Value::String(pattern))) => {
+                Pattern::Literal(eval::compile(pattern, anchored))
+            }
+            _ => Pattern::Dynamic,
+        };
+        let function = match name {
+            "length" => Function::Length,
+            "count" => Function::Count,
+            "value" => Function::Value,
+            "match" => Function::Match(pattern(true)),
+            _ => Function::Search(pattern(false)),
+        };
+        let call = Call {
+            function,
+            arguments,
+        };
+        Ok(Expr::Call(call, result))
+    }
+
+    /// Checks that `expr`, which starts at `at`, can be a test.
+    fn to_logical(&self, expr: Expr, at: usize) -> Result<Logical, JsonPathError> {
+        let found = match expr {
+            Expr::Logical(logical) | Expr::Paren(logical) => return Ok(logical),
+            Expr::Query(query) => return Ok(Logical::Exists(query)),
+            Expr::Call(call, Type::Logical | Type::Nodes) => return Ok(Logical::Call(call)),
+            Expr::Call(_, Type::Value) => "a function returning a value, compare it",
+            Expr::Literal(_) => "a literal",
+        };
+        Err(self.error_at(at, format!("Expected a test, found {found}")))
+    }
+
+    /// Checks that `expr`, which starts at `at`, can be compared.
+    fn to_comparable(&self, expr: Expr, at: usize) -> Result<Comparable, JsonPathError> {
+        let found = match expr {
+            Expr::Literal(value) => return Ok(Comparable::Literal(value)),
+            Expr::Query(query) if query.is_singular() => return Ok(Comparable::Query(query)),
+            Expr::Call(call, Type::Value) => return Ok(Comparable::Call(call)),
+            Expr::Query(_) => "a query which may select several nodes",
+            Expr::Call(_, Type::Logical | Type::Nodes) => "a function which doesn't return a value",
+            Expr::Logical(_) | Expr::Paren(_) => "a logical expression",
+        };
+        Err(self.error_at(at, format!("Expected a value, found {found}")))
+    }
+}
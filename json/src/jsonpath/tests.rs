@@ -0,0 +1,411 @@
+use crate::{json, JsonPath, Value};
+
+/// The document of the examples of RFC 9535.
+fn store() -> Value<'static> {
+    json!({ "store": {
+        "book": [
+            { "category": "reference",
+              "author": "Nigel Rees",
+              "title": "Sayings of the Century",
+              "price": 8.95
+            },
+            { "category": "fiction",
+              "author": "Evelyn Waugh",
+              "title": "Sword of Honour",
+              "price": 12.99
+            },
+            { "category": "fiction",
+              "author": "Herman Melville",
+              "title": "Moby Dick",
+              "isbn": "0-553-21311-3",
+              "price": 8.99
+            },
+            { "category": "fiction",
+              "author": "J. R. R. Tolkien",
+              "title": "The Lord of the Rings",
+              "isbn": "0-395-19395-8",
+              "price": 22.99
+            }
+        ],
+        "bicycle": {
+            "color": "red",
+            "price": 399
+        }
+    }})
+}
+
+/// Runs `query` and lists the normalized paths of the nodes.
+#[track_caller]
+fn paths(value: &Value<'_>, query: &str) -> Vec<String> {
+    let path = JsonPath::parse(query).unwrap_or_else(|error| panic!("{query}: {error}"));
+    path.query(value)
+        .iter()
+        .map(|node| node.path().to_string())
+        .collect()
+}
+
+#[track_caller]
+fn values<'v, 'a>(value: &'v Value<'a>, query: &str) -> Vec<&'v Value<'a>> {
+    let path = JsonPath::parse(query).unwrap_or_else(|error| panic!("{query}: {error}"));
+    path.query(value).iter().map(|node| node.value()).collect()
+}
+
+#[track_caller]
+fn error(query: &str) -> String {
+    JsonPath::parse(query).expect_err(query).to_string()
+}
+
+#[test]
+fn store_examples() {
+    let store = store();
+    let authors = [
+        "$['store']['book'][0]['author']",
+        "$['store']['book'][1]['author']",
+        "$['store']['book'][2]['author']",
+        "$['store']['book'][3]['author']",
+    ];
+    assert_eq!(paths(&store, "$.store.book[*].author"), authors);
+    assert_eq!(paths(&store, "$..author"), authors);
+    assert_eq!(
+        paths(&store, "$.store.*"),
+        ["$['store']['book']", "$['store']['bicycle']"]
+    );
+    assert_eq!(
+        values(&store, "$.store..price"),
+        [
+            &json!(8.95),
+            &json!(12.99),
+            &json!(8.99),
+            &json!(22.99),
+            &json!(399)
+        ]
+    );
+    assert_eq!(paths(&store, "$..book[2]"), ["$['store']['book'][2]"]);
+    assert_eq!(
+        values(&store, "$..book[2].author"),
+        [&json!("Herman Melville")]
+    );
+    assert_eq!(values(&store, "$..book[2].publisher"), Vec::<&Value>::new());
+    assert_eq!(paths(&store, "$..book[-1]"), ["$['store']['book'][3]"]);
+    assert_eq!(
+        paths(&store, "$..book[0,1]"),
+        ["$['store']['book'][0]", "$['store']['book'][1]"]
+    );
+    assert_eq!(
+        paths(&store, "$..book[:2]"),
+        ["$['store']['book'][0]", "$['store']['book'][1]"]
+    );
+    assert_eq!(
+        paths(&store, "$..book[?@.isbn]"),
+        ["$['store']['book'][2]", "$['store']['book'][3]"]
+    );
+    assert_eq!(
+        paths(&store, "$..book[?@.price<10]"),
+        ["$['store']['book'][0]", "$['store']['book'][2]"]
+    );
+    assert_eq!(paths(&store, "$..*").len(), 27);
+}
+
+#[test]
+fn selectors() {
+    let value = json!({"o": {"j j": {"k.k": 3}}, "'": {"@": 2}, "a": [0, 1, 2, 3, 4, 5, 6, 7]});
+    assert_eq!(values(&value, "$.o['j j']"), [&json!({"k.k": 3})]);
+    assert_eq!(values(&value, "$.o['j j']['k.k']"), [&json!(3)]);
+    assert_eq!(values(&value, r#"$.o["j j"]["k.k"]"#), [&json!(3)]);
+    assert_eq!(values(&value, r#"$["'"]["@"]"#), [&json!(2)]);
+    assert_eq!(values(&value, "$['\\'']['\\u0040']"), [&json!(2)]);
+    assert_eq!(paths(&value, "$"), ["$"]);
+    assert_eq!(paths(&value, "$.missing"), Vec::<String>::new());
+    assert_eq!(paths(&value, "$.a.b"), Vec::<String>::new());
+    assert_eq!(paths(&value, "$.o[0]"), Vec::<String>::new());
+
+    let numbers = |query| -> Vec<i64> {
+        values(&value, query)
+            .iter()
+            .map(|value| value.as_i64().unwrap())
+            .collect()
+    };
+    assert_eq!(numbers("$.a[1]"), [1]);
+    assert_eq!(numbers("$.a[-2]"), [6]);
+    assert_eq!(numbers("$.a[8]"), Vec::<i64>::new());
+    assert_eq!(numbers("$.a[-9]"), Vec::<i64>::new());
+    assert_eq!(numbers("$.a[1:3]"), [1, 2]);
+    assert_eq!(numbers("$.a[5:]"), [5, 6, 7]);
+    assert_eq!(numbers("$.a[1:5:2]"), [1, 3]);
+    assert_eq!(numbers("$.a[5:1:-2]"), [5, 3]);
+    assert_eq!(numbers("$.a[::-1]"), [7, 6, 5, 4, 3, 2, 1, 0]);
+    assert_eq!(numbers("$.a[-3:]"), [5, 6, 7]);
+    assert_eq!(numbers("$.a[-100:2]"), [0, 1]);
+    assert_eq!(numbers("$.a[::0]"), Vec::<i64>::new());
+    assert_eq!(numbers("$.a[ 1 : 3 : 1 ]"), [1, 2]);
+    // selectors in order, duplicates kept
+    assert_eq!(numbers("$.a[0, 3, 0:2, -1]"), [0, 3, 0, 1, 7]);
+
+    // descendants: each node before its children, in document order
+    let nested = json!({"a": [{"a": 1}, [{"a": 2}]], "b": {"a": 3}});
+    assert_eq!(
+        paths(&nested, "$..a"),
+        [
+            "$['a']",
+            "$['a'][0]['a']",
+            "$['a'][1][0]['a']",
+            "$['b']['a']"
+        ]
+    );
+    assert_eq!(paths(&nested, "$..[0]"), ["$['a'][0]", "$['a'][1][0]"]);
+    assert_eq!(paths(&nested, "$ .b ..a"), ["$['b']['a']"]);
+}
+
+#[test]
+fn filters() {
+    let value = json!({
+        "a": [3, 5, 1, 2, 4, 6, {"b": "j"}, {"b": "k"}, {"b": {}}, {"b": "kilo"}],
+        "o": {"p": 1, "q": 2, "r": 3, "s": 5, "t": {"u": 6}},
+        "e": "f"
+    });
+    let count = |query| values(&value, query).len();
+    assert_eq!(
+        values(&value, "$.a[?@.b == 'kilo']"),
+        [&json!({"b": "kilo"})]
+    );
+    assert_eq!(
+        values(&value, "$.a[?(@.b == 'kilo')]"),
+        [&json!({"b": "kilo"})]
+    );
+    assert_eq!(
+        values(&value, "$.a[?@>3.5]"),
+        [&json!(5), &json!(4), &json!(6)]
+    );
+    assert_eq!(count("$.a[?@.b]"), 4);
+    assert_eq!(count("$[?@.*]"), 2);
+    assert_eq!(values(&value, "$[?@[?@.b]]"), [&value["a"]]);
+    assert_eq!(
+        values(&value, "$.o[?@<3, ?@<3]"),
+        [&json!(1), &json!(2), &json!(1), &json!(2)]
+    );
+    assert_eq!(count("$.a[?@<2 || @.b == \"k\"]"), 2);
+    assert_eq!(count("$.a[?match(@.b, \"[jk]\")]"), 2);
+    assert_eq!(count("$.a[?search(@.b, \"[jk]\")]"), 3);
+    assert_eq!(values(&value, "$.o[?@>1 && @<4]"), [&json!(2), &json!(3)]);
+    assert_eq!(values(&value, "$.o[?@.u || @.x]"), [&json!({"u": 6})]);
+    assert_eq!(count("$.a[?@.b == $.x]"), 6);
+    assert_eq!(values(&value, "$.a[?@ == @]").len(), 10);
+    assert_eq!(count("$.a[?!@.b]"), 6);
+    assert_eq!(count("$.a[?!(@.b == 'j' || @.b == 'k')]"), 8);
+    // `$` in a filter is the root
+    assert_eq!(values(&value, "$.o[?@ == $.o.s]"), [&json!(5)]);
+    // only numbers and strings are ordered
+    assert_eq!(count("$.a[?@.b < 'k']"), 1);
+    assert_eq!(count("$.a[?@.b == true]"), 0);
+
+    let mixed = json!([1, 1.0, -0.0, 0, 1e0, "1", [1], {"a": [1]}, null, true]);
+    assert_eq!(values(&mixed, "$[?@ == 1]").len(), 3);
+    assert_eq!(values(&mixed, "$[?@ == -0]").len(), 2);
+    assert_eq!(values(&mixed, "$[?@ == null]"), [&Value::Null]);
+    assert_eq!(values(&mixed, "$[?@.a == $[7].a]"), [&json!({"a": [1]})]);
+    assert_eq!(values(&mixed, "$[?@ >= true]"), [&json!(true)]);
+}
+
+#[test]
+fn functions() {
+    let value = json!([
+        {"name": "Rarity", "friends": ["Spike", "Twilight"], "note": "a\nb"},
+        {"name": "Applejack", "friends": []},
+        {"name": "Spike", "friends": {"best": "Rarity"}},
+        {"name": "Ünïcödé", "friends": [1, 2, 3]}
+    ]);
+    let names = |query| -> Vec<&str> {
+        values(&value, query)
+            .iter()
+            .map(|value| value["name"].as_str().unwrap())
+            .collect()
+    };
+    assert_eq!(names("$[?length(@.name) == 6]"), ["Rarity"]);
+    assert_eq!(names("$[?length(@.name) == 7]"), ["Ünïcödé"]);
+    assert_eq!(names("$[?length(@.friends) >= 2]"), ["Rarity", "Ünïcödé"]);
+    assert_eq!(names("$[?length(@.friends) == 1]"), ["Spike"]);
+    assert_eq!(names("$[?length(@.missing) == 0]"), Vec::<&str>::new());
+    assert_eq!(names("$[?count(@.friends.*) == 0]"), ["Applejack"]);
+    assert_eq!(names("$[?count(@..*) >= 5]"), ["Rarity", "Ünïcödé"]);
+    assert_eq!(names("$[?value(@.friends.best) == 'Rarity']"), ["Spike"]);
+    assert_eq!(names("$[?value(@.friends.*) == 'Rarity']"), ["Spike"]);
+    assert_eq!(names("$[?match(@.name, 'R.*')]"), ["Rarity"]);
+    assert_eq!(names("$[?match(@.name, 'R')]"), Vec::<&str>::new());
+    assert_eq!(names("$[?search(@.name, 'R')]"), ["Rarity"]);
+    assert_eq!(
+        names("$[?search(@.name, '[aeiou]{2}')]"),
+        Vec::<&str>::new()
+    );
+    assert_eq!(names("$[?match(@.name, $[2].friends.best)]"), ["Rarity"]);
+    // the dot matches neither line break, an invalid pattern nothing
+    assert_eq!(names("$[?match(@.note, 'a.b')]"), Vec::<&str>::new());
+    assert_eq!(names("$[?match(@.note, 'a\\nb')]"), ["Rarity"]);
+    assert_eq!(names("$[?search(@.name, '(')]"), Vec::<&str>::new());
+    assert_eq!(names("$[?!search(@.name, 'i')]"), ["Applejack", "Ünïcödé"]);
+}
+
+#[test]
+fn normalized_paths() {
+    let value = json!({"a'b": {"c\\d": [{"\u{7}\n": {"/~": 1}}]}});
+    let path = JsonPath::parse("$..*").unwrap();
+    let nodes = path.query(&value);
+    let last = nodes.last().unwrap();
+    assert_eq!(
+        last.path().to_string(),
+        r"$['a\'b']['c\\d'][0]['\u0007\n']['/~']"
+    );
+    assert_eq!(last.path().to_pointer(), "/a'b/c\\d/0/\u{7}\n/~1~0");
+    assert_eq!(value.pointer(&last.path().to_pointer()), Some(last.value()));
+    assert_eq!(
+        JsonPath::parse("$").unwrap().query(&value)[0]
+            .path()
+            .elements(),
+        []
+    );
+
+    // a normalized path is a query for its node
+    for node in &nodes {
+        let path = node.path().to_string();
+        assert_eq!(values(&value, &path), [node.value()]);
+    }
+
+    let nodes = value.query("$['a\\'b'].*").unwrap();
+    assert_eq!(nodes.len(), 1);
+}
+
+#[test]
+fn syntax_errors() {
+    assert_eq!(
+        error(""),
+        "Expected '$', found the end of the query at byte 0"
+    );
+    assert_eq!(error("@.a"), "Expected '$', found '@' at byte 0");
+    assert_eq!(
+        error("$a"),
+        "Expected '.', '..' or '[', found 'a' at byte 1"
+    );
+    assert_eq!(
+        error("$.a "),
+        "Expected '.', '..' or '[', found ' ' at byte 3"
+    );
+    assert_eq!(error("$. a"), "Expected a member name, found ' ' at byte 2");
+    assert_eq!(error("$.1"), "Expected a member name, found '1' at byte 2");
+    assert_eq!(
+        error("$..['a'"),
+        "Expected ',' or ']', found the end of the query at byte 7"
+    );
+    assert_eq!(error("$[]"), "Expected a selector, found ']' at byte 2");
+    assert_eq!(error("$['a',]"), "Expected a selector, found ']' at byte 6");
+    assert_eq!(
+        error("$[01]"),
+        "Indices can't have leading zeros or be -0 at byte 2"
+    );
+    assert_eq!(
+        error("$[-0]"),
+        "Indices can't have leading zeros or be -0 at byte 2"
+    );
+    assert_eq!(
+        error("$[9007199254740992]"),
+        "Indices must be within ±9007199254740991 at byte 2"
+    );
+    assert_eq!(
+        error("$[1:2:3:4]"),
+        "Expected ',' or ']', found ':' at byte 7"
+    );
+    assert_eq!(error("$['a]"), "The string doesn't end at byte 2");
+    assert_eq!(error("$['\\x']"), "Unknown escape '\\x' at byte 3");
+    assert_eq!(
+        error("$['\\ud800']"),
+        "Unpaired surrogate in a \\u escape at byte 3"
+    );
+    assert_eq!(
+        error("$['\u{1}']"),
+        "Control character '\\u{1}' must be escaped at byte 3"
+    );
+    assert_eq!(
+        error("$[?@.a = 1]"),
+        "Expected ',' or ']', found '=' at byte 7"
+    );
+    assert_eq!(
+        error("$[?@.a == ]"),
+        "Expected a literal, a query or a function, found ']' at byte 10"
+    );
+    assert_eq!(error("$[?(@.a]"), "Expected ')', found ']' at byte 7");
+    assert_eq!(
+        error("$[?01 == 1]"),
+        "Numbers can't have leading zeros at byte 3"
+    );
+    assert_eq!(
+        error("$[?@ == 1.]"),
+        "Expected a digit, found ']' at byte 10"
+    );
+    assert_eq!(
+        error("$[?nothing(@)]"),
+        "Unknown function nothing() at byte 3"
+    );
+    assert_eq!(error("$[?length (@)]"), "Expected '(', found ' ' at byte 9");
+    let deep = format!("$[?{}@{}]", "(".repeat(100), ")".repeat(100));
+    assert_eq!(
+        error(&deep),
+        "Filters are nested more than 64 levels deep at byte 66"
+    );
+}
+
+#[test]
+fn type_errors() {
+    assert_eq!(error("$[?1]"), "Expected a test, found a literal at byte 3");
+    assert_eq!(
+        error("$[?true]"),
+        "Expected a test, found a literal at byte 3"
+    );
+    assert_eq!(
+        error("$[?@.a && 'b']"),
+        "Expected a test, found a literal at byte 10"
+    );
+    assert_eq!(
+        error("$[?length(@.a)]"),
+        "Expected a test, found a function returning a value, compare it at byte 3"
+    );
+    assert_eq!(
+        error("$[?@.* == 1]"),
+        "Expected a value, found a query which may select several nodes at byte 3"
+    );
+    assert_eq!(
+        error("$[?@..a == 1]"),
+        "Expected a value, found a query which may select several nodes at byte 3"
+    );
+    assert_eq!(
+        error("$[?match(@.a, 'b') == true]"),
+        "Expected a value, found a function which doesn't return a value at byte 3"
+    );
+    assert_eq!(
+        error("$[?(@.a) == 1]"),
+        "Expected a value, found a logical expression at byte 3"
+    );
+    assert_eq!(
+        error("$[?!@.a == 1]"),
+        "Expected ',' or ']', found '=' at byte 8"
+    );
+    assert_eq!(
+        error("$[?length(@.*) == 1]"),
+        "Expected a value, found a query which may select several nodes at byte 10"
+    );
+    assert_eq!(
+        error("$[?count(1) == 1]"),
+        "Expected a query as the argument of count() at byte 9"
+    );
+    assert_eq!(
+        error("$[?count(@.a, @.b) == 1]"),
+        "count() takes 1 argument at byte 14"
+    );
+    assert_eq!(
+        error("$[?match(@.a) == 1]"),
+        "match() takes 2 arguments at byte 3"
+    );
+    assert_eq!(
+        error("$[?length(@.a == 1) == 1]"),
+        "Expected a value, found a logical expression at byte 10"
+    );
+    // singular queries may be compared
+    assert!(JsonPath::parse("$[?@.a[0]['b'] == $.c[-1]]").is_ok());
+}
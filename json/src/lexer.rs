@@ -1,29 +1,139 @@
 mod cursor;
 
-use cursor::Cursor;
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::Range;
+
+pub(crate) use cursor::Cursor;
+use memchr::{memchr, memchr2, memchr_iter, memmem, memrchr};
+
+use crate::number::Number;
+use crate::ParseOptions;
+
+/// Lazy iterator over the tokens of a JSON text.
+///
+/// Malformed input doesn't stop the iteration: it's reported as
+/// [`TokenKind::Invalid`] and lexing goes on right after it.
+pub struct Lexer<'a> {
+    cursor: Cursor<'a>,
+    /// Position at the end of the previous token.
+    line: usize,
+    column: usize,
+    offset: usize,
+}
 
-#[derive(Debug)]
-pub(crate) struct Token {
-    pub(crate) kind: TokenKind,
-    pub(crate) line: usize,
-    pub(crate) column: usize,
+impl<'a> Lexer<'a> {
+    pub fn new(input: &'a str) -> Lexer<'a> {
+        Lexer::with_options(input, &ParseOptions::new())
+    }
+
+    pub fn with_options(input: &'a str, options: &ParseOptions) -> Lexer<'a> {
+        Lexer {
+            cursor: Cursor::new(input, options),
+            line: 1,
+            column: 0,
+            offset: 0,
+        }
+    }
 }
 
-impl Token {
-    fn new(kind: TokenKind, position: (usize, usize)) -> Token {
-        Token {
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let RawToken { kind, span } = self.cursor.eat_token()?;
+
+        let bytes = &self.cursor.input().as_bytes()[self.offset..span.end];
+        (self.line, self.column) = advance((self.line, self.column), bytes);
+        self.offset = span.end;
+
+        Some(Token {
             kind,
-            line: position.0,
-            column: position.1,
+            line: self.line,
+            column: self.column,
+            offset: span.start,
+            len: span.len(),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Token<'a> {
+    pub kind: TokenKind<'a>,
+    /// Line of the last character of the token, starting from 1.
+    pub line: usize,
+    /// Column of the last character of the token, starting from 1.
+    /// A newline ends up at column 0 of the next line.
+    pub column: usize,
+    /// Byte offset of the first character of the token.
+    pub offset: usize,
+    /// Length of the token in bytes.
+    pub len: usize,
+}
+
+/// Token without line and column, which the parser computes
+/// only for the token it reports an error at.
+#[derive(Debug)]
+pub(crate) struct RawToken<'a> {
+    pub(crate) kind: TokenKind<'a>,
+    pub(crate) span: Range<usize>,
+}
+
+/// Lazily lexes `input` for the parser, without tracking line and column.
+pub(crate) fn tokenize<'a>(input: &'a str, options: &ParseOptions) -> Cursor<'a> {
+    let mut cursor = Cursor::new(input, options);
+    cursor.skip_whitespace = true;
+    cursor
+}
+
+/// Returns line and column of the character right before `offset`,
+/// the same way [`Token`] counts them.
+pub(crate) fn line_column(input: &str, offset: usize) -> (usize, usize) {
+    advance((1, 0), &input.as_bytes()[..offset])
+}
+
+/// Moves the line and column of a position over `bytes`.
+pub(crate) fn advance((line, column): (usize, usize), bytes: &[u8]) -> (usize, usize) {
+    match memrchr(b'\n', bytes) {
+        Some(last_newline) => (
+            line + memchr_iter(b'\n', bytes).count(),
+            count_chars(&bytes[last_newline + 1..]),
+        ),
+        None => (line, column + count_chars(bytes)),
+    }
+}
+
+fn count_chars(bytes: &[u8]) -> usize {
+    // every byte except UTF-8 continuation bytes starts a char
+    bytes.iter().filter(|&&byte| (byte as i8) >= -0x40).count()
+}
+
+/// Finds the first byte below 0x20, checking eight bytes at a time.
+fn find_control_byte(bytes: &[u8]) -> Option<usize> {
+    const ONES: u64 = u64::from_ne_bytes([0x01; 8]);
+    const HIGH_BITS: u64 = u64::from_ne_bytes([0x80; 8]);
+
+    let mut checked = 0;
+    for chunk in bytes.chunks_exact(8) {
+        let word = u64::from_ne_bytes(chunk.try_into().unwrap());
+        // sets the high bit of a byte below 0x20, if there is any
+        if word.wrapping_sub(ONES * 0x20) & !word & HIGH_BITS != 0 {
+            break;
         }
-        // Token { kind }
+        checked += 8;
     }
+
+    bytes[checked..]
+        .iter()
+        .position(|&byte| byte < 0x20)
+        .map(|index| checked + index)
 }
 
-#[derive(Debug, PartialEq)]
-pub(crate) enum TokenKind {
-    String(String),
-    Number(f64),
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind<'a> {
+    /// Borrowed from the input unless the literal has escapes.
+    String(Cow<'a, str>),
+    Number(Number),
     True,
     False,
 
@@ -31,8 +141,10 @@ pub(crate) enum TokenKind {
     Colon,
     // done
     Comma,
-    // done
+    // a whole run of them
     Whitespace,
+    /// Only with [`ParseOptions::allow_comments`].
+    Comment,
 
     // done
     OpenCurly,
@@ -50,21 +162,79 @@ pub(crate) enum TokenKind {
     Invalid(TokenizeError),
 }
 
-#[derive(Debug, PartialEq)]
-pub(crate) enum TokenizeError {
+impl TokenKind<'_> {
+    pub fn into_owned(self) -> TokenKind<'static> {
+        match self {
+            TokenKind::String(string) => TokenKind::String(Cow::Owned(string.into_owned())),
+            TokenKind::Number(number) => TokenKind::Number(number),
+            TokenKind::True => TokenKind::True,
+            TokenKind::False => TokenKind::False,
+            TokenKind::Colon => TokenKind::Colon,
+            TokenKind::Comma => TokenKind::Comma,
+            TokenKind::Whitespace => TokenKind::Whitespace,
+            TokenKind::Comment => TokenKind::Comment,
+            TokenKind::OpenCurly => TokenKind::OpenCurly,
+            TokenKind::ClosedCurly => TokenKind::ClosedCurly,
+            TokenKind::OpenBracket => TokenKind::OpenBracket,
+            TokenKind::ClosedBracket => TokenKind::ClosedBracket,
+            TokenKind::Null => TokenKind::Null,
+            TokenKind::Invalid(error) => TokenKind::Invalid(error),
+        }
+    }
+}
+
+/// Describes the token in a few words, e.g. `closed bracket` or `number 12`.
+impl fmt::Display for TokenKind<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenKind::String(string) => write!(f, "string {string:?}"),
+            TokenKind::Number(number) => write!(f, "number {number}"),
+            TokenKind::True => write!(f, "'true'"),
+            TokenKind::False => write!(f, "'false'"),
+            TokenKind::Colon => write!(f, "colon"),
+            TokenKind::Comma => write!(f, "comma"),
+            TokenKind::Whitespace => write!(f, "whitespace"),
+            TokenKind::Comment => write!(f, "comment"),
+            TokenKind::OpenCurly => write!(f, "open curly"),
+            TokenKind::ClosedCurly => write!(f, "closed curly"),
+            TokenKind::OpenBracket => write!(f, "open bracket"),
+            TokenKind::ClosedBracket => write!(f, "closed bracket"),
+            TokenKind::Null => write!(f, "'null'"),
+            TokenKind::Invalid(_) => write!(f, "invalid token"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenizeError {
     MetEndOfFile,
 
-    InvalidUnicode(String),
     MissingDoubleQuote(String),
+    NumberOutOfRange(String),
 
     InvalidUnicodeChar(char),
+    UnpairedHighSurrogate(u16),
+    UnpairedLowSurrogate(u16),
     NoSuchToken(char),
     NoSuchEscapeSymbol(char),
+    /// U+0000..=U+001F found in a string literal in strict mode.
+    UnescapedControlChar {
+        char: char,
+        line: usize,
+        column: usize,
+    },
     ExpectedDigit(char),
     ExpectedDot(char),
+    ExpectedExponentSignOrDigit(char),
+    ExpectedExponentDigit(char),
     ExpectedTrue(char),
     ExpectedFalse(char),
     ExpectedNull(char),
+    /// A `/*` comment without `*/`.
+    UnterminatedComment,
+    /// A string longer than [`ParseOptions::max_string_len`] once unescaped.
+    /// The token ends with the character or escape which crosses the limit.
+    StringTooLong,
 }
 
 enum NumberState {
@@ -73,291 +243,467 @@ enum NumberState {
     IntegerPart,
     Mantissa,
     Dot,
-}
-
-enum StringState {
-    String,
-    Escape,
+    Exponent,
+    ExponentSign,
+    ExponentPart,
 }
 
 pub(crate) struct NumberContext {
-    number: f64,
-    fraction: f64,
-    first_char: char,
     state: NumberState,
 }
 
-pub(crate) struct StringContext {
-    string: String,
-    state: StringState,
-}
-
 impl NumberContext {
-    fn new(first_char: char) -> NumberContext {
+    fn new(first_byte: u8) -> NumberContext {
         NumberContext {
-            number: if let Some(digit) = first_char.to_digit(10) {
-                f64::from(digit)
-            } else {
-                0.0
-            },
-            fraction: 0.1,
-            first_char,
-            state: match first_char {
-                '-' => NumberState::Sign,
-                '0' => NumberState::LeadingZero,
-                '1'..='9' => NumberState::IntegerPart,
+            state: match first_byte {
+                b'-' => NumberState::Sign,
+                b'0' => NumberState::LeadingZero,
+                b'1'..=b'9' => NumberState::IntegerPart,
                 _ => unreachable!(),
             },
         }
     }
 
-    fn number_sign(self) -> f64 {
-        if self.first_char == '-' {
-            -self.number
-        } else {
-            self.number
-        }
-    }
-
-    fn push_integer_digit(&mut self, num: char) {
-        let digit = f64::from(num.to_digit(10).unwrap());
-
-        // -123
-        // first_char = '-' = -0.0
-        //  -0.0 * 10 =   -0.0 + (-1.0) = -1.0
-        //  -1.0 * 10 =  -10.0 + (-2.0) = -12.0
-        // -12.0 * 10 = -120.0 + (-3.0) = -123.0
-        // -(12.0 * 10 + (3.0)) = -123.0
-        // number * 10 - digit
-
-        // 123
-        // first_char = '1' = 1.0
-        //   1.0 * 10 =   10.0 + (2.0) = 12.0
-        //  12.0 * 10 =  120.0 + (3.0) = 123.0
-        // number * 10 + digit
+    /// Converts the lexeme once the state machine has accepted it.
+    /// The value is parsed from the digits as a whole to be correctly rounded.
+    fn finish(self, lexeme: &str) -> TokenKind<'static> {
+        let is_float = matches!(
+            self.state,
+            NumberState::Mantissa | NumberState::ExponentPart
+        );
 
-        // suggestion: we know the first character of the number -
-        // - we can make it negative at the end of the function or
-        // leave it unchanged: if first_char == '-' {
-        //    return -number
-        // } else {
-        //    return number
-        // }
-        self.number = self.number.mul_add(10.0, digit);
+        match Number::from_lexeme(lexeme, is_float) {
+            Some(number) => TokenKind::Number(number),
+            None => TokenKind::Invalid(TokenizeError::NumberOutOfRange(lexeme.to_owned())),
+        }
     }
+}
 
-    fn push_mantissa_digit(&mut self, num: char) {
-        let digit = f64::from(num.to_digit(10).unwrap());
+impl<'a> Iterator for Cursor<'a> {
+    type Item = RawToken<'a>;
 
-        self.number = self.fraction.mul_add(digit, self.number);
-        self.fraction *= 0.1;
+    fn next(&mut self) -> Option<RawToken<'a>> {
+        self.eat_token()
     }
 }
 
-impl StringContext {
-    fn new() -> StringContext {
-        StringContext {
-            string: "".to_owned(),
-            state: StringState::String,
+impl<'a> Cursor<'a> {
+    /// Parses a token from the input string.
+    pub fn eat_token(&mut self) -> Option<RawToken<'a>> {
+        //     token_start  position
+        //           |      |
+        //           v      v
+        // {"mare": "Rarity", "cute": true}
+        //           \------/
+        //          token_str()
+
+        if self.skip_whitespace {
+            self.eat_whitespace();
+            while self.options().comments && self.eat_comment() {
+                self.eat_whitespace();
+            }
+            self.reset_token_len();
         }
-    }
-}
 
-impl Cursor<'_> {
-    /// Parses a token from the input string.
-    pub fn eat_token(&mut self) -> Option<Token> {
-        // struct Cursor { len_remaining, chars }
-        //
-        //    token_len_and_remaining = 9|remaining = chars.as_str().len() = 5
-        //      /---|----\
-        // Ford,Mare,1950
-        //      |   ^
-        //      |   chars.next() -> Some(',')
-        //      |
-        //      ^- (last reset position)
-        //         token_len = token_len_and_remainging - chars.as_str().len() = 4
-        //
-
-        let first_char = self.eat_char()?;
-
-        let token_kind = match first_char {
-            '{' => TokenKind::OpenCurly,
-            '}' => TokenKind::ClosedCurly,
-            '[' => TokenKind::OpenBracket,
-            ']' => TokenKind::ClosedBracket,
-
-            ':' => TokenKind::Colon,
-            ',' => TokenKind::Comma,
+        let first_byte = self.peek_first()?;
+
+        let token_kind = match first_byte {
+            b' ' | b'\t' | b'\n' | b'\r' => {
+                self.eat_whitespace();
+                TokenKind::Whitespace
+            }
+
+            b'{' | b'}' | b'[' | b']' | b':' | b',' => {
+                self.bump();
+                match first_byte {
+                    b'{' => TokenKind::OpenCurly,
+                    b'}' => TokenKind::ClosedCurly,
+                    b'[' => TokenKind::OpenBracket,
+                    b']' => TokenKind::ClosedBracket,
+                    b':' => TokenKind::Colon,
+                    _ => TokenKind::Comma,
+                }
+            }
 
             // null, true, false
-            'n' => self.eat_bool_or_null(TokenKind::Null, "ull"),
-            't' => self.eat_bool_or_null(TokenKind::True, "rue"),
-            'f' => self.eat_bool_or_null(TokenKind::False, "alse"),
+            b'n' => self.eat_bool_or_null(TokenKind::Null, b"null"),
+            b't' => self.eat_bool_or_null(TokenKind::True, b"true"),
+            b'f' => self.eat_bool_or_null(TokenKind::False, b"false"),
 
             // Number
-            '0'..='9' | '-' => self.eat_number(first_char),
+            b'0'..=b'9' | b'-' => {
+                self.bump();
+                self.eat_number(first_byte)
+            }
 
             // String
-            '"' => self.eat_string(),
+            b'"' => {
+                self.bump();
+                self.eat_string()
+            }
 
-            first_char => {
-                if first_char.is_whitespace() {
-                    TokenKind::Whitespace
+            // // line or /* block */ comment
+            b'/' if self.options().comments => {
+                if self.eat_comment() {
+                    TokenKind::Comment
+                } else if self.peek_second() == Some(b'*') {
+                    self.skip(self.rest().len());
+                    TokenKind::Invalid(TokenizeError::UnterminatedComment)
                 } else {
-                    TokenKind::Invalid(TokenizeError::NoSuchToken(first_char))
+                    self.bump();
+                    TokenKind::Invalid(TokenizeError::NoSuchToken('/'))
                 }
             }
+
+            // Only the four JSON whitespace characters are allowed
+            // between tokens, not NBSP or U+2028.
+            _ => {
+                let first_char = self.eat_char().unwrap();
+                TokenKind::Invalid(TokenizeError::NoSuchToken(first_char))
+            }
+        };
+        let res = RawToken {
+            kind: token_kind,
+            span: self.token_offset()..self.offset(),
         };
-        let res = Token::new(token_kind, self.get_position());
         self.reset_token_len();
         Some(res)
     }
 
-    fn eat_string(&mut self) -> TokenKind {
-        let mut context = StringContext::new();
+    /// Consumes a run of spaces, tabs and newlines.
+    fn eat_whitespace(&mut self) {
+        let count = self
+            .rest()
+            .iter()
+            .take_while(|&&byte| matches!(byte, b' ' | b'\t' | b'\n' | b'\r'))
+            .count();
+        self.skip(count);
+    }
+
+    /// Consumes a comment if a complete one starts here.
+    /// A line comment doesn't include the newline.
+    fn eat_comment(&mut self) -> bool {
+        let len = match self.rest() {
+            [b'/', b'/', tail @ ..] => 2 + memchr(b'\n', tail).unwrap_or(tail.len()),
+            [b'/', b'*', tail @ ..] => match memmem::find(tail, b"*/") {
+                Some(end) => 2 + end + 2,
+                None => return false,
+            },
+            _ => return false,
+        };
+        self.skip(len);
+        true
+    }
+
+    fn eat_string(&mut self) -> TokenKind<'a> {
+        // stays `None` until the first escape, while the literal
+        // can still be borrowed straight from the input
+        let mut unescaped: Option<String> = None;
+        let max_len = self.options().max_string_len;
         loop {
-            let char = self.eat_char();
-            match (&context.state, char) {
-                // \
-                (StringState::String, Some('\\')) => {
-                    context.state = StringState::Escape;
+            let len = match &unescaped {
+                Some(string) => string.len(),
+                None => self.offset() - self.token_offset() - 1,
+            };
+            if len > max_len {
+                return TokenKind::Invalid(TokenizeError::StringTooLong);
+            }
+
+            // everything up to the next quote or backslash is taken as is
+            let rest = self.rest();
+            let mut end = memchr2(b'"', b'\\', rest).unwrap_or(rest.len());
+            // stop after the character which crosses the limit
+            let too_long = end > max_len - len;
+            if too_long {
+                end = max_len - len + 1;
+                while !self.input().is_char_boundary(self.offset() + end) {
+                    end += 1;
                 }
+            }
+
+            if self.options().strict_strings {
+                if let Some(index) = find_control_byte(&rest[..end]) {
+                    self.skip(index);
+                    // counted up to the character before, so that a newline
+                    // is reported on its own line
+                    let (line, column) = line_column(self.input(), self.offset());
+                    self.bump();
+                    return TokenKind::Invalid(TokenizeError::UnescapedControlChar {
+                        char: char::from(rest[index]),
+                        line,
+                        column: column + 1,
+                    });
+                }
+            }
+
+            if let Some(string) = &mut unescaped {
+                let position = self.offset();
+                string.push_str(&self.input()[position..position + end]);
+            }
+            self.skip(end);
+            if too_long {
+                return TokenKind::Invalid(TokenizeError::StringTooLong);
+            }
+
+            match self.peek_first() {
                 // "
-                (StringState::String, Some('"')) => return TokenKind::String(context.string),
-                //
-                (StringState::String, Some(char)) => {
-                    context.string.push(char);
-                    context.state = StringState::String;
+                Some(b'"') => {
+                    self.bump();
+                    let string = match unescaped {
+                        Some(string) => Cow::Owned(string),
+                        None => {
+                            let lexeme = self.token_str();
+                            Cow::Borrowed(&lexeme[1..lexeme.len() - 1])
+                        }
+                    };
+                    return TokenKind::String(string);
                 }
-                // \"
-                (StringState::Escape, Some(char)) => {
+                // \
+                Some(_) => {
+                    let string = unescaped.get_or_insert_with(|| {
+                        // everything between the opening quote and the backslash
+                        self.token_str()[1..].to_owned()
+                    });
+                    self.bump();
+
+                    let Some(char) = self.eat_char() else {
+                        let string = std::mem::take(string);
+                        return TokenKind::Invalid(TokenizeError::MissingDoubleQuote(string));
+                    };
+
                     let unescaped = match char {
                         '"' => '"',
                         '\\' => '\\',
                         '/' => '/',        // solidus: '\/'
-                        'b' => '\u{232B}', // backspace
+                        'b' => '\u{0008}', // backspace
                         'f' => '\u{000C}', // formfeed
                         'n' => '\n',
                         't' => '\t',
                         'r' => '\r',
                         'u' => {
-                            let mut buf = "".to_owned();
-
-                            for _ in 0..4 {
-                                let Some(char) = self.peek_first() else {
-                                    return TokenKind::Invalid(TokenizeError::MetEndOfFile)
-                                };
-
-                                if !char.is_ascii_hexdigit() {
-                                    return TokenKind::Invalid(TokenizeError::InvalidUnicodeChar(
-                                        char,
-                                    ));
-                                }
-
-                                buf.push(char);
-                                self.eat_char();
+                            if let Err(error) = self.eat_unicode_escape(string) {
+                                return TokenKind::Invalid(error);
                             }
-
-                            let Ok(unicode) = u32::from_str_radix(&buf, 16) else {
-                                return TokenKind::Invalid(TokenizeError::InvalidUnicode(buf))
-                            };
-
-                            let Some(unicode_char) = char::from_u32(unicode) else {
-                                return TokenKind::Invalid(TokenizeError::InvalidUnicode(buf))
-                            };
-                            unicode_char
+                            continue;
                         }
                         _ => return TokenKind::Invalid(TokenizeError::NoSuchEscapeSymbol(char)),
                     };
-                    context.string.push(unescaped);
-                    context.state = StringState::String;
+                    string.push(unescaped);
                 }
                 // "str\EOF
-                (StringState::String | StringState::Escape, None) => {
-                    return TokenKind::Invalid(TokenizeError::MissingDoubleQuote(context.string))
+                None => {
+                    let string = match unescaped {
+                        Some(string) => string,
+                        None => self.token_str()[1..].to_owned(),
+                    };
+                    return TokenKind::Invalid(TokenizeError::MissingDoubleQuote(string));
                 }
             }
         }
     }
 
-    fn eat_number(&mut self, first_char: char) -> TokenKind {
-        // TODO: scientific notation | binary OR hexadecimal form
-        let mut context = NumberContext::new(first_char);
+    /// Moves past the rest of a string cut short by [`TokenizeError::StringTooLong`],
+    /// up to its closing quote or the end of the input.
+    pub(crate) fn skip_string_rest(&mut self) {
+        loop {
+            let rest = self.rest();
+            let Some(index) = memchr2(b'"', b'\\', rest) else {
+                self.skip(rest.len());
+                break;
+            };
+            self.skip(index);
+            self.bump();
+            // the character after a backslash is escaped
+            if rest[index] == b'"' || self.eat_char().is_none() {
+                break;
+            }
+        }
+        self.reset_token_len();
+    }
 
+    /// Decodes the `XXXX` part of a `\uXXXX` escape. A high surrogate
+    /// must be immediately followed by an escaped low one.
+    fn eat_unicode_escape(&mut self, string: &mut String) -> Result<(), TokenizeError> {
+        let mut unit = self.eat_utf16_unit()?;
         loop {
-            let char = self.peek_first();
-            match (&context.state, char) {
+            match unit {
+                0xD800..=0xDBFF => {
+                    if self.peek_first() != Some(b'\\') || self.peek_second() != Some(b'u') {
+                        return self
+                            .lone_surrogate(string, TokenizeError::UnpairedHighSurrogate(unit));
+                    }
+                    self.bump();
+                    self.bump();
+
+                    let low = self.eat_utf16_unit()?;
+                    if let 0xDC00..=0xDFFF = low {
+                        let high_bits = u32::from(unit - 0xD800) << 10;
+                        let low_bits = u32::from(low - 0xDC00);
+                        string.push(char::from_u32(0x10000 + (high_bits | low_bits)).unwrap());
+                        return Ok(());
+                    }
+
+                    self.lone_surrogate(string, TokenizeError::UnpairedHighSurrogate(unit))?;
+                    // the second escape is decoded on its own
+                    unit = low;
+                }
+                0xDC00..=0xDFFF => {
+                    return self.lone_surrogate(string, TokenizeError::UnpairedLowSurrogate(unit));
+                }
+                _ => {
+                    string.push(char::from_u32(u32::from(unit)).unwrap());
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    fn eat_utf16_unit(&mut self) -> Result<u16, TokenizeError> {
+        let mut unit = 0;
+
+        for _ in 0..4 {
+            let Some(byte) = self.peek_first() else {
+                return Err(TokenizeError::MetEndOfFile);
+            };
+
+            let Some(digit) = char::from(byte).to_digit(16) else {
+                return Err(TokenizeError::InvalidUnicodeChar(self.peek_char().unwrap()));
+            };
+
+            unit = unit << 4 | digit as u16;
+            self.bump();
+        }
+
+        Ok(unit)
+    }
+
+    fn lone_surrogate(
+        &self,
+        string: &mut String,
+        error: TokenizeError,
+    ) -> Result<(), TokenizeError> {
+        if self.options().lossy_unicode {
+            string.push(char::REPLACEMENT_CHARACTER);
+            return Ok(());
+        }
+        Err(error)
+    }
+
+    fn eat_number(&mut self, first_byte: u8) -> TokenKind<'a> {
+        let mut context = NumberContext::new(first_byte);
+
+        loop {
+            let byte = self.peek_first();
+            match (&context.state, byte) {
                 // -0
-                (NumberState::Sign, Some('0')) => {
+                (NumberState::Sign, Some(b'0')) => {
                     context.state = NumberState::LeadingZero;
                 }
 
                 // -1..=9
-                (NumberState::Sign, Some(num @ '1'..='9')) => {
+                (NumberState::Sign, Some(b'1'..=b'9')) => {
                     context.state = NumberState::IntegerPart;
-                    context.push_integer_digit(num);
                 }
 
                 // 0. | // 0..=9 .
-                (NumberState::LeadingZero | NumberState::IntegerPart, Some('.')) => {
+                (NumberState::LeadingZero | NumberState::IntegerPart, Some(b'.')) => {
                     context.state = NumberState::Dot;
                 }
 
                 // .0..=9
-                (NumberState::Dot, Some(num @ '0'..='9')) => {
+                (NumberState::Dot, Some(b'0'..=b'9')) => {
                     context.state = NumberState::Mantissa;
-                    context.push_mantissa_digit(num);
                 }
 
-                // 0..=9 0..=9
-                (NumberState::IntegerPart, Some(num @ '0'..='9')) => {
-                    context.push_integer_digit(num);
+                // 0..=9 0..=9 | .0..=9 0..=9
+                (NumberState::IntegerPart | NumberState::Mantissa, Some(b'0'..=b'9')) => {}
+
+                // 0e | 1..=9E | 0..=9.0..=9e
+                (
+                    NumberState::LeadingZero | NumberState::IntegerPart | NumberState::Mantissa,
+                    Some(b'e' | b'E'),
+                ) => {
+                    context.state = NumberState::Exponent;
+                }
+
+                // e+ | e-
+                (NumberState::Exponent, Some(b'+' | b'-')) => {
+                    context.state = NumberState::ExponentSign;
                 }
 
-                // 0..=9: 0..=9
-                (NumberState::Mantissa, Some(num @ '0'..='9')) => {
-                    context.push_mantissa_digit(num);
+                // e0..=9 | e+0..=9 | e0..=9 0..=9
+                (
+                    NumberState::Exponent | NumberState::ExponentSign | NumberState::ExponentPart,
+                    Some(b'0'..=b'9'),
+                ) => {
+                    context.state = NumberState::ExponentPart;
                 }
 
                 // -AnyChar | .AnyChar | 0{0, 1..=9}
                 // -K, .k, 01
-                (NumberState::Sign | NumberState::Dot, Some(char)) => {
-                    return TokenKind::Invalid(TokenizeError::ExpectedDigit(char))
+                (NumberState::Sign | NumberState::Dot, Some(_)) => {
+                    let char = self.peek_char().unwrap();
+                    return TokenKind::Invalid(TokenizeError::ExpectedDigit(char));
                 }
 
-                (NumberState::Sign | NumberState::Dot, None) => {
-                    return TokenKind::Invalid(TokenizeError::MetEndOfFile)
+                // eK, E.
+                (NumberState::Exponent, Some(_)) => {
+                    let char = self.peek_char().unwrap();
+                    return TokenKind::Invalid(TokenizeError::ExpectedExponentSignOrDigit(char));
                 }
 
-                (NumberState::LeadingZero, Some(char @ '0'..='9')) => {
-                    return TokenKind::Invalid(TokenizeError::ExpectedDot(char))
+                // e+K, E-.
+                (NumberState::ExponentSign, Some(_)) => {
+                    let char = self.peek_char().unwrap();
+                    return TokenKind::Invalid(TokenizeError::ExpectedExponentDigit(char));
+                }
+
+                (
+                    NumberState::Sign
+                    | NumberState::Dot
+                    | NumberState::Exponent
+                    | NumberState::ExponentSign,
+                    None,
+                ) => return TokenKind::Invalid(TokenizeError::MetEndOfFile),
+
+                (NumberState::LeadingZero, Some(digit @ b'0'..=b'9')) => {
+                    return TokenKind::Invalid(TokenizeError::ExpectedDot(char::from(digit)))
                 }
 
                 // .0..=9
                 (
-                    NumberState::Mantissa | NumberState::IntegerPart | NumberState::LeadingZero,
+                    NumberState::Mantissa
+                    | NumberState::IntegerPart
+                    | NumberState::LeadingZero
+                    | NumberState::ExponentPart,
                     _,
                 ) => {
-                    return TokenKind::Number(context.number_sign());
+                    return context.finish(self.token_str());
                 }
             }
-            self.eat_char();
+            self.bump();
         }
     }
 
-    fn eat_bool_or_null(&mut self, kind: TokenKind, expected: &str) -> TokenKind {
-        for expected_char in expected.chars() {
-            let char = if let Some(char) = self.peek_first() {
-                char
-            } else {
+    fn eat_bool_or_null(&mut self, kind: TokenKind<'a>, expected: &[u8]) -> TokenKind<'a> {
+        if self.rest().starts_with(expected) {
+            self.skip(expected.len());
+            return kind;
+        }
+
+        // the first letter is already known to match
+        self.bump();
+        for &expected_byte in &expected[1..] {
+            let Some(byte) = self.peek_first() else {
                 return TokenKind::Invalid(TokenizeError::MetEndOfFile);
             };
 
-            if char == expected_char {
-                self.eat_char();
+            if byte == expected_byte {
+                self.bump();
                 continue;
             }
 
+            let char = self.peek_char().unwrap();
             let error = match kind {
                 TokenKind::True => TokenizeError::ExpectedTrue(char),
                 TokenKind::False => TokenizeError::ExpectedFalse(char),
@@ -366,20 +712,8 @@ impl Cursor<'_> {
             };
             return TokenKind::Invalid(error);
         }
-        kind
-    }
-}
-
-// Box<[Token]>
-pub(crate) fn tokenize(string: &str) -> Vec<Token> {
-    let mut tokens = vec![];
-    let mut cursor = Cursor::new(string);
-
-    while let Some(token) = cursor.eat_token() {
-        tokens.push(token);
+        unreachable!("the whole literal matched")
     }
-
-    tokens
 }
 
 #[cfg(test)]
@@ -393,24 +727,85 @@ mod tests {
         // assert_snapshot("-1", "Number(-1.0)");
         assert_snapshot("0.", "{Invalid(MetEndOfFile)|L1:C2}");
         // assert_snapshot(".9", "Invalid");
-        assert_snapshot("10", "{Number(10.0)|L1:C2}");
+        assert_snapshot("10", "{Number(10)|L1:C2}");
         assert_snapshot("1.1", "{Number(1.1)|L1:C3}");
-        assert_snapshot("0", "{Number(0.0)|L1:C1}");
+        assert_snapshot("0", "{Number(0)|L1:C1}");
 
         assert_snapshot("10.250", "{Number(10.25)|L1:C6}");
         assert_snapshot("-0.01", "{Number(-0.01)|L1:C5}");
-        assert_snapshot("-100.000001", "{Number(-100.0)|L1:C11}");
+        assert_snapshot("-100.000001", "{Number(-100.000001)|L1:C11}");
         assert_snapshot(
             "[100.200]",
             "{OpenBracket|L1:C1},{Number(100.2)|L1:C8},{ClosedBracket|L1:C9}",
         );
         assert_snapshot(
             "1-00",
-            "{Number(1.0)|L1:C1},{Invalid(ExpectedDot('0'))|L1:C3},{Number(0.0)|L1:C4}",
+            "{Number(1)|L1:C1},{Invalid(ExpectedDot('0'))|L1:C3},{Number(0)|L1:C4}",
         );
         assert_snapshot("-201.102", "{Number(-201.102)|L1:C8}");
     }
 
+    #[test]
+    fn number_state_transitions() {
+        let table = [
+            // Sign
+            ("-0", "{Number(-0.0)|L1:C2}"),
+            ("-7", "{Number(-7)|L1:C2}"),
+            ("-a", "{Invalid(ExpectedDigit('a'))|L1:C1},{Invalid(NoSuchToken('a'))|L1:C2}"),
+            ("-", "{Invalid(MetEndOfFile)|L1:C1}"),
+            // LeadingZero
+            ("0.5", "{Number(0.5)|L1:C3}"),
+            ("0e1", "{Number(0.0)|L1:C3}"),
+            ("01", "{Invalid(ExpectedDot('1'))|L1:C1},{Number(1)|L1:C2}"),
+            ("0,", "{Number(0)|L1:C1},{Comma|L1:C2}"),
+            // IntegerPart
+            ("123", "{Number(123)|L1:C3}"),
+            ("12.5", "{Number(12.5)|L1:C4}"),
+            ("1E2", "{Number(100.0)|L1:C3}"),
+            ("7]", "{Number(7)|L1:C1},{ClosedBracket|L1:C2}"),
+            // Dot
+            ("1.e", "{Invalid(ExpectedDigit('e'))|L1:C2},{Invalid(NoSuchToken('e'))|L1:C3}"),
+            ("1.", "{Invalid(MetEndOfFile)|L1:C2}"),
+            // Mantissa
+            ("1.25", "{Number(1.25)|L1:C4}"),
+            ("1.5e1", "{Number(15.0)|L1:C5}"),
+            ("1.5}", "{Number(1.5)|L1:C3},{ClosedCurly|L1:C4}"),
+            // Exponent
+            ("1e+2", "{Number(100.0)|L1:C4}"),
+            ("25e-1", "{Number(2.5)|L1:C5}"),
+            ("6e2", "{Number(600.0)|L1:C3}"),
+            (
+                "1eK",
+                "{Invalid(ExpectedExponentSignOrDigit('K'))|L1:C2},{Invalid(NoSuchToken('K'))|L1:C3}",
+            ),
+            ("1E", "{Invalid(MetEndOfFile)|L1:C2}"),
+            // ExponentSign
+            (
+                "1e+x",
+                "{Invalid(ExpectedExponentDigit('x'))|L1:C3},{Invalid(NoSuchToken('x'))|L1:C4}",
+            ),
+            ("1e-", "{Invalid(MetEndOfFile)|L1:C3}"),
+            // ExponentPart
+            ("1e10", "{Number(10000000000.0)|L1:C4}"),
+            ("1e2,", "{Number(100.0)|L1:C3},{Comma|L1:C4}"),
+        ];
+
+        for (string, expected) in table {
+            assert_snapshot(string, expected);
+        }
+    }
+
+    #[test]
+    fn scientific_notation() {
+        assert_snapshot("6.02E23", "{Number(6.02e23)|L1:C7}");
+        assert_snapshot("-1.5e+3", "{Number(-1500.0)|L1:C7}");
+        #[cfg(not(feature = "arbitrary_precision"))]
+        assert_snapshot("1e400", "{Invalid(NumberOutOfRange(\"1e400\"))|L1:C5}");
+        #[cfg(feature = "arbitrary_precision")]
+        assert_snapshot("1e400", "{Number(inf)|L1:C5}");
+        assert_snapshot("1e-400", "{Number(0.0)|L1:C6}");
+    }
+
     #[test]
     fn smoke_string() {
         assert_snapshot("\"abcd\"", "{String(\"abcd\")|L1:C6}");
@@ -423,34 +818,318 @@ mod tests {
         assert_snapshot("\"abcd", "{Invalid(MissingDoubleQuote(\"abcd\"))|L1:C5}");
     }
 
+    #[test]
+    fn escapes() {
+        assert_snapshot(
+            r#""\"\\\/\b\f\n\r\t""#,
+            r#"{String("\"\\/\u{8}\u{c}\n\r\t")|L1:C18}"#,
+        );
+        assert_snapshot(
+            r#""\a""#,
+            "{Invalid(NoSuchEscapeSymbol('a'))|L1:C3},{Invalid(MissingDoubleQuote(\"\"))|L1:C4}",
+        );
+    }
+
+    #[test]
+    fn control_chars() {
+        assert_snapshot("\"a\tb\"", "{String(\"a\\tb\")|L1:C5}");
+
+        let options = ParseOptions::new().strict_strings(true);
+        assert_snapshot_with("\"a\"", &options, "{String(\"a\")|L1:C3}");
+        assert_snapshot_with(
+            "\"a\tb\"",
+            &options,
+            "{Invalid(UnescapedControlChar { char: '\\t', line: 1, column: 3 })|L1:C3},{Invalid(NoSuchToken('b'))|L1:C4},{Invalid(MissingDoubleQuote(\"\"))|L1:C5}",
+        );
+        assert_snapshot_with(
+            "\"a\nb\"",
+            &options,
+            "{Invalid(UnescapedControlChar { char: '\\n', line: 1, column: 3 })|L2:C0},{Invalid(NoSuchToken('b'))|L2:C1},{Invalid(MissingDoubleQuote(\"\"))|L2:C2}",
+        );
+        assert_snapshot_with(
+            "\"\u{1f}\u{7f}\"",
+            &options,
+            "{Invalid(UnescapedControlChar { char: '\\u{1f}', line: 1, column: 2 })|L1:C2},{Invalid(NoSuchToken('\\u{7f}'))|L1:C3},{Invalid(MissingDoubleQuote(\"\"))|L1:C4}",
+        );
+        assert_snapshot_with("\"\u{7f}\"", &options, "{String(\"\\u{7f}\")|L1:C3}");
+    }
+
+    #[test]
+    fn surrogate_pairs() {
+        assert_snapshot(r#""\uD83D\uDE00""#, "{String(\"😀\")|L1:C14}");
+        assert_snapshot(r#""\ud834\udd1e""#, "{String(\"𝄞\")|L1:C14}");
+        assert_snapshot(r#""\u00e9""#, "{String(\"é\")|L1:C8}");
+
+        assert_snapshot(
+            r#""\uD83D""#,
+            "{Invalid(UnpairedHighSurrogate(55357))|L1:C7},{Invalid(MissingDoubleQuote(\"\"))|L1:C8}",
+        );
+        assert_snapshot(
+            r#""\uD83Dx""#,
+            "{Invalid(UnpairedHighSurrogate(55357))|L1:C7},{Invalid(NoSuchToken('x'))|L1:C8},{Invalid(MissingDoubleQuote(\"\"))|L1:C9}",
+        );
+        assert_snapshot(
+            r#""\uD83D\u0041""#,
+            "{Invalid(UnpairedHighSurrogate(55357))|L1:C13},{Invalid(MissingDoubleQuote(\"\"))|L1:C14}",
+        );
+        assert_snapshot(
+            r#""\uDE00""#,
+            "{Invalid(UnpairedLowSurrogate(56832))|L1:C7},{Invalid(MissingDoubleQuote(\"\"))|L1:C8}",
+        );
+        assert_snapshot(
+            r#""\uD83D\uDE""#,
+            "{Invalid(InvalidUnicodeChar('\"'))|L1:C11},{Invalid(MissingDoubleQuote(\"\"))|L1:C12}",
+        );
+    }
+
+    #[test]
+    fn lossy_surrogates() {
+        let options = ParseOptions::new().lossy_unicode(true);
+
+        assert_snapshot_with(r#""\uD83D\uDE00""#, &options, "{String(\"😀\")|L1:C14}");
+        assert_snapshot_with(r#""a\uD83Db""#, &options, "{String(\"a�b\")|L1:C10}");
+        assert_snapshot_with(r#""\uDE00\uD83D""#, &options, "{String(\"��\")|L1:C14}");
+        assert_snapshot_with(
+            r#""\uD83D\uD83D\uDE00""#,
+            &options,
+            "{String(\"�😀\")|L1:C20}",
+        );
+        assert_snapshot_with(r#""\uD83D\u0041""#, &options, "{String(\"�A\")|L1:C14}");
+    }
+
+    #[test]
+    fn smoke_span() {
+        let spans: Vec<_> = Lexer::new("{\"пони\": [1.5e3, null]}")
+            .map(|token| (token.offset, token.len))
+            .collect();
+
+        assert_eq!(
+            spans,
+            [
+                (0, 1),
+                (1, 10),
+                (11, 1),
+                (12, 1),
+                (13, 1),
+                (14, 5),
+                (19, 1),
+                (20, 1),
+                (21, 4),
+                (25, 1),
+                (26, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn borrowed_strings() {
+        let kinds: Vec<_> = Lexer::new(r#""plain" "esc\\aped" "tail\n""#)
+            .map(|token| token.kind)
+            .filter(|kind| *kind != TokenKind::Whitespace)
+            .collect();
+
+        assert!(matches!(
+            &kinds[0],
+            TokenKind::String(Cow::Borrowed("plain"))
+        ));
+        assert!(
+            matches!(&kinds[1], TokenKind::String(Cow::Owned(string)) if string == "esc\\aped")
+        );
+        assert!(matches!(&kinds[2], TokenKind::String(Cow::Owned(string)) if string == "tail\n"));
+    }
+
+    #[test]
+    fn lazy() {
+        let mut lexer = Lexer::new("[true");
+
+        assert_eq!(
+            lexer.next().map(|token| token.kind),
+            Some(TokenKind::OpenBracket)
+        );
+        assert_eq!(lexer.next().map(|token| token.kind), Some(TokenKind::True));
+        assert_eq!(lexer.next().map(|token| token.kind), None);
+    }
+
+    #[test]
+    fn control_byte_search() {
+        let mut bytes = [b'a'; 40];
+        assert_eq!(find_control_byte(&bytes), None);
+
+        for index in [0, 7, 8, 15, 31, 39] {
+            bytes[index] = b'\n';
+            assert_eq!(find_control_byte(&bytes), Some(index));
+            bytes[index] = 0x7f;
+            assert_eq!(find_control_byte(&bytes), None);
+            bytes[index] = b'a';
+        }
+
+        assert_eq!(find_control_byte("ÿ ÿ\u{1f}".as_bytes()), Some(5));
+    }
+
+    #[test]
+    fn lazy_line_column() {
+        let input = "[\"пони\",\n  \"🦄\"\r\n]";
+
+        assert_eq!(line_column(input, 0), (1, 0));
+        assert_eq!(line_column(input, 1), (1, 1));
+        assert_eq!(line_column(input, 12), (1, 8));
+        assert_eq!(line_column(input, 13), (2, 0));
+        assert_eq!(line_column(input, 21), (2, 5));
+        assert_eq!(line_column(input, input.len()), (3, 1));
+
+        let positions: Vec<_> = Lexer::new(input)
+            .map(|token| (token.line, token.column))
+            .collect();
+        assert_eq!(
+            positions,
+            [(1, 1), (1, 7), (1, 8), (2, 2), (2, 5), (3, 0), (3, 1)]
+        );
+    }
+
+    #[test]
+    fn long_strings() {
+        let long = "mare ".repeat(100);
+        assert_snapshot(
+            &format!("\"{long}\\n{long}\""),
+            &format!("{{String({:?})|L1:C1004}}", format!("{long}\n{long}")),
+        );
+
+        let options = ParseOptions::new().strict_strings(true);
+        assert_snapshot_with(
+            &format!("\"{long}\t\""),
+            &options,
+            "{Invalid(UnescapedControlChar { char: '\\t', line: 1, column: 502 })|L1:C502},{Invalid(MissingDoubleQuote(\"\"))|L1:C503}",
+        );
+    }
+
+    #[test]
+    fn whitespace_runs() {
+        assert_snapshot(
+            "[\n    1,\r\n\t2 ]",
+            "{OpenBracket|L1:C1},{Whitespace|L2:C4},{Number(1)|L2:C5},{Comma|L2:C6},{Whitespace|L3:C1},{Number(2)|L3:C2},{Whitespace|L3:C3},{ClosedBracket|L3:C4}",
+        );
+
+        let spans: Vec<_> = Lexer::new("  \n\t{ }")
+            .map(|token| (token.kind, token.offset, token.len))
+            .collect();
+        assert_eq!(
+            spans,
+            [
+                (TokenKind::Whitespace, 0, 4),
+                (TokenKind::OpenCurly, 4, 1),
+                (TokenKind::Whitespace, 5, 1),
+                (TokenKind::ClosedCurly, 6, 1)
+            ]
+        );
+
+        let kinds: Vec<_> = tokenize(" [ 1 ,\n2 ] ", &ParseOptions::default())
+            .map(|token| (token.kind, token.span))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                (TokenKind::OpenBracket, 1..2),
+                (TokenKind::Number(Number::from(1u64)), 3..4),
+                (TokenKind::Comma, 5..6),
+                (TokenKind::Number(Number::from(2u64)), 7..8),
+                (TokenKind::ClosedBracket, 9..10)
+            ]
+        );
+    }
+
+    #[test]
+    fn only_json_whitespace() {
+        assert_snapshot(
+            "1\u{a0}2",
+            "{Number(1)|L1:C1},{Invalid(NoSuchToken('\\u{a0}'))|L1:C2},{Number(2)|L1:C3}",
+        );
+        assert_snapshot(
+            "\u{2028}\u{b}\u{c}",
+            "{Invalid(NoSuchToken('\\u{2028}'))|L1:C1},{Invalid(NoSuchToken('\\u{b}'))|L1:C2},{Invalid(NoSuchToken('\\u{c}'))|L1:C3}",
+        );
+    }
+
+    #[test]
+    fn comments() {
+        let options = ParseOptions::new().allow_comments(true);
+
+        assert_snapshot_with(
+            "1 // one\n/* two */2",
+            &options,
+            "{Number(1)|L1:C1},{Whitespace|L1:C2},{Comment|L1:C8},{Whitespace|L2:C0},{Comment|L2:C9},{Number(2)|L2:C10}",
+        );
+        assert_snapshot_with(
+            "/**/ /*",
+            &options,
+            "{Comment|L1:C4},{Whitespace|L1:C5},{Invalid(UnterminatedComment)|L1:C7}",
+        );
+        assert_snapshot_with(
+            "/ 1",
+            &options,
+            "{Invalid(NoSuchToken('/'))|L1:C1},{Whitespace|L1:C2},{Number(1)|L1:C3}",
+        );
+        assert_snapshot("// 1", "{Invalid(NoSuchToken('/'))|L1:C1},{Invalid(NoSuchToken('/'))|L1:C2},{Whitespace|L1:C3},{Number(1)|L1:C4}");
+
+        let kinds: Vec<_> = tokenize("[/* a */ 1 // b\n]", &options)
+            .map(|token| token.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                TokenKind::OpenBracket,
+                TokenKind::Number(Number::from(1u64)),
+                TokenKind::ClosedBracket
+            ]
+        );
+    }
+
+    #[test]
+    fn string_limit() {
+        let options = ParseOptions::new().max_string_len(3);
+
+        assert_snapshot_with(r#""abc""#, &options, r#"{String("abc")|L1:C5}"#);
+        // cut after the character or escape which crosses the limit
+        assert_snapshot_with(
+            r#""abcd""#,
+            &options,
+            r#"{Invalid(StringTooLong)|L1:C5},{Invalid(MissingDoubleQuote(""))|L1:C6}"#,
+        );
+        assert_snapshot_with(
+            "\"ab\u{e9}\"",
+            &options,
+            r#"{Invalid(StringTooLong)|L1:C4},{Invalid(MissingDoubleQuote(""))|L1:C5}"#,
+        );
+        assert_snapshot_with(
+            r#""ab\n\u00e9""#,
+            &options,
+            r#"{Invalid(StringTooLong)|L1:C11},{Invalid(MissingDoubleQuote(""))|L1:C12}"#,
+        );
+    }
+
     #[test]
     fn smoke_position() {
         assert_snapshot(
             "100,200,\n300",
-            "{Number(100.0)|L1:C3},{Comma|L1:C4},{Number(200.0)|L1:C7},{Comma|L1:C8},{Whitespace|L2:C0},{Number(300.0)|L2:C3}"
+            "{Number(100)|L1:C3},{Comma|L1:C4},{Number(200)|L1:C7},{Comma|L1:C8},{Whitespace|L2:C0},{Number(300)|L2:C3}"
         );
     }
 
     #[track_caller]
     fn assert_snapshot(string: &str, expected: &str) {
-        let tokens = tokenize(string);
+        assert_snapshot_with(string, &ParseOptions::default(), expected);
+    }
+
+    #[track_caller]
+    fn assert_snapshot_with(string: &str, options: &ParseOptions, expected: &str) {
+        let tokens = Lexer::with_options(string, options);
 
         let mut actual = vec![];
 
         for elem in tokens {
             let Token {
-                mut kind,
-                line,
-                column,
+                kind, line, column, ..
             } = elem;
 
-            if let TokenKind::Number(num) = &mut kind {
-                *num = (*num * 1000.0).trunc() / 1000.0;
-            }
-            // let to_print = match kind {
-            //     TokenKind::Number(num) => TokenKind::Number(((num * 1000.0).trunc()) / 1000.0),
-            //     _ => kind,
-            // };
             actual.push(format!("{{{kind:?}|L{line}:C{column}}}"));
         }
 
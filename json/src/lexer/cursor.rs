@@ -1,57 +1,96 @@
-use std::str::Chars;
+use crate::ParseOptions;
 
-/// Peekable iterator over a char sequence.
+/// Peekable cursor over the bytes of a string.
 ///
-/// Next characters can be peeked via `first` method,
+/// Next bytes can be peeked via `peek_first` method,
 /// and position can be shifted forward via `bump` method.
+/// Line and column are not tracked here: they are counted from
+/// the byte offset only when somebody asks for them.
 pub(crate) struct Cursor<'a> {
-    token_len_and_remaining: usize,
-    /// Iterator over chars. Slightly faster than a &str.
-    chars: Chars<'a>,
-    line: usize,
-    column: usize,
+    input: &'a str,
+    /// Byte offset of the first byte of the current token.
+    token_start: usize,
+    /// Byte offset of the next unconsumed byte.
+    position: usize,
+    options: ParseOptions,
+    /// Whitespace is dropped instead of being returned as a token.
+    pub(crate) skip_whitespace: bool,
 }
 
 impl<'a> Cursor<'a> {
-    pub(crate) fn new(input: &'a str) -> Cursor<'a> {
+    pub(crate) fn new(input: &'a str, options: &ParseOptions) -> Cursor<'a> {
         Cursor {
-            token_len_and_remaining: input.len(),
-            chars: input.chars(),
-            line: 1,
-            column: 0,
+            input,
+            token_start: 0,
+            position: 0,
+            options: *options,
+            skip_whitespace: false,
         }
     }
 
-    pub(crate) fn get_position(&self) -> (usize, usize) {
-        (self.line, self.column)
+    pub(crate) fn input(&self) -> &'a str {
+        self.input
     }
 
-    /// Peeks the next symbol from the input stream without consuming it.
-    /// If requested position doesn't exist, `EOF_CHAR` is returned.
-    /// However, getting `EOF_CHAR` doesn't always mean actual end of file,
-    /// it should be checked with `is_eof` method.
-    pub(crate) fn peek_first(&self) -> Option<char> {
-        // `.next()` optimizes better than `.nth(0)`
-        self.chars.clone().next()
+    pub(crate) fn options(&self) -> &ParseOptions {
+        &self.options
     }
 
-    /// Resets the number of bytes consumed to 0.
-    pub(crate) fn reset_token_len(&mut self) {
-        self.token_len_and_remaining = self.chars.as_str().len();
+    /// Peeks the next byte from the input stream without consuming it.
+    pub(crate) fn peek_first(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.position).copied()
+    }
+
+    /// Peeks the byte after the next one without consuming anything.
+    pub(crate) fn peek_second(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.position + 1).copied()
+    }
+
+    /// Peeks the whole next character, for error messages
+    /// which shouldn't show a part of a multi-byte sequence.
+    pub(crate) fn peek_char(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    /// Returns the unconsumed part of the input.
+    pub(crate) fn rest(&self) -> &'a [u8] {
+        &self.input.as_bytes()[self.position..]
+    }
+
+    /// Moves past the next byte, which must be ASCII.
+    pub(crate) fn bump(&mut self) {
+        self.position += 1;
+    }
+
+    /// Moves `count` bytes forward. The new position must be a char boundary.
+    pub(crate) fn skip(&mut self, count: usize) {
+        self.position += count;
     }
 
     /// Moves to the next character.
     pub(crate) fn eat_char(&mut self) -> Option<char> {
-        let char = self.chars.next()?;
-        match char {
-            '\n' => {
-                self.line += 1;
-                self.column = 0;
-            }
-            _ => {
-                self.column += 1;
-            }
-        }
+        let char = self.peek_char()?;
+        self.position += char.len_utf8();
         Some(char)
     }
+
+    /// Returns the byte offset of the first character consumed since the last reset.
+    pub(crate) fn token_offset(&self) -> usize {
+        self.token_start
+    }
+
+    /// Returns the byte offset of the next unconsumed character.
+    pub(crate) fn offset(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the part of the input consumed since the last reset.
+    pub(crate) fn token_str(&self) -> &'a str {
+        &self.input[self.token_start..self.position]
+    }
+
+    /// Resets the number of bytes consumed to 0.
+    pub(crate) fn reset_token_len(&mut self) {
+        self.token_start = self.position;
+    }
 }
@@ -1,15 +1,69 @@
-pub mod colors_test;
+mod convert;
+mod de;
+mod error;
+mod jsonpath;
 mod lexer;
+mod macros;
+mod map;
+mod number;
+mod options;
 mod parser;
+mod pointer;
+mod reader;
+mod ser;
+mod value;
+mod writer;
 
-use crate::parser::{ParsingContext, ParsingError};
-pub use parser::Value;
+use crate::parser::ParsingContext;
+pub use convert::FromValueError;
+pub use de::{from_str, from_str_with};
+pub use error::Error;
+pub use jsonpath::{JsonPath, JsonPathError, Node, NormalizedPath, PathElement};
+pub use lexer::{Lexer, Token, TokenKind, TokenizeError};
+pub use map::Map;
+pub use number::Number;
+pub use options::{DuplicateKeys, ParseOptions, WriteOptions};
+pub use parser::{
+    Documents, Event, Events, Expected, IncrementalParser, ParsingError, ParsingErrorKind,
+    Recovered, Report, Scalar, Status, Value,
+};
+pub use pointer::PointerError;
+pub use reader::{from_reader, from_reader_with};
+pub use ser::{to_string, to_string_with, to_value, to_writer, to_writer_with};
+pub use value::ValueIndex;
 
 #[cfg(test)]
 #[macro_use]
 extern crate pretty_assertions;
 
-pub fn parse(string: &str) -> Result<Value, ParsingError> {
-    let context = ParsingContext::new();
+pub fn parse(string: &str) -> Result<Value<'_>, ParsingError> {
+    parse_with(string, &ParseOptions::default())
+}
+
+pub fn parse_with<'a>(string: &'a str, options: &ParseOptions) -> Result<Value<'a>, ParsingError> {
+    let context = ParsingContext::new(options);
     context.parse(string)
 }
+
+/// Parses `string` without building a [`Value`], handing each [`Event`]
+/// to `handler` as soon as it's read. Memory use doesn't grow with the
+/// document, see [`Events`], which pulls the same events one at a time.
+/// The events before an error have already been handed out when it's returned.
+pub fn parse_events<'a>(
+    string: &'a str,
+    options: &ParseOptions,
+    handler: impl FnMut(Event<'a>),
+) -> Result<(), ParsingError> {
+    let context = ParsingContext::new(options);
+    context.parse_events(string, handler)
+}
+
+/// Parses the whole `string` even if it's malformed, and reports every error
+/// instead of stopping at the first one. Broken values are replaced with
+/// `Null`, listed in [`Recovered::placeholders`], broken members and elements
+/// are dropped, and unclosed containers are closed. With no errors,
+/// the value is what [`parse_with`] gives.
+pub fn parse_recovering<'a>(string: &'a str, options: &ParseOptions) -> Recovered<'a> {
+    let context = ParsingContext::new(options);
+    context.parse_recovering(string)
+}
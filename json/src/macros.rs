@@ -0,0 +1,98 @@
+/// Builds a [`Value`](crate::Value) with JSON syntax.
+///
+/// Any Rust expression which converts into a `Value` can stand for a value,
+/// and keys can be expressions giving `&str` or `String` in parentheses.
+/// A value made of several tokens, like `age + 1`, ends at the next comma.
+///
+/// ```
+/// let name = "Rarity";
+/// let value = json::json!({
+///     "name": name,
+///     "age": 18 + 1,
+///     "friends": ["Twilight", "Applejack", null],
+///     ("pet".to_owned()): {"name": "Opal", "likes": []},
+/// });
+/// assert_eq!(value["pet"]["name"], "Opal");
+/// assert_eq!(value.to_string(), r#"{"name":"Rarity","age":19,"friends":["Twilight","Applejack",null],"pet":{"name":"Opal","likes":[]}}"#);
+/// ```
+#[macro_export]
+macro_rules! json {
+    (null) => {
+        $crate::Value::Null
+    };
+    (true) => {
+        $crate::Value::Bool(true)
+    };
+    (false) => {
+        $crate::Value::Bool(false)
+    };
+    ([]) => {
+        $crate::Value::Array(::std::vec::Vec::new())
+    };
+    ([$($elements:tt)+]) => {
+        $crate::Value::Array($crate::json!(@array [] $($elements)+))
+    };
+    ({}) => {
+        $crate::Value::Object($crate::Map::new())
+    };
+    ({$($members:tt)+}) => {{
+        let mut object = $crate::Map::new();
+        $crate::json!(@object object $($members)+);
+        $crate::Value::Object(object)
+    }};
+
+    // Elements, one at a time, into a `vec!`. Literals and nested containers
+    // come first, an expression would take them otherwise.
+    (@array [$($done:expr,)*]) => {
+        ::std::vec![$($done,)*]
+    };
+    (@array [$($done:expr,)*] null $(, $($rest:tt)*)?) => {
+        $crate::json!(@array [$($done,)* $crate::json!(null),] $($($rest)*)?)
+    };
+    (@array [$($done:expr,)*] true $(, $($rest:tt)*)?) => {
+        $crate::json!(@array [$($done,)* $crate::json!(true),] $($($rest)*)?)
+    };
+    (@array [$($done:expr,)*] false $(, $($rest:tt)*)?) => {
+        $crate::json!(@array [$($done,)* $crate::json!(false),] $($($rest)*)?)
+    };
+    (@array [$($done:expr,)*] [$($inner:tt)*] $(, $($rest:tt)*)?) => {
+        $crate::json!(@array [$($done,)* $crate::json!([$($inner)*]),] $($($rest)*)?)
+    };
+    (@array [$($done:expr,)*] {$($inner:tt)*} $(, $($rest:tt)*)?) => {
+        $crate::json!(@array [$($done,)* $crate::json!({$($inner)*}),] $($($rest)*)?)
+    };
+    (@array [$($done:expr,)*] $value:expr $(, $($rest:tt)*)?) => {
+        $crate::json!(@array [$($done,)* $crate::Value::from($value),] $($($rest)*)?)
+    };
+
+    // Members, one at a time, inserted into `object` in the same order.
+    (@object $object:ident) => {};
+    (@object $object:ident $key:tt : null $(, $($rest:tt)*)?) => {
+        $object.insert($key, $crate::json!(null));
+        $crate::json!(@object $object $($($rest)*)?);
+    };
+    (@object $object:ident $key:tt : true $(, $($rest:tt)*)?) => {
+        $object.insert($key, $crate::json!(true));
+        $crate::json!(@object $object $($($rest)*)?);
+    };
+    (@object $object:ident $key:tt : false $(, $($rest:tt)*)?) => {
+        $object.insert($key, $crate::json!(false));
+        $crate::json!(@object $object $($($rest)*)?);
+    };
+    (@object $object:ident $key:tt : [$($inner:tt)*] $(, $($rest:tt)*)?) => {
+        $object.insert($key, $crate::json!([$($inner)*]));
+        $crate::json!(@object $object $($($rest)*)?);
+    };
+    (@object $object:ident $key:tt : {$($inner:tt)*} $(, $($rest:tt)*)?) => {
+        $object.insert($key, $crate::json!({$($inner)*}));
+        $crate::json!(@object $object $($($rest)*)?);
+    };
+    (@object $object:ident $key:tt : $value:expr $(, $($rest:tt)*)?) => {
+        $object.insert($key, $crate::Value::from($value));
+        $crate::json!(@object $object $($($rest)*)?);
+    };
+
+    ($other:expr) => {
+        $crate::Value::from($other)
+    };
+}
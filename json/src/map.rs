@@ -0,0 +1,352 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use indexmap::IndexMap;
+
+use crate::Value;
+
+/// Members of a JSON object, in the order they were inserted.
+///
+/// Parsing keeps the order of the document, unless
+/// [`ParseOptions::sort_keys`](crate::ParseOptions::sort_keys) asks for sorted keys.
+///
+/// With [`DuplicateKeys::Collect`](crate::DuplicateKeys::Collect), a key keeps its
+/// first value and the members which repeat it are kept aside, see
+/// [`get_all`](Map::get_all) and [`duplicates`](Map::duplicates). The other
+/// methods, iterating and writing the map only see the first value.
+#[derive(Default)]
+pub struct Map<'a> {
+    inner: IndexMap<Cow<'a, str>, Value<'a>>,
+    /// Members whose key came earlier in the object, in document order.
+    duplicates: Vec<(Cow<'a, str>, Value<'a>)>,
+}
+
+impl<'a> Map<'a> {
+    pub fn new() -> Map<'a> {
+        Map {
+            inner: IndexMap::new(),
+            duplicates: Vec::new(),
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Map<'a> {
+        Map {
+            inner: IndexMap::with_capacity(capacity),
+            duplicates: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.inner.clear();
+        self.duplicates.clear();
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.inner.contains_key(key)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value<'a>> {
+        self.inner.get(key)
+    }
+
+    /// Like [`get`](Map::get), with the key as it's stored.
+    pub fn get_key_value(&self, key: &str) -> Option<(&Cow<'a, str>, &Value<'a>)> {
+        self.inner.get_key_value(key)
+    }
+
+    /// Every value of `key` in document order: the one [`get`](Map::get)
+    /// returns, then the ones of its [`duplicates`](Map::duplicates).
+    pub fn get_all<'m>(&'m self, key: &'m str) -> impl Iterator<Item = &'m Value<'a>> {
+        let duplicates = self
+            .duplicates
+            .iter()
+            .filter(move |(duplicate, _)| duplicate == key)
+            .map(|(_, value)| value);
+        self.get(key).into_iter().chain(duplicates)
+    }
+
+    /// Members whose key already appeared in the object, in document order.
+    /// Only parsing with [`DuplicateKeys::Collect`](crate::DuplicateKeys::Collect) keeps them.
+    pub fn duplicates(&self) -> &[(Cow<'a, str>, Value<'a>)] {
+        &self.duplicates
+    }
+
+    /// Keeps a member whose key is already in the map aside.
+    pub(crate) fn push_duplicate(&mut self, key: Cow<'a, str>, value: Value<'a>) {
+        self.duplicates.push((key, value));
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value<'a>> {
+        self.inner.get_mut(key)
+    }
+
+    /// Member at `index` in the order of the map.
+    pub fn get_index(&self, index: usize) -> Option<(&Cow<'a, str>, &Value<'a>)> {
+        self.inner.get_index(index)
+    }
+
+    /// Inserts a member and returns the value it replaced.
+    /// A new key goes last, a replaced one keeps its place.
+    pub fn insert(&mut self, key: impl Into<Cow<'a, str>>, value: Value<'a>) -> Option<Value<'a>> {
+        self.inner.insert(key.into(), value)
+    }
+
+    /// Removes a member, the ones after it move up to keep their order.
+    /// Its duplicates are removed too.
+    pub fn remove(&mut self, key: &str) -> Option<Value<'a>> {
+        self.duplicates.retain(|(duplicate, _)| duplicate != key);
+        self.inner.shift_remove(key)
+    }
+
+    /// Sorts the members by key, like [`BTreeMap`](std::collections::BTreeMap) would.
+    pub fn sort_keys(&mut self) {
+        self.inner.sort_keys();
+        self.duplicates.sort_by(|(a, _), (b, _)| a.cmp(b));
+    }
+
+    pub fn iter(&self) -> Iter<'_, 'a> {
+        Iter(self.inner.iter())
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, 'a> {
+        IterMut(self.inner.iter_mut())
+    }
+
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &Cow<'a, str>> + ExactSizeIterator {
+        self.inner.keys()
+    }
+
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &Value<'a>> + ExactSizeIterator {
+        self.inner.values()
+    }
+
+    pub fn values_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = &mut Value<'a>> + ExactSizeIterator {
+        self.inner.values_mut()
+    }
+
+    /// Detaches the keys and values from the parsed text.
+    pub fn into_owned(self) -> Map<'static> {
+        let owned = |(key, value): (Cow<'_, str>, Value<'_>)| {
+            (Cow::Owned(key.into_owned()), value.into_owned())
+        };
+        Map {
+            inner: self.inner.into_iter().map(owned).collect(),
+            duplicates: self.duplicates.into_iter().map(owned).collect(),
+        }
+    }
+}
+
+impl<'a> Index<&str> for Map<'a> {
+    type Output = Value<'a>;
+
+    /// Panics if there is no such key.
+    fn index(&self, key: &str) -> &Value<'a> {
+        match self.get(key) {
+            Some(value) => value,
+            None => panic!("no key {key:?} in the object"),
+        }
+    }
+}
+
+impl<'a> IndexMut<&str> for Map<'a> {
+    /// Panics if there is no such key.
+    fn index_mut(&mut self, key: &str) -> &mut Value<'a> {
+        match self.get_mut(key) {
+            Some(value) => value,
+            None => panic!("no key {key:?} in the object"),
+        }
+    }
+}
+
+/// Maps are equal when they have the same members, in any order,
+/// and the same duplicates in the same order.
+impl PartialEq for Map<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner && self.duplicates == other.duplicates
+    }
+}
+
+/// Duplicates are listed after the members.
+impl fmt::Debug for Map<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let duplicates = self.duplicates.iter().map(|(key, value)| (key, value));
+        f.debug_map()
+            .entries(self.iter())
+            .entries(duplicates)
+            .finish()
+    }
+}
+
+impl<'a, K: Into<Cow<'a, str>>> FromIterator<(K, Value<'a>)> for Map<'a> {
+    fn from_iter<I: IntoIterator<Item = (K, Value<'a>)>>(iter: I) -> Map<'a> {
+        let mut map = Map::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<'a, K: Into<Cow<'a, str>>> Extend<(K, Value<'a>)> for Map<'a> {
+    fn extend<I: IntoIterator<Item = (K, Value<'a>)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+/// Borrowing iterator over the members of a [`Map`], in order.
+pub struct Iter<'m, 'a>(indexmap::map::Iter<'m, Cow<'a, str>, Value<'a>>);
+
+/// Iterator over the members of a [`Map`] with mutable values, in order.
+pub struct IterMut<'m, 'a>(indexmap::map::IterMut<'m, Cow<'a, str>, Value<'a>>);
+
+/// Owning iterator over the members of a [`Map`], in order.
+pub struct IntoIter<'a>(indexmap::map::IntoIter<Cow<'a, str>, Value<'a>>);
+
+impl<'m, 'a> Iterator for Iter<'m, 'a> {
+    type Item = (&'m Cow<'a, str>, &'m Value<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_, '_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back()
+    }
+}
+
+impl ExactSizeIterator for Iter<'_, '_> {}
+
+impl<'m, 'a> Iterator for IterMut<'m, 'a> {
+    type Item = (&'m Cow<'a, str>, &'m mut Value<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for IterMut<'_, '_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back()
+    }
+}
+
+impl ExactSizeIterator for IterMut<'_, '_> {}
+
+impl<'a> Iterator for IntoIter<'a> {
+    type Item = (Cow<'a, str>, Value<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for IntoIter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back()
+    }
+}
+
+impl ExactSizeIterator for IntoIter<'_> {}
+
+impl<'m, 'a> IntoIterator for &'m Map<'a> {
+    type Item = (&'m Cow<'a, str>, &'m Value<'a>);
+    type IntoIter = Iter<'m, 'a>;
+
+    fn into_iter(self) -> Iter<'m, 'a> {
+        self.iter()
+    }
+}
+
+impl<'m, 'a> IntoIterator for &'m mut Map<'a> {
+    type Item = (&'m Cow<'a, str>, &'m mut Value<'a>);
+    type IntoIter = IterMut<'m, 'a>;
+
+    fn into_iter(self) -> IterMut<'m, 'a> {
+        self.iter_mut()
+    }
+}
+
+impl<'a> IntoIterator for Map<'a> {
+    type Item = (Cow<'a, str>, Value<'a>);
+    type IntoIter = IntoIter<'a>;
+
+    fn into_iter(self) -> IntoIter<'a> {
+        IntoIter(self.inner.into_iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(map: &Map) -> Vec<String> {
+        map.keys().map(|key| key.to_string()).collect()
+    }
+
+    #[test]
+    fn insertion_order() {
+        let mut map: Map = [("b", Value::Null), ("c", Value::Null), ("a", Value::Null)]
+            .into_iter()
+            .collect();
+        assert_eq!(keys(&map), ["b", "c", "a"]);
+
+        // replacing keeps the place, a new key goes last
+        assert!(map.insert("c", Value::Bool(true)).is_some());
+        assert!(map.insert("d", Value::Null).is_none());
+        assert_eq!(keys(&map), ["b", "c", "a", "d"]);
+        assert_eq!(map.get_index(1).map(|(key, _)| key.as_ref()), Some("c"));
+
+        assert!(map.remove("c").is_some());
+        assert_eq!(keys(&map), ["b", "a", "d"]);
+        assert!(map.remove("c").is_none());
+
+        map.sort_keys();
+        assert_eq!(keys(&map), ["a", "b", "d"]);
+        assert_eq!(format!("{map:?}"), r#"{"a": Null, "b": Null, "d": Null}"#);
+    }
+
+    #[test]
+    fn duplicates() {
+        let mut map: Map = [("a", Value::Bool(true)), ("b", Value::Null)]
+            .into_iter()
+            .collect();
+        map.push_duplicate("a".into(), Value::Bool(false));
+        let all: Vec<_> = map.get_all("a").collect();
+        assert_eq!(all, [&Value::Bool(true), &Value::Bool(false)]);
+        assert_eq!(map.get_all("b").count(), 1);
+        assert_eq!(map.get_all("c").count(), 0);
+        assert_eq!(
+            format!("{map:?}"),
+            r#"{"a": Bool(true), "b": Null, "a": Bool(false)}"#
+        );
+
+        map.remove("a");
+        assert!(map.duplicates().is_empty());
+        assert_eq!(map.get_all("a").count(), 0);
+    }
+}
@@ -0,0 +1,254 @@
+use std::fmt;
+
+/// A JSON number.
+///
+/// Integers that fit are kept as `u64` (non-negative) or `i64` (negative),
+/// everything else is a correctly rounded `f64` parsed from the lexeme.
+///
+/// With the `arbitrary_precision` feature the original digits are kept
+/// as well, so numbers are written back exactly as they were read.
+#[derive(Clone, PartialEq)]
+pub struct Number {
+    n: N,
+    #[cfg(feature = "arbitrary_precision")]
+    digits: Box<str>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum N {
+    PosInt(u64),
+    /// Always less than zero.
+    NegInt(i64),
+    /// Always finite, unless built from digits out of the `f64` range
+    /// with the `arbitrary_precision` feature.
+    Float(f64),
+}
+
+impl Number {
+    /// Converts a lexeme accepted by the lexer into a number.
+    ///
+    /// `is_float` tells whether the lexeme has a fraction or an exponent.
+    /// Returns `None` if the value doesn't fit in `f64`.
+    pub(crate) fn from_lexeme(lexeme: &str, is_float: bool) -> Option<Number> {
+        let n = if is_float {
+            Self::parse_float(lexeme)?
+        } else if let Ok(num) = lexeme.parse::<u64>() {
+            N::PosInt(num)
+        } else {
+            match lexeme.parse::<i64>() {
+                // "-0" is the only negative lexeme that becomes non-negative
+                Ok(0) => N::Float(-0.0),
+                Ok(num) => N::NegInt(num),
+                // too big for 64 bits
+                Err(_) => Self::parse_float(lexeme)?,
+            }
+        };
+
+        Some(Number {
+            n,
+            #[cfg(feature = "arbitrary_precision")]
+            digits: lexeme.into(),
+        })
+    }
+
+    #[cfg(not(feature = "arbitrary_precision"))]
+    fn parse_float(lexeme: &str) -> Option<N> {
+        let float = lexeme.parse::<f64>().ok()?;
+        float.is_finite().then_some(N::Float(float))
+    }
+
+    #[cfg(feature = "arbitrary_precision")]
+    fn parse_float(lexeme: &str) -> Option<N> {
+        // the digits are kept anyway, so `1e400` is a valid (if infinite) number
+        lexeme.parse::<f64>().ok().map(N::Float)
+    }
+
+    /// Creates a number from a finite float. Returns `None` for NaN and infinities.
+    pub fn from_f64(float: f64) -> Option<Number> {
+        if !float.is_finite() {
+            return None;
+        }
+        Some(Number {
+            n: N::Float(float),
+            #[cfg(feature = "arbitrary_precision")]
+            digits: format!("{float:?}").into(),
+        })
+    }
+
+    /// Returns `true` if the number is an integer between `0` and `u64::MAX`.
+    pub fn is_u64(&self) -> bool {
+        matches!(self.n, N::PosInt(_))
+    }
+
+    /// Returns `true` if the number is an integer between `i64::MIN` and `i64::MAX`.
+    pub fn is_i64(&self) -> bool {
+        match self.n {
+            N::PosInt(num) => i64::try_from(num).is_ok(),
+            N::NegInt(_) => true,
+            N::Float(_) => false,
+        }
+    }
+
+    /// Returns `true` if the number was written with a fraction or an exponent,
+    /// or is an integer too big for 64 bits.
+    pub fn is_f64(&self) -> bool {
+        matches!(self.n, N::Float(_))
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match self.n {
+            N::PosInt(num) => Some(num),
+            N::NegInt(_) | N::Float(_) => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self.n {
+            N::PosInt(num) => i64::try_from(num).ok(),
+            N::NegInt(num) => Some(num),
+            N::Float(_) => None,
+        }
+    }
+
+    /// Returns the number as `f64`. Integers above 2^53 lose precision.
+    pub fn as_f64(&self) -> f64 {
+        match self.n {
+            N::PosInt(num) => num as f64,
+            N::NegInt(num) => num as f64,
+            N::Float(num) => num,
+        }
+    }
+
+    /// Returns the digits exactly as they were written in the document.
+    #[cfg(feature = "arbitrary_precision")]
+    pub fn as_str(&self) -> &str {
+        &self.digits
+    }
+}
+
+impl From<u64> for Number {
+    fn from(num: u64) -> Number {
+        Number {
+            n: N::PosInt(num),
+            #[cfg(feature = "arbitrary_precision")]
+            digits: num.to_string().into(),
+        }
+    }
+}
+
+impl From<i64> for Number {
+    fn from(num: i64) -> Number {
+        let n = if num < 0 {
+            N::NegInt(num)
+        } else {
+            N::PosInt(num as u64)
+        };
+        Number {
+            n,
+            #[cfg(feature = "arbitrary_precision")]
+            digits: num.to_string().into(),
+        }
+    }
+}
+
+/// Name of the newtype struct which carries the digits of a number through
+/// serde, as a string, so they survive [`to_value`](crate::to_value) and
+/// [`from_str`](crate::from_str) into a [`Value`](crate::Value).
+#[cfg(feature = "arbitrary_precision")]
+pub(crate) const TOKEN: &str = "$json::private::Number";
+
+/// Reads a number from the digits [`TOKEN`] carries,
+/// `None` if they aren't a JSON number.
+#[cfg(feature = "arbitrary_precision")]
+pub(crate) fn from_digits(digits: &str) -> Option<Number> {
+    match crate::parse(digits) {
+        Ok(crate::Value::Number(number)) => Some(number),
+        _ => None,
+    }
+}
+
+#[cfg(not(feature = "arbitrary_precision"))]
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.n {
+            N::PosInt(num) => write!(f, "{num}"),
+            N::NegInt(num) => write!(f, "{num}"),
+            // `Debug` is the shortest representation that round-trips,
+            // and it is valid JSON for every finite float
+            N::Float(num) => write!(f, "{num:?}"),
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary_precision")]
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.digits)
+    }
+}
+
+impl fmt::Debug for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.n {
+            N::PosInt(num) => fmt::Debug::fmt(&num, f),
+            N::NegInt(num) => fmt::Debug::fmt(&num, f),
+            N::Float(num) => fmt::Debug::fmt(&num, f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integers() {
+        let number = Number::from_lexeme("18446744073709551615", false).unwrap();
+        assert_eq!(number.as_u64(), Some(u64::MAX));
+        assert_eq!(number.as_i64(), None);
+
+        let number = Number::from_lexeme("-9223372036854775808", false).unwrap();
+        assert_eq!(number.as_i64(), Some(i64::MIN));
+        assert!(!number.is_u64());
+
+        let number = Number::from_lexeme("18446744073709551616", false).unwrap();
+        assert!(number.is_f64());
+        assert_eq!(number.as_f64(), 18446744073709551616.0);
+
+        let number = Number::from_lexeme("-0", false).unwrap();
+        assert!(number.is_f64());
+        assert!(number.as_f64().is_sign_negative());
+    }
+
+    #[test]
+    fn floats_are_correctly_rounded() {
+        let number = Number::from_lexeme("-100.000001", true).unwrap();
+        assert_eq!(number.as_f64(), -100.000001);
+        assert_eq!(number.to_string(), "-100.000001");
+
+        let number = Number::from_lexeme("0.1", true).unwrap();
+        assert_eq!(number.as_f64(), 0.1);
+
+        let number = Number::from_lexeme("6.02E23", true).unwrap();
+        assert_eq!(number.as_f64(), 6.02e23);
+    }
+
+    #[test]
+    #[cfg(not(feature = "arbitrary_precision"))]
+    fn out_of_range() {
+        assert_eq!(Number::from_lexeme("1e400", true), None);
+        assert_eq!(Number::from_f64(f64::NAN), None);
+    }
+
+    #[test]
+    #[cfg(feature = "arbitrary_precision")]
+    fn digits_round_trip() {
+        let lexeme = "123456789012345678901234567890.000000000000000001";
+        let number = Number::from_lexeme(lexeme, true).unwrap();
+        assert_eq!(number.as_str(), lexeme);
+        assert_eq!(number.to_string(), lexeme);
+
+        let number = Number::from_lexeme("1e400", true).unwrap();
+        assert_eq!(number.to_string(), "1e400");
+    }
+}
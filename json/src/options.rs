@@ -0,0 +1,188 @@
+/// Settings for [`parse_with`](crate::parse_with).
+///
+/// The defaults are what [`parse`](crate::parse) uses.
+#[derive(Debug, Clone, Copy)]
+pub struct ParseOptions {
+    pub(crate) lossy_unicode: bool,
+    pub(crate) strict_strings: bool,
+    pub(crate) max_depth: usize,
+    pub(crate) max_string_len: usize,
+    pub(crate) max_document_len: usize,
+    pub(crate) duplicate_keys: DuplicateKeys,
+    pub(crate) comments: bool,
+    pub(crate) trailing_commas: bool,
+    pub(crate) sort_keys: bool,
+}
+
+/// What to do with an object which has the same key more than once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateKeys {
+    /// Keep the value that comes last, like most JSON parsers do.
+    LastWins,
+    /// Keep the value that comes first.
+    FirstWins,
+    /// Fail with [`ParsingErrorKind::DuplicateKey`](crate::ParsingErrorKind::DuplicateKey),
+    /// the error tells where the key first appeared.
+    Error,
+    /// Keep every value: a key maps to its first value, and the members
+    /// repeating it are kept aside in the [`Map`](crate::Map), see
+    /// [`Map::get_all`](crate::Map::get_all) and [`Map::duplicates`](crate::Map::duplicates).
+    Collect,
+}
+
+impl ParseOptions {
+    pub const fn new() -> ParseOptions {
+        ParseOptions {
+            lossy_unicode: false,
+            strict_strings: false,
+            max_depth: 128,
+            max_string_len: usize::MAX,
+            max_document_len: usize::MAX,
+            duplicate_keys: DuplicateKeys::LastWins,
+            comments: false,
+            trailing_commas: false,
+            sort_keys: false,
+        }
+    }
+
+    /// Replace unpaired UTF-16 surrogates in `\u` escapes with U+FFFD
+    /// instead of failing with `UnpairedHighSurrogate`/`UnpairedLowSurrogate`.
+    pub fn lossy_unicode(mut self, lossy: bool) -> ParseOptions {
+        self.lossy_unicode = lossy;
+        self
+    }
+
+    /// Reject unescaped control characters (U+0000 to U+001F) in strings,
+    /// as RFC 8259 requires. By default they are taken as is.
+    pub fn strict_strings(mut self, strict: bool) -> ParseOptions {
+        self.strict_strings = strict;
+        self
+    }
+
+    /// How many arrays and objects can be nested in each other, 128 by default.
+    pub fn max_depth(mut self, depth: usize) -> ParseOptions {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Longest string or key in bytes, after unescaping. Unlimited by default.
+    pub fn max_string_len(mut self, len: usize) -> ParseOptions {
+        self.max_string_len = len;
+        self
+    }
+
+    /// Largest text to parse in bytes. Unlimited by default.
+    /// A longer one fails at the character which crosses the limit.
+    pub fn max_document_len(mut self, len: usize) -> ParseOptions {
+        self.max_document_len = len;
+        self
+    }
+
+    /// What to do with repeated keys in an object, [`DuplicateKeys::LastWins`] by default.
+    /// Choose [`DuplicateKeys::Error`] for untrusted input, where two parsers
+    /// picking different values is a security hole.
+    pub fn duplicate_keys(mut self, policy: DuplicateKeys) -> ParseOptions {
+        self.duplicate_keys = policy;
+        self
+    }
+
+    /// Allow `// line` and `/* block */` comments wherever whitespace is allowed.
+    pub fn allow_comments(mut self, allow: bool) -> ParseOptions {
+        self.comments = allow;
+        self
+    }
+
+    /// Allow a comma after the last element of an array or member of an object.
+    pub fn allow_trailing_commas(mut self, allow: bool) -> ParseOptions {
+        self.trailing_commas = allow;
+        self
+    }
+
+    /// Sort the members of every object by key instead of keeping
+    /// the order of the document.
+    pub fn sort_keys(mut self, sort: bool) -> ParseOptions {
+        self.sort_keys = sort;
+        self
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> ParseOptions {
+        ParseOptions::new()
+    }
+}
+
+/// Settings for writing a [`Value`](crate::Value) as JSON text,
+/// see [`Value::to_writer_with`](crate::Value::to_writer_with).
+///
+/// The defaults give compact output, like `{}` formatting does.
+#[derive(Debug, Clone, Copy)]
+pub struct WriteOptions {
+    pub(crate) indent: Indent,
+    pub(crate) sort_keys: bool,
+    pub(crate) ascii_only: bool,
+    pub(crate) compact_scalar_arrays: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Indent {
+    /// Everything on one line, without spaces.
+    None,
+    Spaces(usize),
+    Tab,
+}
+
+impl WriteOptions {
+    pub const fn new() -> WriteOptions {
+        WriteOptions {
+            indent: Indent::None,
+            sort_keys: false,
+            ascii_only: false,
+            compact_scalar_arrays: false,
+        }
+    }
+
+    /// Every member and element on its own line, indented by two spaces
+    /// per level. This is what `{:#}` formatting gives.
+    pub const fn pretty() -> WriteOptions {
+        WriteOptions::new().indent(2)
+    }
+
+    /// Pretty print, indenting by `width` spaces per level.
+    pub const fn indent(mut self, width: usize) -> WriteOptions {
+        self.indent = Indent::Spaces(width);
+        self
+    }
+
+    /// Pretty print, indenting by one tab per level.
+    pub const fn tabs(mut self) -> WriteOptions {
+        self.indent = Indent::Tab;
+        self
+    }
+
+    /// Write the members of objects sorted by key instead of in their order.
+    pub const fn sort_keys(mut self, sort: bool) -> WriteOptions {
+        self.sort_keys = sort;
+        self
+    }
+
+    /// Escape every non-ASCII character as `\uXXXX`, using surrogate pairs
+    /// outside the Basic Multilingual Plane.
+    pub const fn ascii_only(mut self, ascii_only: bool) -> WriteOptions {
+        self.ascii_only = ascii_only;
+        self
+    }
+
+    /// When pretty printing, keep arrays without objects and arrays
+    /// inside on one line, like `[1, 2, 3]`.
+    pub const fn compact_scalar_arrays(mut self, compact: bool) -> WriteOptions {
+        self.compact_scalar_arrays = compact;
+        self
+    }
+}
+
+impl Default for WriteOptions {
+    fn default() -> WriteOptions {
+        WriteOptions::new()
+    }
+}
@@ -1,255 +1,451 @@
+mod builder;
+mod documents;
+mod events;
+mod incremental;
 mod parsing_error_context;
-
-pub(crate) use parsing_error_context::{ParsingError, ParsingErrorKind};
+mod recovery;
+mod report;
+
+use builder::TreeBuilder;
+pub use documents::Documents;
+pub use events::{Event, Events, Scalar};
+pub use incremental::{IncrementalParser, Status};
+pub(crate) use parsing_error_context::{json_path, Segment};
+pub use parsing_error_context::{Expected, ParsingError, ParsingErrorKind};
+pub use recovery::Recovered;
+pub use report::Report;
+use std::borrow::Cow;
 use std::collections::BTreeMap;
-
-use crate::lexer::{self, Token, TokenKind};
-
-#[derive(Debug)]
-pub enum Value {
+use std::mem;
+use std::ops::Range;
+
+use crate::lexer::{self, RawToken, TokenKind, TokenizeError};
+use crate::number::Number;
+use crate::{DuplicateKeys, Map, ParseOptions};
+
+/// A parsed JSON value.
+///
+/// Strings and object keys without escapes borrow from the parsed text,
+/// use [`Value::into_owned`] to detach the value from it.
+#[derive(Debug, PartialEq)]
+pub enum Value<'a> {
     Null,
     Bool(bool),
-    Number(f64),
-    String(String),
-    Array(Vec<Value>),
-    Object(BTreeMap<String, Value>),
+    Number(Number),
+    String(Cow<'a, str>),
+    Array(Vec<Value<'a>>),
+    Object(Map<'a>),
+}
+
+impl Value<'_> {
+    pub fn into_owned(self) -> Value<'static> {
+        match self {
+            Value::Null => Value::Null,
+            Value::Bool(bool) => Value::Bool(bool),
+            Value::Number(number) => Value::Number(number),
+            Value::String(string) => Value::String(Cow::Owned(string.into_owned())),
+            Value::Array(array) => Value::Array(array.into_iter().map(Value::into_owned).collect()),
+            Value::Object(object) => Value::Object(object.into_owned()),
+        }
+    }
 }
 
 #[derive(Debug)]
-enum ExpectingValue {
+enum ExpectingValue<'a> {
+    /// `len` members are already parsed.
     Obj {
-        acc: BTreeMap<String, Value>,
-        key: String,
+        len: usize,
+        key: Cow<'a, str>,
+        seen: SeenKeys<'a>,
     },
 
-    Arr {
-        acc: Vec<Value>,
-    },
+    /// `len` values of the array are already parsed.
+    Arr { len: usize },
 }
 
 #[derive(Debug)]
-enum Expectation {
+enum Expectation<'a> {
     Value,
     Obj {
-        acc: BTreeMap<String, Value>,
-        kv: KvState,
+        len: usize,
+        kv: KvState<'a>,
+        seen: SeenKeys<'a>,
     },
     CommaOrClosedBracket {
-        acc: Vec<Value>,
+        len: usize,
     },
-    EndOfTokens(Value),
+    EndOfTokens,
 }
 
 #[derive(Debug)]
-enum KvState {
+enum KvState<'a> {
     Start,
-    AteKey(String),
+    AteKey(Cow<'a, str>),
     AteValue,
 }
 
-#[derive(Debug)]
-pub(crate) struct ParsingContext {
-    stack: Vec<ExpectingValue>,
-    expectation: Expectation,
+/// Where each key of an object first appeared, for [`DuplicateKeys::Error`].
+/// Stays empty with the other policies, which only the tree builder applies.
+#[derive(Debug, Default)]
+struct SeenKeys<'a> {
+    spans: BTreeMap<Cow<'a, str>, Range<usize>>,
+}
+
+/// The part of the input the parser still has, to place errors.
+/// It's all of it, unless the input is read in chunks.
+pub(crate) struct Text<'t> {
+    text: &'t str,
+    /// Byte offset of `text` in the whole input.
+    offset: usize,
+    /// Line and column of the character right before `text`.
+    start: (usize, usize),
+    /// Positions of the ends of keys no longer in `text`, by byte offset.
+    /// Kept for [`DuplicateKeys::Error`], which points at the first key.
+    keys: Option<&'t BTreeMap<usize, (usize, usize)>>,
 }
 
-struct ParsingLoopContext {
-    base: ParsingContext,
-    token: Token,
+impl<'t> Text<'t> {
+    fn new(input: &'t str) -> Text<'t> {
+        Text {
+            text: input,
+            offset: 0,
+            start: (1, 0),
+            keys: None,
+        }
+    }
+
+    fn position(&self, offset: usize) -> (usize, usize) {
+        match offset.checked_sub(self.offset) {
+            Some(relative) => lexer::advance(self.start, &self.text.as_bytes()[..relative]),
+            None => self
+                .keys
+                .and_then(|keys| keys.get(&offset))
+                .copied()
+                .unwrap_or(self.start),
+        }
+    }
+
+    /// Position of the last character which isn't whitespace,
+    /// where errors about the end of the input point.
+    fn end_position(&self) -> (usize, usize) {
+        lexer::advance(self.start, self.text.trim_end().as_bytes())
+    }
+}
+
+/// The state machine of the parser. It checks the order of the tokens
+/// and turns them into [`Event`]s, but keeps no values: only a frame
+/// per open container, so memory doesn't grow with the document.
+#[derive(Debug)]
+pub(crate) struct ParsingContext<'a> {
+    stack: Vec<ExpectingValue<'a>>,
+    expectation: Expectation<'a>,
+    options: ParseOptions,
 }
 
-impl ParsingLoopContext {
-    fn create_error(self, error: ParsingErrorKind) -> ParsingError {
-        ParsingError {
-            error,
-            context: self.base,
-            token_kind: Some(self.token.kind),
-            position: Some((self.token.line, self.token.column)),
+impl<'a> ParsingContext<'a> {
+    pub(crate) fn new(options: &ParseOptions) -> ParsingContext<'a> {
+        ParsingContext {
+            stack: Vec::new(),
+            expectation: Expectation::Value,
+            options: *options,
+        }
+    }
+
+    fn create_error(
+        &self,
+        error: ParsingErrorKind,
+        token: Option<RawToken<'_>>,
+        input: &str,
+    ) -> ParsingError {
+        self.create_error_in(error, token, &Text::new(input))
+    }
+
+    /// Like [`create_error`](Self::create_error), with spans and
+    /// positions relative to the whole input when only a part of it is kept.
+    fn create_error_in(
+        &self,
+        error: ParsingErrorKind,
+        token: Option<RawToken<'_>>,
+        text: &Text<'_>,
+    ) -> ParsingError {
+        match token {
+            Some(RawToken { kind, span }) => {
+                let position = text.position(span.end);
+                let mut error =
+                    ParsingError::new(error, self, Some(kind.into_owned()), span, position);
+                if let Some(first) = self.first_key_span(&error) {
+                    let position = text.position(first.end);
+                    error.set_first_key(first, position);
+                }
+                error
+            }
+            None => {
+                let end = text.offset + text.text.len();
+                ParsingError::new(error, self, None, end..end, text.end_position())
+            }
+        }
+    }
+
+    /// [`ParsingErrorKind::DocumentTooLarge`] for a text over `max_document_len`,
+    /// at the character which crosses the limit: what comes after it
+    /// doesn't matter, so neither does how much of it is known.
+    fn too_large_error(&self, input: &str) -> ParsingError {
+        self.too_large_error_in(&Text::new(input))
+    }
+
+    /// Like [`too_large_error`](Self::too_large_error), when only a part
+    /// of the input is kept.
+    fn too_large_error_in(&self, text: &Text<'_>) -> ParsingError {
+        let mut start = self.options.max_document_len - text.offset;
+        while !text.text.is_char_boundary(start) {
+            start -= 1;
+        }
+        let len = text.text[start..].chars().next().map_or(0, char::len_utf8);
+        let span = text.offset + start..text.offset + start + len;
+        let position = text.position(span.end);
+        ParsingError::new(
+            ParsingErrorKind::DocumentTooLarge,
+            self,
+            None,
+            span,
+            position,
+        )
+    }
+
+    /// Where the key of a [`ParsingErrorKind::DuplicateKey`] error first appeared.
+    fn first_key_span(&self, error: &ParsingError) -> Option<Range<usize>> {
+        match (&self.expectation, error.found()?) {
+            (Expectation::Obj { seen, .. }, TokenKind::String(key))
+                if error.kind() == ParsingErrorKind::DuplicateKey =>
+            {
+                seen.spans.get(key.as_ref()).cloned()
+            }
+            _ => None,
         }
     }
 
-    fn eat_token(mut self) -> Result<ParsingContext, ParsingError> {
-        match &mut self.base.expectation {
-            Expectation::Value => match self.token.kind {
-                TokenKind::String(string) => self.base.make_value(Value::String(string)),
-                TokenKind::Number(num) => self.base.make_value(Value::Number(num)),
-                TokenKind::True => self.base.make_value(Value::Bool(true)),
-                TokenKind::False => self.base.make_value(Value::Bool(false)),
-                TokenKind::Null => self.base.make_value(Value::Null),
+    pub(crate) fn parse(self, string: &'a str) -> Result<Value<'a>, ParsingError> {
+        let mut builder = TreeBuilder::new(&self.options);
+        self.parse_events(string, |event| builder.push(event))?;
+        Ok(builder.finish())
+    }
 
-                TokenKind::Whitespace => {}
+    /// Hands every event of `string` to `handler`, in order.
+    pub(crate) fn parse_events(
+        mut self,
+        string: &'a str,
+        mut handler: impl FnMut(Event<'a>),
+    ) -> Result<(), ParsingError> {
+        if string.len() > self.options.max_document_len {
+            return Err(self.too_large_error(string));
+        }
 
+        let options = self.options;
+        for RawToken { kind, span } in lexer::tokenize(string, &options) {
+            match self.eat_token(kind, &span) {
+                Ok(Some(event)) => handler(event),
+                Ok(None) => {}
+                Err((error, kind)) => {
+                    return Err(self.create_error(error, Some(RawToken { kind, span }), string));
+                }
+            }
+        }
+        if let Some(error) = self.end_error() {
+            return Err(self.create_error(error, None, string));
+        }
+        Ok(())
+    }
+
+    /// Why the input can't end here, if it can't.
+    fn end_error(&self) -> Option<ParsingErrorKind> {
+        let error = match &self.expectation {
+            Expectation::EndOfTokens => return None,
+            Expectation::Value => ParsingErrorKind::ExpectedValue,
+            Expectation::Obj { kv, .. } => match kv {
+                KvState::Start => ParsingErrorKind::ExpectedKey,
+                KvState::AteKey(_) => ParsingErrorKind::ExpectedColon,
+                KvState::AteValue => ParsingErrorKind::ExpectedCommaOrClosedCurly,
+            },
+            Expectation::CommaOrClosedBracket { len: _ } => {
+                ParsingErrorKind::ExpectedCommaOrClosedBracket
+            }
+        };
+        Some(error)
+    }
+
+    /// Feeds the next token to the state machine, and returns the event
+    /// it makes: commas and colons make none. A rejected token is handed
+    /// back with the reason, and the context stays as it was before it.
+    /// `span` is where the token is in the input.
+    fn eat_token(
+        &mut self,
+        token: TokenKind<'a>,
+        span: &Range<usize>,
+    ) -> Result<Option<Event<'a>>, (ParsingErrorKind, TokenKind<'a>)> {
+        if let TokenKind::Invalid(TokenizeError::StringTooLong) = &token {
+            return Err((ParsingErrorKind::StringTooLong, token));
+        }
+
+        let event = match &mut self.expectation {
+            Expectation::Value => match token {
+                TokenKind::String(string) => {
+                    self.make_value();
+                    Event::Scalar(Scalar::String(string))
+                }
+                TokenKind::Number(num) => {
+                    self.make_value();
+                    Event::Scalar(Scalar::Number(num))
+                }
+                TokenKind::True => {
+                    self.make_value();
+                    Event::Scalar(Scalar::Bool(true))
+                }
+                TokenKind::False => {
+                    self.make_value();
+                    Event::Scalar(Scalar::Bool(false))
+                }
+                TokenKind::Null => {
+                    self.make_value();
+                    Event::Scalar(Scalar::Null)
+                }
+
+                TokenKind::OpenCurly | TokenKind::OpenBracket
+                    if self.stack.len() >= self.options.max_depth =>
+                {
+                    return Err((ParsingErrorKind::DepthLimitExceeded, token));
+                }
                 TokenKind::OpenCurly => {
-                    self.base.expectation = Expectation::Obj {
-                        acc: BTreeMap::new(),
+                    self.expectation = Expectation::Obj {
+                        len: 0,
                         kv: KvState::Start,
+                        seen: SeenKeys::default(),
                     };
+                    Event::StartObject
                 }
                 TokenKind::OpenBracket => {
-                    self.base
-                        .stack
-                        .push(ExpectingValue::Arr { acc: Vec::new() });
+                    self.stack.push(ExpectingValue::Arr { len: 0 });
+                    Event::StartArray
                 }
 
                 TokenKind::ClosedBracket => {
-                    let Some(peeked) = self.base.stack.last() else {
-                        return Err(self.create_error(ParsingErrorKind::ExpectedValue))
+                    let Some(peeked) = self.stack.last() else {
+                        return Err((ParsingErrorKind::ExpectedValue, token));
                     };
 
-                    let acc = match peeked {
-                        ExpectingValue::Arr { acc } => acc,
-                        ExpectingValue::Obj { acc: _, key: _ } => {
-                            return Err(self.create_error(ParsingErrorKind::ExpectedValue))
+                    let len = match peeked {
+                        ExpectingValue::Arr { len } => *len,
+                        ExpectingValue::Obj { .. } => {
+                            return Err((ParsingErrorKind::ExpectedValue, token))
                         }
                     };
-                    if !acc.is_empty() {
-                        return Err(self.create_error(ParsingErrorKind::ExpectedValue));
+                    // [1, 2,]
+                    if len > 0 && !self.options.trailing_commas {
+                        return Err((ParsingErrorKind::TrailingComma, token));
                     }
-                    self.base.stack.pop();
-                    self.base.make_value(Value::Array(Vec::new()));
+                    self.stack.pop();
+                    self.make_value();
+                    Event::EndArray
                 }
 
-                TokenKind::Invalid(_) => return Err(self.create_error(ParsingErrorKind::Syntax)),
+                TokenKind::Invalid(_) => return Err((ParsingErrorKind::Syntax, token)),
 
-                _ => return Err(self.create_error(ParsingErrorKind::ExpectedValue)),
+                _ => return Err((ParsingErrorKind::ExpectedValue, token)),
             },
 
-            Expectation::Obj { acc, kv } => match kv {
-                KvState::Start => match self.token.kind {
-                    TokenKind::String(string) => *kv = KvState::AteKey(string),
+            Expectation::Obj { len, kv, seen } => match kv {
+                KvState::Start => match token {
+                    TokenKind::String(string) => {
+                        if self.options.duplicate_keys == DuplicateKeys::Error {
+                            if seen.spans.contains_key(&string) {
+                                return Err((
+                                    ParsingErrorKind::DuplicateKey,
+                                    TokenKind::String(string),
+                                ));
+                            }
+                            seen.spans.insert(string.clone(), span.clone());
+                        }
+                        *kv = KvState::AteKey(string.clone());
+                        Event::Key(string)
+                    }
                     TokenKind::ClosedCurly => {
-                        if acc.is_empty() {
-                            self.base.make_value(Value::Object(BTreeMap::new()));
-                            return Ok(self.base);
+                        if *len == 0 || self.options.trailing_commas {
+                            self.make_value();
+                            return Ok(Some(Event::EndObject));
                         }
-                        return Err(self.create_error(ParsingErrorKind::TrailingComma));
+                        return Err((ParsingErrorKind::TrailingComma, token));
                     }
-                    TokenKind::Whitespace => {}
 
-                    TokenKind::Invalid(_) => {
-                        return Err(self.create_error(ParsingErrorKind::Syntax))
-                    }
-                    _ => return Err(self.create_error(ParsingErrorKind::ExpectedKey)),
+                    TokenKind::Invalid(_) => return Err((ParsingErrorKind::Syntax, token)),
+                    _ => return Err((ParsingErrorKind::ExpectedKey, token)),
                 },
-                KvState::AteKey(key) => match self.token.kind {
+                KvState::AteKey(key) => match token {
                     TokenKind::Colon => {
-                        self.base.stack.push(ExpectingValue::Obj {
-                            acc: std::mem::take(acc),
-                            key: std::mem::take(key),
+                        self.stack.push(ExpectingValue::Obj {
+                            len: *len,
+                            key: mem::take(key),
+                            seen: mem::take(seen),
                         });
-                        self.base.expectation = Expectation::Value;
+                        self.expectation = Expectation::Value;
+                        return Ok(None);
                     }
-                    TokenKind::Whitespace => {}
 
-                    TokenKind::Invalid(_) => {
-                        return Err(self.create_error(ParsingErrorKind::Syntax))
-                    }
-                    _ => return Err(self.create_error(ParsingErrorKind::ExpectedColon)),
+                    TokenKind::Invalid(_) => return Err((ParsingErrorKind::Syntax, token)),
+                    _ => return Err((ParsingErrorKind::ExpectedColon, token)),
                 },
-                KvState::AteValue => match self.token.kind {
+                KvState::AteValue => match token {
                     TokenKind::Comma => {
                         *kv = KvState::Start;
+                        return Ok(None);
                     }
                     TokenKind::ClosedCurly => {
-                        let buf = Value::Object(std::mem::take(acc));
-                        self.base.make_value(buf);
+                        self.make_value();
+                        Event::EndObject
                     }
 
-                    TokenKind::Whitespace => {}
+                    TokenKind::Invalid(_) => return Err((ParsingErrorKind::Syntax, token)),
 
-                    TokenKind::Invalid(_) => {
-                        return Err(self.create_error(ParsingErrorKind::Syntax))
-                    }
-
-                    _ => {
-                        return Err(self.create_error(ParsingErrorKind::ExpectedCommaOrClosedCurly))
-                    }
+                    _ => return Err((ParsingErrorKind::ExpectedCommaOrClosedCurly, token)),
                 },
             },
-            Expectation::CommaOrClosedBracket { acc } => match self.token.kind {
+            Expectation::CommaOrClosedBracket { len } => match token {
                 TokenKind::Comma => {
-                    self.base.stack.push(ExpectingValue::Arr {
-                        acc: std::mem::take(acc),
-                    });
-                    self.base.expectation = Expectation::Value;
+                    self.stack.push(ExpectingValue::Arr { len: *len });
+                    self.expectation = Expectation::Value;
+                    return Ok(None);
                 }
                 TokenKind::ClosedBracket => {
-                    let buf = Value::Array(std::mem::take(acc));
-                    self.base.make_value(buf);
+                    self.make_value();
+                    Event::EndArray
                 }
 
-                TokenKind::Whitespace => {}
-
-                TokenKind::Invalid(_) => return Err(self.create_error(ParsingErrorKind::Syntax)),
-                _ => return Err(self.create_error(ParsingErrorKind::ExpectedCommaOrClosedBracket)),
+                TokenKind::Invalid(_) => return Err((ParsingErrorKind::Syntax, token)),
+                _ => return Err((ParsingErrorKind::ExpectedCommaOrClosedBracket, token)),
             },
-            Expectation::EndOfTokens(_) => {
-                if TokenKind::Whitespace == self.token.kind {
-                    return Ok(self.base);
-                }
-                return Err(self.create_error(ParsingErrorKind::ExpectedEndOfFile));
-            }
-        }
-
-        Ok(self.base)
-    }
-}
-
-impl ParsingContext {
-    pub(crate) fn new() -> ParsingContext {
-        ParsingContext {
-            stack: Vec::new(),
-            expectation: Expectation::Value,
-        }
-    }
-
-    pub(crate) fn parse(mut self, string: &str) -> Result<Value, ParsingError> {
-        for token in lexer::tokenize(string) {
-            let ctx = ParsingLoopContext { base: self, token };
-            self = ctx.eat_token()?;
-        }
-        let error = match self.expectation {
-            Expectation::EndOfTokens(value) => return Ok(value),
-            Expectation::Value => ParsingErrorKind::ExpectedValue,
-            Expectation::Obj { acc: _, ref kv } => match kv {
-                KvState::Start => ParsingErrorKind::ExpectedKey,
-                KvState::AteKey(_) => ParsingErrorKind::ExpectedCommaOrClosedCurly,
-                KvState::AteValue => ParsingErrorKind::ExpectedCommaOrClosedCurly,
-            },
-            Expectation::CommaOrClosedBracket { acc: _ } => {
-                ParsingErrorKind::ExpectedCommaOrClosedBracket
+            Expectation::EndOfTokens => {
+                return Err((ParsingErrorKind::ExpectedEndOfFile, token));
             }
         };
 
-        Err(ParsingError {
-            error,
-            context: self,
-            token_kind: None,
-            position: None,
-        })
+        Ok(Some(event))
     }
 
     // receive stack, not self
     // context: we find simple literal or finished creating a Value::(Obj or Arr)
-    fn make_value(&mut self, value: Value) {
+    fn make_value(&mut self) {
         let Some(popped) = self.stack.pop() else {
-            self.expectation = Expectation::EndOfTokens(value);
+            self.expectation = Expectation::EndOfTokens;
             return;
         };
         match popped {
-            ExpectingValue::Obj {
-                acc: mut pop_acc,
-                key: pop_key,
-            } => {
-                pop_acc.insert(pop_key, value);
+            ExpectingValue::Obj { len, key: _, seen } => {
                 self.expectation = Expectation::Obj {
-                    acc: pop_acc,
+                    len: len + 1,
                     kv: KvState::AteValue,
+                    seen,
                 };
             }
-            ExpectingValue::Arr { acc: mut pop_acc } => {
-                pop_acc.push(value);
-                self.expectation = Expectation::CommaOrClosedBracket { acc: pop_acc };
+            ExpectingValue::Arr { len } => {
+                self.expectation = Expectation::CommaOrClosedBracket { len: len + 1 };
             }
         }
         // match self.stack.pop() {
@@ -277,7 +473,7 @@ impl ParsingContext {
 }
 
 #[cfg(test)]
-mod test;
+pub(crate) mod test;
 
 // fn iterate_slice(slice: &[usize]) {
 //     match slice {
@@ -0,0 +1,118 @@
+use std::borrow::Cow;
+
+use crate::parser::{Event, Value};
+use crate::{DuplicateKeys, Map, ParseOptions};
+
+/// Builds a [`Value`] out of the events of the parser. The events are
+/// known to be well formed, the state machine has already checked them.
+#[derive(Debug)]
+pub(crate) struct TreeBuilder<'a> {
+    /// Containers from the root to the one being filled.
+    stack: Vec<Partial<'a>>,
+    /// The top-level value, once it's complete.
+    root: Option<Value<'a>>,
+    options: ParseOptions,
+}
+
+#[derive(Debug)]
+enum Partial<'a> {
+    Obj {
+        acc: Map<'a>,
+        /// Key of the member whose value comes next.
+        key: Option<Cow<'a, str>>,
+    },
+    Arr {
+        acc: Vec<Value<'a>>,
+    },
+}
+
+impl<'a> TreeBuilder<'a> {
+    pub(crate) fn new(options: &ParseOptions) -> TreeBuilder<'a> {
+        TreeBuilder {
+            stack: Vec::new(),
+            root: None,
+            options: *options,
+        }
+    }
+
+    pub(crate) fn push(&mut self, event: Event<'a>) {
+        match event {
+            Event::StartObject => self.stack.push(Partial::Obj {
+                acc: Map::new(),
+                key: None,
+            }),
+            Event::StartArray => self.stack.push(Partial::Arr { acc: Vec::new() }),
+            Event::Key(new_key) => {
+                if let Some(Partial::Obj { key, .. }) = self.stack.last_mut() {
+                    *key = Some(new_key);
+                }
+            }
+            Event::Scalar(scalar) => self.make_value(scalar.into()),
+            Event::EndObject | Event::EndArray => self.close_container(),
+        }
+    }
+
+    /// Takes the event of a token, if it made one.
+    pub(crate) fn push_some(&mut self, event: Option<Event<'a>>) {
+        if let Some(event) = event {
+            self.push(event);
+        }
+    }
+
+    /// Closes the innermost container with whatever it has by now.
+    /// A key without a value gets `Null`.
+    pub(crate) fn close_container(&mut self) {
+        let value = match self.stack.pop() {
+            Some(Partial::Obj { mut acc, key }) => {
+                if let Some(key) = key {
+                    acc.insert(key, Value::Null);
+                }
+                if self.options.sort_keys {
+                    acc.sort_keys();
+                }
+                Value::Object(acc)
+            }
+            Some(Partial::Arr { acc }) => Value::Array(acc),
+            None => Value::Null,
+        };
+        self.make_value(value);
+    }
+
+    /// Puts a complete value into its container.
+    pub(crate) fn make_value(&mut self, value: Value<'a>) {
+        let Some(container) = self.stack.last_mut() else {
+            self.root = Some(value);
+            return;
+        };
+        match container {
+            Partial::Obj { acc, key } => {
+                let Some(key) = key.take() else {
+                    unreachable!("a value in an object comes after its key");
+                };
+                match self.options.duplicate_keys {
+                    DuplicateKeys::FirstWins => {
+                        if !acc.contains_key(&key) {
+                            acc.insert(key, value);
+                        }
+                    }
+                    DuplicateKeys::LastWins | DuplicateKeys::Error => {
+                        acc.insert(key, value);
+                    }
+                    DuplicateKeys::Collect => {
+                        if acc.contains_key(&key) {
+                            acc.push_duplicate(key, value);
+                        } else {
+                            acc.insert(key, value);
+                        }
+                    }
+                }
+            }
+            Partial::Arr { acc } => acc.push(value),
+        }
+    }
+
+    pub(crate) fn finish(self) -> Value<'a> {
+        self.root
+            .expect("the input can end only after the top-level value")
+    }
+}
@@ -0,0 +1,180 @@
+use memchr::{memchr, memrchr};
+
+use crate::lexer::{self, Cursor, RawToken};
+use crate::parser::{Expectation, ParsingContext, ParsingError, Text, TreeBuilder, Value};
+use crate::ParseOptions;
+
+/// Iterator over the top-level values of a text holding several,
+/// like JSON Lines (NDJSON) or values simply written one after another.
+///
+/// A value ends where its last token does, so values need nothing
+/// between them unless they'd run together, like two numbers.
+/// A malformed value gives its error, and the iteration goes on at the
+/// start of the next line, or of the line of the error if the value
+/// started on an earlier one: it was likely cut short by the newline.
+/// For the same reason, a value which runs into the end of the text
+/// fails at the end of the line it starts on, and a string can't hold
+/// a raw newline: it's cut short at the end of its line.
+/// Errors are placed in the whole text, [`ParsingError::line`] is the
+/// line of the text and not a line of the value.
+///
+/// [`ParseOptions`] apply to each value, except `max_document_len`,
+/// which limits the whole text.
+///
+/// ```
+/// use json::{json, Documents};
+///
+/// let lines = "{\"level\": \"info\"}\n{\"level\": \n{\"level\": \"warn\"}\n";
+/// let mut documents = Documents::new(lines);
+/// assert_eq!(documents.next().unwrap().unwrap(), json!({"level": "info"}));
+/// assert_eq!(documents.next().unwrap().unwrap_err().line(), 2);
+/// assert_eq!(documents.next().unwrap().unwrap(), json!({"level": "warn"}));
+/// assert!(documents.next().is_none());
+/// ```
+pub struct Documents<'a> {
+    input: &'a str,
+    options: ParseOptions,
+    /// Where the next value is looked for.
+    offset: usize,
+    /// A byte offset with its line and column, moved forward value
+    /// by value, so errors aren't counted from the start of the text.
+    counted: (usize, (usize, usize)),
+}
+
+impl<'a> Documents<'a> {
+    pub fn new(input: &'a str) -> Documents<'a> {
+        Documents::with_options(input, &ParseOptions::default())
+    }
+
+    pub fn with_options(input: &'a str, options: &ParseOptions) -> Documents<'a> {
+        Documents {
+            input,
+            options: *options,
+            offset: 0,
+            counted: (0, (1, 0)),
+        }
+    }
+}
+
+impl<'a> Iterator for Documents<'a> {
+    type Item = Result<Value<'a>, ParsingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset == 0 && self.input.len() > self.options.max_document_len {
+            self.offset = self.input.len();
+            let context = ParsingContext::new(&self.options);
+            return Some(Err(context.too_large_error(self.input)));
+        }
+        let (from, position) = self.counted;
+        let bytes = &self.input.as_bytes()[from..self.offset];
+        self.counted = (self.offset, lexer::advance(position, bytes));
+        let parsed = parse_value(self.input, self.counted, &self.options)?;
+        self.offset = parsed.next;
+
+        // A value which runs into the end of the text, past the end of the line
+        // it starts on, was most likely cut short by the newline. The error
+        // is reported on that line, and the values after it are still read.
+        let ends_early = matches!(&parsed.result, Err(error) if error.found().is_none());
+        let line_end = memchr(b'\n', &self.input.as_bytes()[parsed.start..])
+            .map_or(self.input.len(), |newline| parsed.start + newline);
+        if ends_early && line_end < self.input.trim_end().len() {
+            let line = &self.input[..line_end];
+            let cut = parse_value(line, self.counted, &self.options)
+                .expect("the line holds the start of the value");
+            self.offset = line_end + 1;
+            return Some(cut.result);
+        }
+        Some(parsed.result)
+    }
+}
+
+struct Parsed<'a> {
+    result: Result<Value<'a>, ParsingError>,
+    /// Where the value starts.
+    start: usize,
+    /// Where to look for the next one.
+    next: usize,
+}
+
+/// Parses the value which starts after `offset`, if there's one.
+/// `position` is the line and column there.
+fn parse_value<'a>(
+    input: &'a str,
+    (offset, position): (usize, (usize, usize)),
+    options: &ParseOptions,
+) -> Option<Parsed<'a>> {
+    let text = Text {
+        text: &input[offset..],
+        offset,
+        start: position,
+        keys: None,
+    };
+    let mut context = ParsingContext::new(options);
+    let mut builder = TreeBuilder::new(options);
+    let mut tokens = lexer::tokenize(input, options);
+    Cursor::skip(&mut tokens, offset);
+    tokens.reset_token_len();
+
+    let mut start = None;
+    for RawToken { kind, span } in tokens {
+        let start = *start.get_or_insert(span.start);
+        // A string going on past the end of its line is an unterminated one
+        // which took the quote of a later line. It fails on its own line.
+        let bytes = &input.as_bytes()[span.clone()];
+        if let (Some(b'"'), Some(newline)) = (bytes.first(), memchr(b'\n', bytes)) {
+            let line_end = span.start + newline;
+            let mut cut = parse_value(&input[..line_end], (offset, position), options)
+                .expect("the line holds the start of the value");
+            cut.next = line_end + 1;
+            return Some(cut);
+        }
+        match context.eat_token(kind, &span) {
+            Ok(event) => {
+                builder.push_some(event);
+                if let Expectation::EndOfTokens = context.expectation {
+                    let result = Ok(builder.finish());
+                    let next = span.end;
+                    return Some(Parsed {
+                        result,
+                        start,
+                        next,
+                    });
+                }
+            }
+            Err((error, kind)) => {
+                let next = resume_at(input, start, span.start);
+                let token = RawToken { kind, span };
+                let result = Err(context.create_error_in(error, Some(token), &text));
+                return Some(Parsed {
+                    result,
+                    start,
+                    next,
+                });
+            }
+        }
+    }
+    // `None` when only whitespace is left
+    let start = start?;
+    let error = context
+        .end_error()
+        .expect("a complete value returns right away");
+    let result = Err(context.create_error_in(error, None, &text));
+    let next = input.len();
+    Some(Parsed {
+        result,
+        start,
+        next,
+    })
+}
+
+/// Where to go on after an error at `error` in the value which starts
+/// at `start`: at the line of the error if the value started on
+/// an earlier line, at the line after it otherwise.
+fn resume_at(input: &str, start: usize, error: usize) -> usize {
+    let bytes = input.as_bytes();
+    let line_start = memrchr(b'\n', &bytes[..error]).map_or(0, |newline| newline + 1);
+    if line_start > start {
+        return line_start;
+    }
+    memchr(b'\n', &bytes[error..]).map_or(bytes.len(), |newline| error + newline + 1)
+}
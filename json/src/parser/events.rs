@@ -0,0 +1,124 @@
+use std::borrow::Cow;
+
+use crate::lexer::{self, Cursor, RawToken};
+use crate::number::Number;
+use crate::parser::{ParsingContext, ParsingError, Value};
+use crate::ParseOptions;
+
+/// A step through a JSON document, in the order of the text.
+///
+/// Containers are opened and closed by pairs of events,
+/// a member of an object is its key followed by the events of its value:
+/// `{"a": [1]}` is `StartObject`, `Key("a")`, `StartArray`,
+/// `Scalar(Number(1))`, `EndArray`, `EndObject`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event<'a> {
+    StartObject,
+    /// Key of the next member, borrowed from the input unless it has escapes.
+    Key(Cow<'a, str>),
+    EndObject,
+    StartArray,
+    EndArray,
+    Scalar(Scalar<'a>),
+}
+
+/// A value which isn't a container.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Scalar<'a> {
+    Null,
+    Bool(bool),
+    Number(Number),
+    /// Borrowed from the input unless the literal has escapes.
+    String(Cow<'a, str>),
+}
+
+impl<'a> From<Scalar<'a>> for Value<'a> {
+    fn from(scalar: Scalar<'a>) -> Value<'a> {
+        match scalar {
+            Scalar::Null => Value::Null,
+            Scalar::Bool(bool) => Value::Bool(bool),
+            Scalar::Number(number) => Value::Number(number),
+            Scalar::String(string) => Value::String(string),
+        }
+    }
+}
+
+/// Lazy iterator over the [`Event`]s of a JSON text.
+///
+/// Memory use depends on how deep containers are nested, not on how
+/// big the document is: nothing is kept of the values already passed.
+/// The one exception is [`DuplicateKeys::Error`](crate::DuplicateKeys::Error),
+/// which remembers the keys of every open object.
+///
+/// The iteration stops after the first error. The other
+/// [`ParseOptions`] apply as they do to [`parse_with`](crate::parse_with),
+/// except `sort_keys` and the duplicate key policies which pick values:
+/// every member is reported as it appears.
+///
+/// ```
+/// use json::{Event, Events, Scalar};
+///
+/// let mut sum = 0;
+/// for event in Events::new(r#"{"a": [1, 2], "b": {"c": 3}}"#) {
+///     if let Event::Scalar(Scalar::Number(number)) = event.unwrap() {
+///         sum += number.as_u64().unwrap();
+///     }
+/// }
+/// assert_eq!(sum, 6);
+/// ```
+pub struct Events<'a> {
+    context: ParsingContext<'a>,
+    tokens: Cursor<'a>,
+    input: &'a str,
+    /// After the end of the input or an error.
+    done: bool,
+}
+
+impl<'a> Events<'a> {
+    pub fn new(input: &'a str) -> Events<'a> {
+        Events::with_options(input, &ParseOptions::default())
+    }
+
+    pub fn with_options(input: &'a str, options: &ParseOptions) -> Events<'a> {
+        Events {
+            context: ParsingContext::new(options),
+            tokens: lexer::tokenize(input, options),
+            input,
+            done: false,
+        }
+    }
+}
+
+impl<'a> Iterator for Events<'a> {
+    type Item = Result<Event<'a>, ParsingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        // nothing is read yet
+        if self.tokens.offset() == 0 && self.input.len() > self.context.options.max_document_len {
+            self.done = true;
+            return Some(Err(self.context.too_large_error(self.input)));
+        }
+
+        for RawToken { kind, span } in self.tokens.by_ref() {
+            match self.context.eat_token(kind, &span) {
+                Ok(Some(event)) => return Some(Ok(event)),
+                Ok(None) => {}
+                Err((error, kind)) => {
+                    self.done = true;
+                    let token = RawToken { kind, span };
+                    return Some(Err(self.context.create_error(
+                        error,
+                        Some(token),
+                        self.input,
+                    )));
+                }
+            }
+        }
+        self.done = true;
+        let error = self.context.end_error()?;
+        Some(Err(self.context.create_error(error, None, self.input)))
+    }
+}
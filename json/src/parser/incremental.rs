@@ -0,0 +1,420 @@
+use std::collections::BTreeMap;
+
+use crate::lexer::{self, RawToken, TokenKind, TokenizeError};
+use crate::parser::{Event, Expectation, ParsingContext, ParsingError, Text, TreeBuilder, Value};
+use crate::{DuplicateKeys, ParseOptions};
+
+/// The lexer looks at most this many bytes past the end of an invalid
+/// token to find where it ends: `\u` after a high surrogate, in a string.
+const LOOKAHEAD: usize = 2;
+
+/// Whether the text fed to an [`IncrementalParser`] holds a whole value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// The value isn't complete yet. A number at the very end is
+    /// counted as incomplete: the next piece may add digits to it.
+    NeedMoreInput,
+    /// The top-level value is complete, [`IncrementalParser::finish`]
+    /// returns it unless more text other than whitespace comes.
+    Complete,
+}
+
+/// Parses a JSON text given in pieces, like one arriving over a socket.
+///
+/// The pieces may split tokens anywhere between two characters. Only the
+/// text after the last complete token is kept, values go into the tree
+/// as soon as they're read, so strings and keys are always owned.
+/// Errors, spans and positions are the same as if the whole text were
+/// given to [`parse_with`](crate::parse_with) at once. A text over
+/// `max_document_len` fails as soon as the piece which crosses it comes.
+///
+/// After an error the parser stays broken, feeding it more fails again.
+///
+/// ```
+/// use json::{IncrementalParser, Status};
+///
+/// let mut parser = IncrementalParser::new();
+/// assert_eq!(parser.feed(r#"{"pony": "Rar"#).unwrap(), Status::NeedMoreInput);
+/// assert_eq!(parser.feed(r#"ity", "age": 1"#).unwrap(), Status::NeedMoreInput);
+/// assert_eq!(parser.feed("9}\n").unwrap(), Status::Complete);
+/// assert_eq!(parser.finish().unwrap(), json::json!({"pony": "Rarity", "age": 19}));
+/// ```
+#[derive(Debug)]
+pub struct IncrementalParser {
+    context: ParsingContext<'static>,
+    builder: TreeBuilder<'static>,
+    options: ParseOptions,
+    /// The text from the end of the last complete token.
+    buffer: String,
+    /// Byte offset of `buffer` in the whole text.
+    offset: usize,
+    /// Line and column of the character right before `buffer`.
+    start: (usize, usize),
+    /// Where the keys of the open objects end, by byte offset.
+    /// Only filled for [`DuplicateKeys::Error`], which points at the first key.
+    keys: BTreeMap<usize, (usize, usize)>,
+    /// Byte offsets of the open objects, to forget their keys once closed.
+    objects: Vec<usize>,
+    /// The buffer ends in a string. It can't be complete before a quote comes,
+    /// and lexing it again for every piece would take quadratic time.
+    string: Option<PendingString>,
+}
+
+/// A string at the end of the buffer, measured as pieces come,
+/// so `max_string_len` holds before it ends.
+#[derive(Debug)]
+struct PendingString {
+    /// Byte offset of the opening quote in the buffer.
+    start: usize,
+    /// How far the buffer is measured, the end of the last whole
+    /// character or escape.
+    scanned: usize,
+    /// The least length the content can have once unescaped:
+    /// every escape counts one byte.
+    min_len: usize,
+}
+
+impl Default for IncrementalParser {
+    fn default() -> IncrementalParser {
+        IncrementalParser::new()
+    }
+}
+
+impl IncrementalParser {
+    pub fn new() -> IncrementalParser {
+        IncrementalParser::with_options(&ParseOptions::default())
+    }
+
+    pub fn with_options(options: &ParseOptions) -> IncrementalParser {
+        IncrementalParser {
+            context: ParsingContext::new(options),
+            builder: TreeBuilder::new(options),
+            options: *options,
+            buffer: String::new(),
+            offset: 0,
+            start: (1, 0),
+            keys: BTreeMap::new(),
+            objects: Vec::new(),
+            string: None,
+        }
+    }
+
+    /// Parses the tokens `text` completes. A token which reaches the
+    /// end of the text waits for the next piece, it may go on there.
+    pub fn feed(&mut self, text: &str) -> Result<Status, ParsingError> {
+        self.buffer.push_str(text);
+        if self.offset + self.buffer.len() > self.options.max_document_len {
+            let context = ParsingContext::new(&self.options);
+            return Err(context.too_large_error_in(&self.text()));
+        }
+        if self.string.is_none() || text.contains('"') {
+            self.parse_buffer(false)?;
+        }
+        self.check_string_len()?;
+        Ok(self.status())
+    }
+
+    /// The status after the last piece, as [`feed`](Self::feed) returned it.
+    pub fn status(&self) -> Status {
+        match self.context.expectation {
+            Expectation::EndOfTokens => Status::Complete,
+            _ => Status::NeedMoreInput,
+        }
+    }
+
+    /// Parses the rest of the text and returns the value,
+    /// or the error [`parse_with`](crate::parse_with) gives if the text ends too early.
+    pub fn finish(mut self) -> Result<Value<'static>, ParsingError> {
+        self.parse_buffer(true)?;
+        if let Some(error) = self.context.end_error() {
+            return Err(self.context.create_error_in(error, None, &self.text()));
+        }
+        Ok(self.builder.finish())
+    }
+
+    fn text(&self) -> Text<'_> {
+        Text {
+            text: &self.buffer,
+            offset: self.offset,
+            start: self.start,
+            keys: Some(&self.keys),
+        }
+    }
+
+    /// Parses the complete tokens of the buffer and drops their text.
+    /// At the `end` of the input every token is complete.
+    fn parse_buffer(&mut self, end: bool) -> Result<(), ParsingError> {
+        let track_keys = self.options.duplicate_keys == DuplicateKeys::Error;
+        // the end of the last token parsed, relative to the buffer
+        let mut parsed = 0;
+        // a position in the buffer with its line and column, moved
+        // forward as keys are found, so the buffer is counted once
+        let mut counted = (0, self.start);
+        self.string = None;
+
+        for RawToken { kind, span } in lexer::tokenize(&self.buffer, &self.options) {
+            // what the lexer has to see past a token to know it ends there
+            let lookahead = match kind {
+                TokenKind::Number(_) => 1,
+                TokenKind::Invalid(_) => LOOKAHEAD,
+                _ => 0,
+            };
+            if !end && self.buffer.len() - span.end < lookahead {
+                if self.buffer.as_bytes()[span.start] == b'"' {
+                    let start = span.start - parsed;
+                    self.string = Some(PendingString {
+                        start,
+                        scanned: start + 1,
+                        min_len: 0,
+                    });
+                }
+                break;
+            }
+            let kind = match kind {
+                TokenKind::Invalid(TokenizeError::UnescapedControlChar { char, line, column }) => {
+                    let (line, column) = shift(self.start, (line, column));
+                    TokenKind::Invalid(TokenizeError::UnescapedControlChar { char, line, column })
+                }
+                kind => kind.into_owned(),
+            };
+            let relative_end = span.end;
+            let span = span.start + self.offset..span.end + self.offset;
+
+            match self.context.eat_token(kind, &span) {
+                Ok(Some(event)) => {
+                    if track_keys {
+                        match &event {
+                            Event::StartObject => self.objects.push(span.start),
+                            Event::EndObject => {
+                                if let Some(start) = self.objects.pop() {
+                                    self.keys.split_off(&start);
+                                }
+                            }
+                            Event::Key(_) => {
+                                let (from, position) = counted;
+                                let bytes = &self.buffer.as_bytes()[from..relative_end];
+                                counted = (relative_end, lexer::advance(position, bytes));
+                                self.keys.insert(span.end, counted.1);
+                            }
+                            _ => {}
+                        }
+                    }
+                    self.builder.push(event);
+                }
+                Ok(None) => {}
+                Err((error, kind)) => {
+                    let token = RawToken { kind, span };
+                    return Err(self
+                        .context
+                        .create_error_in(error, Some(token), &self.text()));
+                }
+            }
+            parsed = relative_end;
+        }
+
+        self.start = lexer::advance(self.start, &self.buffer.as_bytes()[..parsed]);
+        self.offset += parsed;
+        self.buffer.drain(..parsed);
+        Ok(())
+    }
+
+    /// Measures the new text of the pending string, and fails like
+    /// [`parse_with`](crate::parse_with) once it's sure to be too long.
+    fn check_string_len(&mut self) -> Result<(), ParsingError> {
+        let Some(string) = &mut self.string else {
+            return Ok(());
+        };
+        let bytes = self.buffer.as_bytes();
+        while string.scanned < bytes.len() {
+            let len = match bytes[string.scanned] {
+                b'\\' => match escape_len(&self.buffer[string.scanned..]) {
+                    Some(len) => len,
+                    None => break,
+                },
+                _ => 1,
+            };
+            if string.scanned + len > bytes.len() {
+                break;
+            }
+            string.scanned += len;
+            string.min_len += 1;
+        }
+        if string.min_len <= self.options.max_string_len {
+            return Ok(());
+        }
+
+        // The lexer stops where the limit is crossed, which is within
+        // what's measured, unless something in it is invalid already.
+        // Both depend on the text up to there only.
+        let (start, scanned) = (string.start, string.scanned);
+        let cut = &self.buffer[start..scanned];
+        let Some(RawToken { kind, span }) = lexer::tokenize(cut, &self.options).next() else {
+            return Ok(());
+        };
+        let kind = match kind {
+            TokenKind::Invalid(TokenizeError::UnescapedControlChar { char, line, column }) => {
+                let before = lexer::advance(self.start, &self.buffer.as_bytes()[..start]);
+                let (line, column) = shift(before, (line, column));
+                TokenKind::Invalid(TokenizeError::UnescapedControlChar { char, line, column })
+            }
+            TokenKind::Invalid(error) => TokenKind::Invalid(error),
+            _ => return Ok(()),
+        };
+        let span = self.offset + start + span.start..self.offset + start + span.end;
+        match self.context.eat_token(kind, &span) {
+            Err((error, kind)) => {
+                let token = RawToken { kind, span };
+                Err(self
+                    .context
+                    .create_error_in(error, Some(token), &self.text()))
+            }
+            Ok(_) => Ok(()),
+        }
+    }
+}
+
+/// Length of the escape `bytes` start with, or `None` until enough of it
+/// came. A high surrogate followed by another `\u` escape is taken
+/// together with it, like the lexer decodes them.
+fn escape_len(text: &str) -> Option<usize> {
+    let symbol = text[1..].chars().next()?;
+    if symbol != 'u' {
+        return Some(1 + symbol.len_utf8());
+    }
+    let bytes = text.as_bytes();
+    let hex = bytes.get(2..6)?;
+    if !hex.iter().all(u8::is_ascii_hexdigit) {
+        // invalid, the lexer stops there
+        return Some(2);
+    }
+    let unit = std::str::from_utf8(hex).map(|hex| u16::from_str_radix(hex, 16));
+    if !matches!(unit, Ok(Ok(0xD800..=0xDBFF))) {
+        return Some(6);
+    }
+    match bytes.get(6..8)? {
+        b"\\u" => Some(12),
+        _ => Some(6),
+    }
+}
+
+/// Moves a position counted from the start of the buffer
+/// to the whole text, where the buffer starts after `start`.
+fn shift(start: (usize, usize), (line, column): (usize, usize)) -> (usize, usize) {
+    if line == 1 {
+        (start.0, start.1 + column)
+    } else {
+        (start.0 + line - 1, column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+
+    /// Feeds `pieces` one by one and collects the statuses.
+    fn statuses(parser: &mut IncrementalParser, pieces: &[&str]) -> Vec<Status> {
+        pieces
+            .iter()
+            .map(|piece| parser.feed(piece).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn status() {
+        use Status::{Complete, NeedMoreInput};
+
+        let mut parser = IncrementalParser::new();
+        let pieces = ["", " [", "1, {\"a", "\": tr", "ue}", "]", " \n"];
+        let expected = [
+            NeedMoreInput,
+            NeedMoreInput,
+            NeedMoreInput,
+            NeedMoreInput,
+            NeedMoreInput,
+            Complete,
+            Complete,
+        ];
+        assert_eq!(statuses(&mut parser, &pieces), expected);
+        assert_eq!(parser.status(), Complete);
+        assert_eq!(parser.finish().unwrap(), json!([1, {"a": true}]));
+
+        // digits may follow
+        let mut parser = IncrementalParser::new();
+        let pieces = ["12", "3", " "];
+        let expected = [NeedMoreInput, NeedMoreInput, Complete];
+        assert_eq!(statuses(&mut parser, &pieces), expected);
+        assert_eq!(parser.finish().unwrap(), json!(123));
+
+        let mut parser = IncrementalParser::new();
+        assert_eq!(parser.feed("-1.5e3").unwrap(), NeedMoreInput);
+        assert_eq!(parser.finish().unwrap(), crate::parse("-1.5e3").unwrap());
+    }
+
+    #[test]
+    fn long_strings() {
+        let mut parser = IncrementalParser::new();
+        parser.feed("[\"").unwrap();
+        for _ in 0..1000 {
+            assert_eq!(parser.feed("\u{e9}\\n").unwrap(), Status::NeedMoreInput);
+        }
+        assert_eq!(parser.feed("\"]").unwrap(), Status::Complete);
+        assert_eq!(parser.finish().unwrap(), json!(["\u{e9}\n".repeat(1000)]));
+    }
+
+    #[test]
+    fn string_limit() {
+        let options = ParseOptions::new().max_string_len(4);
+        let mut parser = IncrementalParser::with_options(&options);
+        parser.feed("[\"").unwrap();
+        parser.feed("ab\\u00").unwrap();
+        parser.feed("41").unwrap();
+        let error = parser.feed("0123456789").unwrap_err();
+        assert_eq!(error.kind(), crate::ParsingErrorKind::StringTooLong);
+        // up to the character which crosses the limit, like `parse_with`
+        assert_eq!(error.span(), 1..12);
+        assert_eq!(error.path(), "$[0]");
+        assert_eq!(
+            error.to_string(),
+            "String is too long at line 1, column 12, in $[0]"
+        );
+        let text = "[\"ab\\u00410123456789";
+        assert_eq!(
+            format!("{error:?}"),
+            format!("{:?}", crate::parse_with(text, &options).unwrap_err())
+        );
+        // and stays broken
+        assert!(parser.feed("0123456789").is_err());
+
+        // escapes count once unescaped
+        let mut parser = IncrementalParser::with_options(&options);
+        for piece in ["\"\\u0041", "\\u0042\\n", "\\t\""] {
+            parser.feed(piece).unwrap();
+        }
+        assert_eq!(parser.finish().unwrap(), json!("AB\n\t"));
+    }
+
+    #[test]
+    fn errors() {
+        let mut parser = IncrementalParser::new();
+        parser.feed("{\"a\": 1,\n").unwrap();
+        let error = parser.feed(" 2}").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Expected string but found number 2 unexpectedly at line 2, column 2"
+        );
+        // stays broken
+        let again = parser.feed("\"b\": 2}").unwrap_err();
+        assert_eq!(again.to_string(), error.to_string());
+
+        let mut parser = IncrementalParser::new();
+        parser.feed("[1, 2").unwrap();
+        let error = parser.finish().unwrap_err();
+        let expected = crate::parse("[1, 2").unwrap_err();
+        assert_eq!(error.to_string(), expected.to_string());
+
+        let mut parser = IncrementalParser::new();
+        parser.feed("[1] ").unwrap();
+        assert!(parser.feed("[").is_err());
+    }
+}
@@ -1,43 +1,414 @@
 use std::error::Error;
 use std::fmt;
+use std::ops::Range;
 
 // use super::{ParsingContext, ParsingError, Expectation, ExpectingValue};
 use crate::lexer::{TokenKind, TokenizeError};
 use crate::parser::{Expectation, ExpectingValue, KvState, ParsingContext};
 
+/// Why and where parsing failed.
+///
+/// `Display` gives a message for people, the accessors give
+/// the same information piece by piece for programs.
 #[derive(Debug)]
 pub struct ParsingError {
-    pub(crate) error: ParsingErrorKind,
-    pub(crate) context: ParsingContext,
-    pub(crate) token_kind: Option<TokenKind>,
-    pub(crate) position: Option<(usize, usize)>,
+    // Boxed to keep `Result<Value, ParsingError>` small.
+    inner: Box<ErrorInner>,
+}
+
+#[derive(Debug)]
+struct ErrorInner {
+    kind: ParsingErrorKind,
+    context: ErrorContext,
+    /// `None` when the input ended too early.
+    token_kind: Option<TokenKind<'static>>,
+    span: Range<usize>,
+    position: (usize, usize),
+    /// Span and position of the first occurrence, for duplicate keys.
+    first_key: Option<(Range<usize>, (usize, usize))>,
 }
 
 impl Error for ParsingError {}
 
+/// Where the parser stood when it failed. Unlike [`ParsingContext`],
+/// it doesn't borrow from the input, so errors outlive it.
+#[derive(Debug)]
+struct ErrorContext {
+    /// Containers from the root to the failing location.
+    stack: Vec<Frame>,
+    expectation: Place,
+}
+
 #[derive(Debug)]
-pub(crate) enum ParsingErrorKind {
+enum Frame {
+    Obj {
+        key: String,
+    },
+    /// `len` values of the array are already parsed.
+    Arr {
+        len: usize,
+    },
+}
+
+#[derive(Debug)]
+enum Place {
+    Value,
+    Key { first: bool },
+    Colon { key: String },
+    CommaOrClosedCurly,
+    CommaOrClosedBracket,
+    EndOfTokens,
+}
+
+impl ErrorContext {
+    fn new(context: &ParsingContext<'_>) -> ErrorContext {
+        let stack = context
+            .stack
+            .iter()
+            .map(|frame| match frame {
+                ExpectingValue::Obj { key, .. } => Frame::Obj {
+                    key: key.to_string(),
+                },
+                ExpectingValue::Arr { len } => Frame::Arr { len: *len },
+            })
+            .collect();
+
+        let expectation = match &context.expectation {
+            Expectation::Value => Place::Value,
+            Expectation::Obj { len, kv, .. } => match kv {
+                KvState::Start => Place::Key { first: *len == 0 },
+                KvState::AteKey(key) => Place::Colon {
+                    key: key.to_string(),
+                },
+                KvState::AteValue => Place::CommaOrClosedCurly,
+            },
+            Expectation::CommaOrClosedBracket { len: _ } => Place::CommaOrClosedBracket,
+            Expectation::EndOfTokens => Place::EndOfTokens,
+        };
+
+        ErrorContext { stack, expectation }
+    }
+}
+
+/// What kind of token the parser met instead of an expected one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParsingErrorKind {
+    /// The lexer couldn't make a token, see [`TokenKind::Invalid`].
     Syntax,
     ExpectedValue,
     ExpectedKey,
+    /// A complete value is followed by something else.
     ExpectedEndOfFile,
     ExpectedColon,
+    /// A comma right before the closing curly.
     TrailingComma,
     ExpectedCommaOrClosedCurly,
     ExpectedCommaOrClosedBracket,
+    /// More arrays and objects nested in each other than
+    /// [`ParseOptions::max_depth`](crate::ParseOptions::max_depth) allows.
+    DepthLimitExceeded,
+    /// See [`ParseOptions::max_string_len`](crate::ParseOptions::max_string_len).
+    StringTooLong,
+    /// See [`ParseOptions::max_document_len`](crate::ParseOptions::max_document_len).
+    DocumentTooLarge,
+    /// With [`DuplicateKeys::Error`](crate::DuplicateKeys::Error).
+    DuplicateKey,
+}
+
+/// A token the parser would have accepted at the place of the error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expected {
+    /// Any value: a literal, an object or an array.
+    Value,
+    /// Object key.
+    String,
+    Colon,
+    Comma,
+    ClosedCurly,
+    ClosedBracket,
+    EndOfFile,
+}
+
+impl ParsingError {
+    pub(crate) fn new(
+        kind: ParsingErrorKind,
+        context: &ParsingContext<'_>,
+        token_kind: Option<TokenKind<'static>>,
+        span: Range<usize>,
+        position: (usize, usize),
+    ) -> ParsingError {
+        ParsingError {
+            inner: Box::new(ErrorInner {
+                kind,
+                context: ErrorContext::new(context),
+                token_kind,
+                span,
+                position,
+                first_key: None,
+            }),
+        }
+    }
+
+    pub(crate) fn set_first_key(&mut self, span: Range<usize>, position: (usize, usize)) {
+        self.inner.first_key = Some((span, position));
+    }
+
+    pub fn kind(&self) -> ParsingErrorKind {
+        self.inner.kind
+    }
+
+    /// Line of the last character of the offending token, starting from 1.
+    /// If the input ended too early, it's the line of the last character
+    /// before the trailing whitespace.
+    pub fn line(&self) -> usize {
+        self.inner.position.0
+    }
+
+    /// Column of the last character of the offending token, starting from 1.
+    /// If the input ended too early, it's the column of the last character
+    /// before the trailing whitespace.
+    pub fn column(&self) -> usize {
+        self.inner.position.1
+    }
+
+    /// Byte offset of the first character of the offending token,
+    /// or the length of the input if it ended too early.
+    pub fn offset(&self) -> usize {
+        self.inner.span.start
+    }
+
+    /// Byte range of the offending token, empty at the end of the input.
+    pub fn span(&self) -> Range<usize> {
+        self.inner.span.clone()
+    }
+
+    /// The offending token, or `None` if the input ended too early.
+    /// Its `Display` describes it in a few words.
+    pub fn found(&self) -> Option<&TokenKind<'static>> {
+        self.inner.token_kind.as_ref()
+    }
+
+    /// For [`ParsingErrorKind::DuplicateKey`], byte range of the key
+    /// where it first appeared. The error itself points at the repeated one.
+    pub fn first_key_span(&self) -> Option<Range<usize>> {
+        let (span, _) = self.inner.first_key.as_ref()?;
+        Some(span.clone())
+    }
+
+    /// For [`ParsingErrorKind::DuplicateKey`], line and column of the key
+    /// where it first appeared, counted like [`line`](Self::line) and [`column`](Self::column).
+    pub fn first_key_position(&self) -> Option<(usize, usize)> {
+        let (_, position) = self.inner.first_key.as_ref()?;
+        Some(*position)
+    }
+
+    /// Tokens that would have been accepted instead.
+    pub fn expected(&self) -> &'static [Expected] {
+        match &self.inner.context.expectation {
+            Place::Value => match self.inner.context.stack.last() {
+                Some(Frame::Arr { len: 0 }) => &[Expected::Value, Expected::ClosedBracket],
+                _ => &[Expected::Value],
+            },
+            Place::Key { first: true } => &[Expected::String, Expected::ClosedCurly],
+            Place::Key { first: false } => &[Expected::String],
+            Place::Colon { key: _ } => &[Expected::Colon],
+            Place::CommaOrClosedCurly => &[Expected::Comma, Expected::ClosedCurly],
+            Place::CommaOrClosedBracket => &[Expected::Comma, Expected::ClosedBracket],
+            Place::EndOfTokens => &[Expected::EndOfFile],
+        }
+    }
+}
+
+/// A step from a container into one of its children.
+pub(crate) enum Segment<'e> {
+    Key(&'e str),
+    Index(usize),
+}
+
+/// Writes steps from the root as a JSONPath, like `$.menu.items[3]`.
+/// Keys which aren't identifiers are quoted: `$['two words']`.
+pub(crate) fn json_path<'e>(segments: impl IntoIterator<Item = Segment<'e>>) -> String {
+    let mut path = String::from("$");
+    for segment in segments {
+        match segment {
+            Segment::Key(key) => {
+                let is_identifier = key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                    && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+                if is_identifier {
+                    path.push('.');
+                    path.push_str(key);
+                } else {
+                    path.push_str("['");
+                    for char in key.chars() {
+                        match char {
+                            '\'' => path.push_str("\\'"),
+                            '\\' => path.push_str("\\\\"),
+                            '\u{0}'..='\u{1f}' => {
+                                path.push_str(&format!("\\u{:04x}", u32::from(char)))
+                            }
+                            _ => path.push(char),
+                        }
+                    }
+                    path.push_str("']");
+                }
+            }
+            Segment::Index(index) => path.push_str(&format!("[{index}]")),
+        }
+    }
+    path
+}
+
+impl ParsingError {
+    /// Steps from the root to the failing location: the value being parsed,
+    /// the member whose key was just read, or else the innermost container.
+    /// A trailing comma belongs to its container, not to an element after it.
+    fn segments(&self) -> Vec<Segment<'_>> {
+        let context = &self.inner.context;
+        let mut segments: Vec<_> = context
+            .stack
+            .iter()
+            .map(|frame| match frame {
+                Frame::Obj { key } => Segment::Key(key),
+                Frame::Arr { len } => Segment::Index(*len),
+            })
+            .collect();
+        if let Place::Colon { key } = &context.expectation {
+            segments.push(Segment::Key(key));
+        }
+        if self.inner.kind == ParsingErrorKind::TrailingComma
+            && matches!(context.stack.last(), Some(Frame::Arr { .. }))
+        {
+            segments.pop();
+        }
+        segments
+    }
+
+    /// Location of the error as a JSONPath, like `$.menu.items[3]`.
+    /// Keys which aren't identifiers are quoted: `$['two words']`.
+    pub fn path(&self) -> String {
+        json_path(self.segments())
+    }
+
+    /// Location of the error as a JSON Pointer (RFC 6901), like `/menu/items/3`.
+    /// The root is the empty string.
+    pub fn pointer(&self) -> String {
+        json_pointer(self.segments())
+    }
+}
+
+/// Writes steps from the root as a JSON Pointer, like `/menu/items/3`.
+fn json_pointer<'e>(segments: impl IntoIterator<Item = Segment<'e>>) -> String {
+    let mut pointer = String::new();
+    for segment in segments {
+        pointer.push('/');
+        match segment {
+            Segment::Key(key) => pointer.push_str(&key.replace('~', "~0").replace('/', "~1")),
+            Segment::Index(index) => pointer.push_str(&index.to_string()),
+        }
+    }
+    pointer
+}
+
+impl ParsingContext<'_> {
+    /// JSON Pointer of the value expected next, or of the member
+    /// whose key was just read.
+    pub(crate) fn pointer(&self) -> String {
+        let mut segments: Vec<_> = self
+            .stack
+            .iter()
+            .map(|frame| match frame {
+                ExpectingValue::Obj { key, .. } => Segment::Key(key),
+                ExpectingValue::Arr { len } => Segment::Index(*len),
+            })
+            .collect();
+        if let Expectation::Obj {
+            kv: KvState::AteKey(key),
+            ..
+        } = &self.expectation
+        {
+            segments.push(Segment::Key(key));
+        }
+        json_pointer(segments)
+    }
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            Expected::Value => "value",
+            Expected::String => "string",
+            Expected::Colon => "colon",
+            Expected::Comma => "comma",
+            Expected::ClosedCurly => "closing curly",
+            Expected::ClosedBracket => "closing bracket",
+            Expected::EndOfFile => "end of file",
+        };
+        f.write_str(description)
+    }
 }
 
 impl fmt::Display for ParsingError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match &self.context.expectation {
-            Expectation::Value => match self.context.stack.last() {
+        self.write_message(f)?;
+
+        match self.inner.token_kind {
+            // no token, but the character which crosses the limit
+            _ if self.inner.kind == ParsingErrorKind::DocumentTooLarge => {
+                let (line, column) = self.inner.position;
+                write!(f, "at line {}, column {}", line, column)?;
+            }
+            Some(_) => {
+                let (line, column) = self.inner.position;
+                write!(f, "at line {}, column {}", line, column)?;
+            }
+            None => write!(f, "at the end")?,
+        }
+        if let Some((line, column)) = self.first_key_position() {
+            write!(f, ", first at line {}, column {}", line, column)?;
+        }
+
+        if !self.segments().is_empty() {
+            write!(f, ", in {}", self.path())?;
+        }
+        Ok(())
+    }
+}
+
+impl ParsingError {
+    /// Writes what went wrong, without the position.
+    /// Each part ends with a space.
+    pub(crate) fn write_message(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = &self.inner;
+
+        // Limits aren't about what the parser expected.
+        match (&inner.kind, &inner.token_kind) {
+            (ParsingErrorKind::DepthLimitExceeded, _) => write!(f, "Nesting is too deep ")?,
+            (ParsingErrorKind::StringTooLong, _) => write!(f, "String is too long ")?,
+            (ParsingErrorKind::DocumentTooLarge, _) => write!(f, "Document is too large ")?,
+            (ParsingErrorKind::DuplicateKey, Some(TokenKind::String(key))) => {
+                write!(f, "Duplicate key \"{key}\" ")?;
+            }
+            _ => return self.write_expectation(f),
+        }
+        if f.alternate() {
+            write!(f, "({:?}) ", inner.kind)?;
+        }
+        Ok(())
+    }
+
+    /// Writes what the parser expected and what it found instead.
+    fn write_expectation(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = &self.inner;
+        match &inner.context.expectation {
+            Place::Value => match inner.context.stack.last() {
                 Some(obj_or_arr) => match obj_or_arr {
-                    ExpectingValue::Obj { acc: _, key } => {
+                    Frame::Obj { key } => {
                         write!(f, "Expected value after key \"{key}\" ")?;
                     }
-                    ExpectingValue::Arr { acc } => {
+                    Frame::Arr { len } => {
                         write!(f, "Expected array value ")?;
-                        if acc.is_empty() {
+                        if *len == 0 {
                             write!(f, "or closing bracket, ")?;
                         }
                     }
@@ -47,50 +418,67 @@ impl fmt::Display for ParsingError {
                 }
             },
             // Expected string after "Fluttershy" but found comma unexpectedly | ExpectedKey
-            Expectation::Obj { acc, kv } => match kv {
-                KvState::Start => {
-                    write!(f, "Expected string ")?;
-                    if acc.is_empty() {
-                        write!(f, "or closing curly, ")?;
-                    }
-                }
-                KvState::AteKey(key) => {
-                    write!(f, "Expected colon after key \"{key}\", ")?;
-                }
-                KvState::AteValue => {
-                    write!(f, "Expected comma or closing curly, ")?;
+            Place::Key { first } => {
+                write!(f, "Expected string ")?;
+                if *first {
+                    write!(f, "or closing curly, ")?;
                 }
-            },
-            Expectation::CommaOrClosedBracket { acc: _ } => {
+            }
+            Place::Colon { key } => {
+                write!(f, "Expected colon after key \"{key}\", ")?;
+            }
+            Place::CommaOrClosedCurly => {
+                write!(f, "Expected comma or closing curly, ")?;
+            }
+            Place::CommaOrClosedBracket => {
                 write!(f, "Expected comma or closed bracket, ")?;
             }
-            Expectation::EndOfTokens(_) => {
+            Place::EndOfTokens => {
                 write!(f, "Expected end of tokens, ")?;
             }
         }
 
-        match &self.error {
+        match &inner.kind {
             ParsingErrorKind::Syntax => {
-                match &self.token_kind {
+                match &inner.token_kind {
                     Some(TokenKind::Invalid(tokenize_error)) => match tokenize_error {
                         TokenizeError::NoSuchToken(char) => {
-                            write!(f, "found '{char}' ")?;
+                            write!(f, "found {char:?} ")?;
                         }
                         TokenizeError::NoSuchEscapeSymbol(char) => {
                             write!(f, "'{char}' - invalid escape symbol ")?;
                         }
+                        TokenizeError::UnescapedControlChar { char, .. } => {
+                            let code = u32::from(*char);
+                            write!(f, "unescaped control character U+{code:04X} ")?;
+                        }
                         TokenizeError::MissingDoubleQuote(string) => {
                             write!(f, "missing double quote in: \"{string}\" ")?;
                         }
+                        TokenizeError::NumberOutOfRange(number) => {
+                            write!(f, "number {number} is out of range ")?;
+                        }
                         TokenizeError::ExpectedDigit(char) => {
                             write!(f, "expected digit, found '{char}' ")?;
                         }
                         TokenizeError::ExpectedDot(char) => {
                             write!(f, "expected dot, found '{char}'")?;
                         }
+                        TokenizeError::ExpectedExponentSignOrDigit(char) => {
+                            write!(f, "expected exponent sign or digit, found '{char}' ")?;
+                        }
+                        TokenizeError::ExpectedExponentDigit(char) => {
+                            write!(f, "expected exponent digit, found '{char}' ")?;
+                        }
                         TokenizeError::MetEndOfFile => {
                             write!(f, "met end of file ")?;
                         }
+                        TokenizeError::UnterminatedComment => {
+                            write!(f, "unterminated comment ")?;
+                        }
+                        TokenizeError::StringTooLong => {
+                            write!(f, "string is too long ")?;
+                        }
                         TokenizeError::ExpectedTrue(char) => {
                             write!(f, "expected 'true' literal, found \"{char}\" ")?;
                         }
@@ -100,14 +488,23 @@ impl fmt::Display for ParsingError {
                         TokenizeError::ExpectedNull(char) => {
                             write!(f, "expected 'null' literal, found \"{char}\" ")?;
                         }
-                        TokenizeError::InvalidUnicode(num) => {
-                            write!(f, "there is no symbol with code {num} ")?;
+                        TokenizeError::UnpairedHighSurrogate(unit) => {
+                            write!(
+                                f,
+                                "high surrogate \\u{unit:04X} is not followed by a low one "
+                            )?;
+                        }
+                        TokenizeError::UnpairedLowSurrogate(unit) => {
+                            write!(
+                                f,
+                                "low surrogate \\u{unit:04X} is not preceded by a high one "
+                            )?;
                         }
                         TokenizeError::InvalidUnicodeChar(char) => {
                             write!(f, "invalid unicode symbol: '{char}' ")?;
                         }
                     },
-                    Some(_) => write!(f, "BUG(Some({:?})) ", &self.token_kind)?,
+                    Some(_) => write!(f, "BUG(Some({:?})) ", &inner.token_kind)?,
                     None => write!(f, "BUG(None) ")?,
                 };
                 if f.alternate() {
@@ -116,7 +513,7 @@ impl fmt::Display for ParsingError {
             }
 
             ParsingErrorKind::ExpectedValue => {
-                match &self.token_kind {
+                match &inner.token_kind {
                     Some(token_kind) => {
                         write!(f, "but found ")?;
                         match token_kind {
@@ -138,7 +535,7 @@ impl fmt::Display for ParsingError {
             }
 
             ParsingErrorKind::ExpectedKey => {
-                match &self.token_kind {
+                match &inner.token_kind {
                     Some(token_kind) => {
                         write!(f, "but found ")?;
                         match token_kind {
@@ -163,12 +560,12 @@ impl fmt::Display for ParsingError {
                 }
             }
             ParsingErrorKind::ExpectedEndOfFile => {
-                match &self.token_kind {
+                match &inner.token_kind {
                     Some(token_kind) => {
                         write!(f, "but found ")?;
                         match token_kind {
-                            TokenKind::String(string) => write!(f, "string \"{string}\"")?,
-                            TokenKind::Number(number) => write!(f, "number {number}")?,
+                            TokenKind::String(string) => write!(f, "string \"{string}\" ")?,
+                            TokenKind::Number(number) => write!(f, "number {number} ")?,
                             TokenKind::True => write!(f, "'true' ")?,
                             TokenKind::False => write!(f, "'false' ")?,
                             TokenKind::Colon => write!(f, "colon ")?,
@@ -181,6 +578,7 @@ impl fmt::Display for ParsingError {
                             TokenKind::Invalid(_) => write!(f, "extra characters ")?,
 
                             TokenKind::Whitespace => write!(f, "BUG(TokenKind::Whitespace) ")?,
+                            TokenKind::Comment => write!(f, "BUG(TokenKind::Comment) ")?,
                         }
                     }
                     None => write!(f, "BUG(None) ")?,
@@ -191,12 +589,12 @@ impl fmt::Display for ParsingError {
                 }
             }
             ParsingErrorKind::ExpectedColon => {
-                match &self.token_kind {
+                match &inner.token_kind {
                     Some(token_kind) => {
                         write!(f, "but found ")?;
                         match token_kind {
-                            TokenKind::String(string) => write!(f, "string \"{string}\"")?,
-                            TokenKind::Number(num) => write!(f, "number {num}")?,
+                            TokenKind::String(string) => write!(f, "string \"{string}\" ")?,
+                            TokenKind::Number(num) => write!(f, "number {num} ")?,
                             TokenKind::True => write!(f, "'true' ")?,
                             TokenKind::False => write!(f, "'false' ")?,
                             TokenKind::Comma => write!(f, "comma ")?,
@@ -218,9 +616,11 @@ impl fmt::Display for ParsingError {
                 }
             }
             ParsingErrorKind::TrailingComma => {
-                match &self.token_kind {
-                    Some(TokenKind::ClosedCurly) => write!(f, "but found trailing comma ")?,
-                    Some(_) => write!(f, "but found BUG({:?})", &self.token_kind)?,
+                match &inner.token_kind {
+                    Some(TokenKind::ClosedCurly | TokenKind::ClosedBracket) => {
+                        write!(f, "but found trailing comma ")?
+                    }
+                    Some(_) => write!(f, "but found BUG({:?})", &inner.token_kind)?,
                     None => write!(f, "BUG(None) ")?,
                 };
                 write!(f, "unexpectedly ")?;
@@ -229,12 +629,12 @@ impl fmt::Display for ParsingError {
                 }
             }
             ParsingErrorKind::ExpectedCommaOrClosedCurly => {
-                match &self.token_kind {
+                match &inner.token_kind {
                     Some(token_kind) => {
                         write!(f, "but found ")?;
                         match token_kind {
-                            TokenKind::String(string) => write!(f, "string \"{string}\"")?,
-                            TokenKind::Number(number) => write!(f, "number {number}")?,
+                            TokenKind::String(string) => write!(f, "string \"{string}\" ")?,
+                            TokenKind::Number(number) => write!(f, "number {number} ")?,
                             TokenKind::True => write!(f, "'true' ")?,
                             TokenKind::False => write!(f, "'false' ")?,
                             TokenKind::Colon => write!(f, "colon ")?,
@@ -255,12 +655,12 @@ impl fmt::Display for ParsingError {
                 }
             }
             ParsingErrorKind::ExpectedCommaOrClosedBracket => {
-                match &self.token_kind {
+                match &inner.token_kind {
                     Some(token_kind) => {
                         write!(f, "but found ")?;
                         match token_kind {
-                            TokenKind::String(string) => write!(f, "string \"{string}\"")?,
-                            TokenKind::Number(number) => write!(f, "number {number}")?,
+                            TokenKind::String(string) => write!(f, "string \"{string}\" ")?,
+                            TokenKind::Number(number) => write!(f, "number {number} ")?,
                             TokenKind::True => write!(f, "'true' ")?,
                             TokenKind::False => write!(f, "'false' ")?,
                             TokenKind::Colon => write!(f, "colon ")?,
@@ -280,16 +680,12 @@ impl fmt::Display for ParsingError {
                     write!(f, "(ExpectedCommaOrClosedBracket) ")?;
                 }
             }
+            ParsingErrorKind::DepthLimitExceeded
+            | ParsingErrorKind::StringTooLong
+            | ParsingErrorKind::DocumentTooLarge
+            | ParsingErrorKind::DuplicateKey => write!(f, "BUG({:?}) ", inner.kind)?,
         }
 
-        match self.position {
-            Some((line, column)) => {
-                write!(f, "at line {}, column {}", line, column)?;
-            }
-            None => {
-                write!(f, "at the end")?;
-            }
-        }
         Ok(())
     }
 }
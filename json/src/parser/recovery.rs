@@ -0,0 +1,331 @@
+use std::collections::BTreeMap;
+
+use crate::lexer::{self, RawToken, TokenKind, TokenizeError};
+use crate::parser::{
+    DuplicateKeys, Event, Expectation, ExpectingValue, KvState, ParsingContext, ParsingError,
+    ParsingErrorKind, Text, TreeBuilder, Value,
+};
+
+/// What [`parse_recovering`](crate::parse_recovering) makes of a document.
+#[derive(Debug)]
+pub struct Recovered<'a> {
+    pub value: Value<'a>,
+    /// In the order of the text.
+    pub errors: Vec<ParsingError>,
+    /// JSON Pointers of the `Null`s put in place of broken or missing
+    /// values, in the order of the text. A `null` written in the
+    /// document is never listed.
+    pub placeholders: Vec<String>,
+}
+
+/// Parser which doesn't stop at the first error. After reporting it,
+/// the document is patched up, or tokens are skipped up to the next comma
+/// or closing delimiter, and parsing goes on.
+struct Recovery<'a> {
+    context: ParsingContext<'a>,
+    builder: TreeBuilder<'a>,
+    input: &'a str,
+    errors: Vec<ParsingError>,
+    placeholders: Vec<String>,
+    /// A byte offset with its line and column. Errors come in the order
+    /// of the text, so each one is counted from the one before.
+    counted: (usize, (usize, usize)),
+    /// Positions of the ends of keys, by byte offset,
+    /// for [`DuplicateKeys::Error`], which points at the first key.
+    keys: BTreeMap<usize, (usize, usize)>,
+    /// Set while skipping to the next comma or closing delimiter,
+    /// counts the containers opened since.
+    skipping: Option<usize>,
+    /// The top-level value is complete, the rest is ignored.
+    trailing: bool,
+}
+
+impl<'a> ParsingContext<'a> {
+    pub(crate) fn parse_recovering(self, string: &'a str) -> Recovered<'a> {
+        if string.len() > self.options.max_document_len {
+            let error = self.too_large_error(string);
+            return Recovered {
+                value: Value::Null,
+                errors: vec![error],
+                placeholders: vec![String::new()],
+            };
+        }
+
+        let options = self.options;
+        let mut recovery = Recovery {
+            builder: TreeBuilder::new(&options),
+            context: self,
+            input: string,
+            errors: Vec::new(),
+            placeholders: Vec::new(),
+            counted: (0, (1, 0)),
+            keys: BTreeMap::new(),
+            skipping: None,
+            trailing: false,
+        };
+        let mut tokens = lexer::tokenize(string, &options);
+        while let Some(token) = tokens.next() {
+            let cut = matches!(token.kind, TokenKind::Invalid(TokenizeError::StringTooLong));
+            recovery.eat_token(token);
+            // the rest of the string would make tokens of its own
+            if cut {
+                tokens.skip_string_rest();
+            }
+        }
+        recovery.finish()
+    }
+}
+
+impl<'a> Recovery<'a> {
+    fn eat_token(&mut self, RawToken { kind, span }: RawToken<'a>) {
+        if self.trailing || self.skip(&kind) {
+            return;
+        }
+
+        // Patching the document up may leave the token wrong in another way,
+        // but it's reported only once.
+        let mut token = kind;
+        let mut reported = false;
+        loop {
+            let (error, rejected) = match self.context.eat_token(token, &span) {
+                Ok(event) => {
+                    if let Some(Event::Key(_)) = &event {
+                        if self.context.options.duplicate_keys == DuplicateKeys::Error {
+                            let position = self.count_to(span.end);
+                            self.keys.insert(span.end, position);
+                        }
+                    }
+                    self.builder.push_some(event);
+                    return;
+                }
+                Err(rejected) => rejected,
+            };
+            if !reported {
+                let found = RawToken {
+                    kind: rejected.clone(),
+                    span: span.clone(),
+                };
+                self.report(error, Some(found));
+                reported = true;
+            }
+            match self.recover(rejected) {
+                Some(retry) => token = retry,
+                None => return,
+            }
+        }
+    }
+
+    /// Fixes the document after `token` was rejected.
+    /// Returns the token back if it should be fed again.
+    fn recover(&mut self, token: TokenKind<'a>) -> Option<TokenKind<'a>> {
+        match &self.context.expectation {
+            Expectation::Value => match token {
+                // [1, 2,]
+                TokenKind::ClosedBracket
+                    if matches!(self.context.stack.last(), Some(ExpectingValue::Arr { .. })) =>
+                {
+                    self.close_container();
+                    None
+                }
+                // [1, , 3] or {"a": }
+                TokenKind::Comma | TokenKind::ClosedCurly | TokenKind::ClosedBracket => {
+                    if self.context.stack.is_empty() {
+                        return None;
+                    }
+                    self.make_null();
+                    Some(token)
+                }
+                TokenKind::Colon => None,
+                // too deep
+                TokenKind::OpenCurly | TokenKind::OpenBracket => {
+                    self.make_null();
+                    self.skip_from(token)
+                }
+                // [1, tru, 3]
+                _ => {
+                    self.make_null();
+                    None
+                }
+            },
+
+            Expectation::Obj {
+                kv: KvState::Start, ..
+            } => match token {
+                // {"a": 1,}
+                TokenKind::ClosedCurly => {
+                    self.close_container();
+                    None
+                }
+                TokenKind::Comma => None,
+                TokenKind::ClosedBracket => self.close_mismatched(token),
+                // {1: 2}
+                _ => self.skip_from(token),
+            },
+            // {"a" 1}, a missing colon
+            Expectation::Obj {
+                kv: KvState::AteKey(_),
+                ..
+            } => {
+                self.feed(TokenKind::Colon);
+                Some(token)
+            }
+            Expectation::Obj {
+                kv: KvState::AteValue,
+                ..
+            } => match token {
+                // {"a": 1 "b": 2}, a missing comma
+                TokenKind::String(_) => {
+                    self.feed(TokenKind::Comma);
+                    Some(token)
+                }
+                TokenKind::ClosedBracket => self.close_mismatched(token),
+                _ => self.skip_from(token),
+            },
+
+            Expectation::CommaOrClosedBracket { len: _ } => match token {
+                TokenKind::ClosedCurly => self.close_mismatched(token),
+                TokenKind::Colon => self.skip_from(token),
+                // [1 2], a missing comma
+                _ => {
+                    self.feed(TokenKind::Comma);
+                    Some(token)
+                }
+            },
+
+            Expectation::EndOfTokens => {
+                self.trailing = true;
+                None
+            }
+        }
+    }
+
+    /// Feeds a token which the state machine is known to accept.
+    /// Only strings use the span, and those are never made up.
+    fn feed(&mut self, token: TokenKind<'a>) {
+        let event = self.context.eat_token(token, &(0..0));
+        debug_assert!(event.is_ok());
+        if let Ok(event) = event {
+            self.builder.push_some(event);
+        }
+    }
+
+    /// Creates an error with positions counted from the last one.
+    fn report(&mut self, error: ParsingErrorKind, token: Option<RawToken<'_>>) {
+        let (offset, start) = self.counted;
+        let text = Text {
+            text: &self.input[offset..],
+            offset,
+            start,
+            keys: Some(&self.keys),
+        };
+        let error = self.context.create_error_in(error, token, &text);
+        self.count_to(error.offset());
+        self.errors.push(error);
+    }
+
+    /// Moves the counted position forward to `offset` and returns it.
+    fn count_to(&mut self, offset: usize) -> (usize, usize) {
+        let (from, position) = self.counted;
+        if offset > from {
+            let bytes = &self.input.as_bytes()[from..offset];
+            self.counted = (offset, lexer::advance(position, bytes));
+        }
+        self.counted.1
+    }
+
+    /// Puts `Null` in place of a broken value.
+    fn make_null(&mut self) {
+        self.placeholders.push(self.context.pointer());
+        self.context.make_value();
+        self.builder.make_value(Value::Null);
+    }
+
+    /// Starts skipping tokens, beginning with `token`.
+    fn skip_from(&mut self, token: TokenKind<'a>) -> Option<TokenKind<'a>> {
+        self.skipping = Some(0);
+        self.skip(&token);
+        None
+    }
+
+    /// Returns whether the token is skipped.
+    fn skip(&mut self, token: &TokenKind<'a>) -> bool {
+        let Some(depth) = &mut self.skipping else {
+            return false;
+        };
+        match token {
+            TokenKind::OpenCurly | TokenKind::OpenBracket => *depth += 1,
+            TokenKind::ClosedCurly | TokenKind::ClosedBracket if *depth > 0 => *depth -= 1,
+            TokenKind::Comma | TokenKind::ClosedCurly | TokenKind::ClosedBracket if *depth == 0 => {
+                // Back in sync: the broken member is dropped,
+                // and the container goes on after it.
+                self.skipping = None;
+                if let Expectation::Obj { kv, .. } = &mut self.context.expectation {
+                    *kv = KvState::AteValue;
+                }
+                return false;
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// [1} or {"a": 1]: the closing delimiter may belong to an outer container,
+    /// then the current one just misses its own.
+    fn close_mismatched(&mut self, token: TokenKind<'a>) -> Option<TokenKind<'a>> {
+        let closes_outer = self.context.stack.iter().any(|frame| {
+            matches!(
+                (frame, &token),
+                (ExpectingValue::Obj { .. }, TokenKind::ClosedCurly)
+                    | (ExpectingValue::Arr { .. }, TokenKind::ClosedBracket)
+            )
+        });
+        if !closes_outer {
+            return None;
+        }
+        self.close_container();
+        Some(token)
+    }
+
+    /// Closes the innermost container with whatever it has by now.
+    fn close_container(&mut self) {
+        match &self.context.expectation {
+            Expectation::Value => {
+                // the container is on the stack, or it's the top-level value
+                match self.context.stack.last() {
+                    // the builder puts `Null` after a key without a value
+                    Some(ExpectingValue::Obj { .. }) => {
+                        self.placeholders.push(self.context.pointer());
+                    }
+                    Some(ExpectingValue::Arr { .. }) => {}
+                    None => {
+                        self.make_null();
+                        return;
+                    }
+                }
+                self.context.stack.pop();
+            }
+            Expectation::Obj {
+                kv: KvState::AteKey(_),
+                ..
+            } => self.placeholders.push(self.context.pointer()),
+            Expectation::Obj { .. } | Expectation::CommaOrClosedBracket { .. } => {}
+            Expectation::EndOfTokens => return,
+        }
+        self.context.make_value();
+        self.builder.close_container();
+    }
+
+    fn finish(mut self) -> Recovered<'a> {
+        if let Some(error) = self.context.end_error() {
+            self.report(error, None);
+            while !matches!(self.context.expectation, Expectation::EndOfTokens) {
+                self.close_container();
+            }
+        }
+        Recovered {
+            value: self.builder.finish(),
+            errors: self.errors,
+            placeholders: self.placeholders,
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use std::fmt;
+
+use nu_ansi_term::{Color, Style};
+
+use crate::lexer::{self, TokenKind, TokenizeError};
+use crate::parser::{Expected, ParsingError, ParsingErrorKind};
+
+/// Renders a [`ParsingError`] together with the text it came from:
+/// the offending line with the token underlined, where in the document
+/// it happened, and a hint when the fix is obvious.
+///
+/// ```text
+/// error: Expected comma or closing curly, but found string "cute" unexpectedly
+///  --> line 2, column 25, in $.pony
+///   |
+/// 2 |   "name": "Rarity" "cute": true
+///   |                    ^^^^^^ expected comma or closing curly
+///   = hint: add a comma before it
+/// ```
+pub struct Report<'a> {
+    error: &'a ParsingError,
+    source: &'a str,
+    colored: bool,
+}
+
+impl ParsingError {
+    /// Prepares a [`Report`] of the error. `source` must be the parsed text.
+    pub fn report<'a>(&'a self, source: &'a str) -> Report<'a> {
+        Report {
+            error: self,
+            source,
+            colored: false,
+        }
+    }
+}
+
+impl Report<'_> {
+    /// Paint the report with ANSI colors, for terminals.
+    pub fn colored(mut self, colored: bool) -> Self {
+        self.colored = colored;
+        self
+    }
+
+    fn style(&self, style: Style) -> Style {
+        if self.colored {
+            style
+        } else {
+            Style::new()
+        }
+    }
+
+    /// What goes next to the carets: the expected tokens, or what's wrong
+    /// with the token when it's the right one but breaks a rule.
+    fn label(&self) -> Option<String> {
+        let label = match self.error.kind() {
+            ParsingErrorKind::DuplicateKey => "duplicate key".to_owned(),
+            ParsingErrorKind::StringTooLong => "string too long".to_owned(),
+            ParsingErrorKind::DepthLimitExceeded => "nested too deep".to_owned(),
+            ParsingErrorKind::DocumentTooLarge => "over the size limit".to_owned(),
+            _ => {
+                let expected: Vec<_> = self
+                    .error
+                    .expected()
+                    .iter()
+                    .map(Expected::to_string)
+                    .collect();
+                format!("expected {}", expected.join(" or "))
+            }
+        };
+        Some(label)
+    }
+
+    fn hint(&self) -> Option<&'static str> {
+        let error = self.error;
+        let hint = match (error.kind(), error.found()?) {
+            (ParsingErrorKind::TrailingComma, _) => "remove the trailing comma before it",
+            (ParsingErrorKind::ExpectedKey, TokenKind::Invalid(_)) => return None,
+            (ParsingErrorKind::ExpectedKey, _) => "object keys must be strings in double quotes",
+            (ParsingErrorKind::ExpectedColon, _) => "put a colon between the key and its value",
+            (
+                ParsingErrorKind::ExpectedCommaOrClosedCurly
+                | ParsingErrorKind::ExpectedCommaOrClosedBracket,
+                TokenKind::String(_)
+                | TokenKind::Number(_)
+                | TokenKind::True
+                | TokenKind::False
+                | TokenKind::Null
+                | TokenKind::OpenCurly
+                | TokenKind::OpenBracket,
+            ) => "add a comma before it",
+            (ParsingErrorKind::ExpectedEndOfFile, _) => {
+                "there can be only one value at the top level"
+            }
+            (ParsingErrorKind::Syntax, TokenKind::Invalid(error)) => match error {
+                TokenizeError::NoSuchToken('\'') => "strings must be in double quotes",
+                TokenizeError::MissingDoubleQuote(_) => "close the string with a double quote",
+                TokenizeError::UnescapedControlChar { .. } => "escape it, like \\n or \\u0001",
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(hint)
+    }
+}
+
+impl fmt::Display for Report<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let error = self.error;
+        let source = self.source;
+
+        // Underline the token, or the place right after the last value
+        // if the text ended too early.
+        let mut span = error.span();
+        if span.is_empty() {
+            let end = source[..span.start].trim_end().len();
+            span = end..end;
+        }
+
+        let first_line = lexer::line_column(source, line_start(source, span.start)).0;
+        let last_line = lexer::line_column(source, span.end).0;
+        let width = last_line.max(first_line).to_string().len();
+
+        let red = self.style(Color::Red.bold());
+        let blue = self.style(Color::Blue.bold());
+        let bold = self.style(Style::new().bold());
+
+        let message = Message(error).to_string();
+        writeln!(
+            f,
+            "{}{}",
+            red.paint("error"),
+            bold.paint(format!(": {}", message.trim_end()))
+        )?;
+        writeln!(
+            f,
+            "{:width$}{} line {}, column {}, in {}",
+            "",
+            blue.paint("-->"),
+            error.line(),
+            error.column(),
+            error.path()
+        )?;
+        writeln!(f, "{:width$} {}", "", blue.paint("|"))?;
+
+        let mut start = line_start(source, span.start);
+        let mut number = first_line;
+        loop {
+            let end = source[start..]
+                .find('\n')
+                .map_or(source.len(), |i| start + i);
+            let line = source[start..end].trim_end_matches('\r');
+            writeln!(f, "{} {line}", blue.paint(format!("{number:>width$} |")))?;
+
+            // Tabs are kept, so the carets line up with the text.
+            let from = span.start.clamp(start, start + line.len());
+            let to = span.end.clamp(from, start + line.len());
+            let indent: String = source[start..from]
+                .chars()
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            let carets = "^".repeat(source[from..to].chars().count().max(1));
+            write!(
+                f,
+                "{:width$} {} {indent}{}",
+                "",
+                blue.paint("|"),
+                red.paint(carets)
+            )?;
+
+            if end >= span.end || end == source.len() {
+                match self.label() {
+                    Some(label) => writeln!(f, " {}", red.paint(label))?,
+                    None => writeln!(f)?,
+                }
+                break;
+            }
+            writeln!(f)?;
+            start = end + 1;
+            number += 1;
+        }
+
+        if let Some(hint) = self.hint() {
+            writeln!(
+                f,
+                "{:width$} {} {}",
+                "",
+                blue.paint("="),
+                self.style(Color::Cyan.normal())
+                    .paint(format!("hint: {hint}"))
+            )?;
+        }
+        if let Some((line, column)) = error.first_key_position() {
+            writeln!(
+                f,
+                "{:width$} {} note: the key first appears at line {line}, column {column}",
+                "",
+                blue.paint("="),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Byte offset of the start of the line that `offset` is on.
+fn line_start(source: &str, offset: usize) -> usize {
+    source[..offset].rfind('\n').map_or(0, |index| index + 1)
+}
+
+struct Message<'a>(&'a ParsingError);
+
+impl fmt::Display for Message<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.write_message(f)
+    }
+}
@@ -1,8 +1,13 @@
-mod texts;
+pub(crate) mod texts;
 
 #[track_caller]
 fn assert_snapshot(string: &str, expected: &str) {
-    let json_value = crate::parse(string);
+    assert_snapshot_with(string, &crate::ParseOptions::new(), expected);
+}
+
+#[track_caller]
+fn assert_snapshot_with(string: &str, options: &crate::ParseOptions, expected: &str) {
+    let json_value = crate::parse_with(string, options);
 
     match json_value {
         Ok(value) => {
@@ -22,7 +27,7 @@ fn assert_snapshot(string: &str, expected: &str) {
 fn smoke_ok() {
     assert_snapshot(
         r#"{"mare": true, "snowpity": "legendary", "cute_level": 999}"#,
-        r#"Object({"cute_level": Number(999.0), "mare": Bool(true), "snowpity": String("legendary")})"#,
+        r#"Object({"mare": Bool(true), "snowpity": String("legendary"), "cute_level": Number(999)})"#,
     );
 }
 
@@ -30,7 +35,7 @@ fn smoke_ok() {
 fn smoke_error() {
     assert_snapshot(
         r#"{"mare": true, "snowpity":"#,
-        r#"Expected value after key "snowpity" but the string ended unexpectedly (ExpectedValue) at the end"#,
+        r#"Expected value after key "snowpity" but the string ended unexpectedly (ExpectedValue) at the end, in $.snowpity"#,
     );
 }
 
@@ -55,15 +60,15 @@ fn empty_complex() {
 
 #[test]
 fn derpibooru() {
-    let response = std::fs::read_to_string(r#".\src\derpibooru_example_response.json"#).unwrap();
-    let actual = texts::derpibooru_deserealized();
-    assert_snapshot(&response, &actual);
+    let response = include_str!("../../derpibooru_example_response.json");
+    // The expected members are sorted by key, so they are compared in any order.
+    assert_eq!(crate::parse(response).unwrap(), texts::derpibooru_value());
 }
 
 #[test]
 fn menu() {
     let response = texts::menu_string();
-    let actual = texts::menu_deserealized();
+    let actual = format!("{:#?}", texts::menu_value());
     // timer start
 
     assert_snapshot(&response, &actual);
@@ -73,13 +78,32 @@ fn menu() {
 
 #[test]
 fn simple_literal() {
-    assert_snapshot("10", "Number(10.0)");
+    assert_snapshot("10", "Number(10)");
     assert_snapshot("\"string\"", "String(\"string\")");
     assert_snapshot("true", "Bool(true)");
     assert_snapshot("false", "Bool(false)");
     assert_snapshot("null", "Null");
 }
 
+#[test]
+fn scientific_notation() {
+    assert_snapshot(r#"{"x": 1e-7}"#, r#"Object({"x": Number(1e-7)})"#);
+    assert_snapshot("[6.02E23]", "Array([Number(6.02e23)])");
+    assert_snapshot(
+        "[1e]",
+        "Expected array value or closing bracket, expected exponent sign or digit, found ']' (Syntax) at line 1, column 3, in $[0]",
+    );
+}
+
+#[test]
+fn exact_numbers() {
+    assert_snapshot(
+        "[18446744073709551615, -9223372036854775808, 0.1, -100.000001]",
+        "Array([Number(18446744073709551615), Number(-9223372036854775808), Number(0.1), Number(-100.000001)])",
+    );
+    assert_snapshot("[1.0, -0]", "Array([Number(1.0), Number(-0.0)])");
+}
+
 #[test]
 fn object_in_object() {
     assert_snapshot(
@@ -100,22 +124,22 @@ fn object_in_array() {
 fn error_object() {
     assert_snapshot(
         r#"{""}"#,
-        r#"Expected colon after key "", but found closed curly unexpectedly (ExpectedColon) at line 1, column 4"#,
+        r#"Expected colon after key "", but found closed curly unexpectedly (ExpectedColon) at line 1, column 4, in $['']"#,
     );
 
     assert_snapshot(
         r#"{"string"}"#,
-        r#"Expected colon after key "string", but found closed curly unexpectedly (ExpectedColon) at line 1, column 10"#,
+        r#"Expected colon after key "string", but found closed curly unexpectedly (ExpectedColon) at line 1, column 10, in $.string"#,
     );
 
     assert_snapshot(
         r#"{"string":}"#,
-        r#"Expected value after key "string" but found closed curly unexpectedly (ExpectedValue) at line 1, column 11"#,
+        r#"Expected value after key "string" but found closed curly unexpectedly (ExpectedValue) at line 1, column 11, in $.string"#,
     );
 
     assert_snapshot(
         r#"{"string-invalid": bbb}"#,
-        r#"Expected value after key "string-invalid" found 'b' (Syntax) at line 1, column 20"#,
+        r#"Expected value after key "string-invalid" found 'b' (Syntax) at line 1, column 20, in $['string-invalid']"#,
     );
 
     assert_snapshot(
@@ -133,17 +157,17 @@ fn error_object() {
 fn error_arr() {
     assert_snapshot(
         r#"[,]"#,
-        r#"Expected array value or closing bracket, but found comma unexpectedly (ExpectedValue) at line 1, column 2"#,
+        r#"Expected array value or closing bracket, but found comma unexpectedly (ExpectedValue) at line 1, column 2, in $[0]"#,
     );
 
     assert_snapshot(
         r#"[10,]]"#,
-        r#"Expected array value but found closed bracket unexpectedly (ExpectedValue) at line 1, column 5"#,
+        r#"Expected array value but found trailing comma unexpectedly (TrailingComma) at line 1, column 5"#,
     );
 
     assert_snapshot(
         r#"[10,{]}]"#,
-        r#"Expected string or closing curly, but found closed bracket unexpectedly (ExpectedKey) at line 1, column 6"#,
+        r#"Expected string or closing curly, but found closed bracket unexpectedly (ExpectedKey) at line 1, column 6, in $[1]"#,
     );
 
     assert_snapshot(
@@ -173,4 +197,874 @@ fn error_string() {
 #[test]
 fn error_string_unicode() {
     assert_snapshot(r#""mare \u2764""#, r#"String("mare ❤")"#);
+    assert_snapshot(r#""mare \ud83d\udc34""#, r#"String("mare 🐴")"#);
+    assert_snapshot(
+        r#"["\uDC34"]"#,
+        r#"Expected array value or closing bracket, low surrogate \uDC34 is not preceded by a high one (Syntax) at line 1, column 8, in $[0]"#,
+    );
+}
+
+/// String cases from JSONTestSuite (https://github.com/nst/JSONTestSuite),
+/// except the ones with invalid UTF-8, which a `&str` can't hold.
+#[test]
+fn json_test_suite_strings() {
+    let options = crate::ParseOptions::new().strict_strings(true);
+
+    let accepted = [
+        (
+            "y_string_1_2_3_bytes_UTF-8_sequences",
+            r#"["\u0060\u012a\u12AB"]"#,
+        ),
+        ("y_string_accepted_surrogate_pair", r#"["\uD801\udc37"]"#),
+        (
+            "y_string_accepted_surrogate_pairs",
+            r#"["\ud83d\ude39\ud83d\udc8d"]"#,
+        ),
+        ("y_string_allowed_escapes", r#"["\"\\\/\b\f\n\r\t"]"#),
+        ("y_string_backslash_and_u_escaped_zero", r#"["\\u0000"]"#),
+        ("y_string_backslash_doublequotes", r#"["\""]"#),
+        ("y_string_comments", r#"["a/*b*/c/*d//e"]"#),
+        ("y_string_double_escape_a", r#"["\\a"]"#),
+        ("y_string_double_escape_n", r#"["\\n"]"#),
+        ("y_string_escaped_control_character", r#"["\u0012"]"#),
+        ("y_string_escaped_noncharacter", r#"["\uFFFF"]"#),
+        ("y_string_in_array", r#"["asd"]"#),
+        ("y_string_in_array_with_leading_space", r#"[ "asd"]"#),
+        ("y_string_last_surrogates_1_and_2", r#"["\uDBFF\uDFFF"]"#),
+        ("y_string_nbsp_uescaped", r#"["new\u00A0line"]"#),
+        ("y_string_nonCharacterInUTF-8_U+10FFFF", "[\"\u{10FFFF}\"]"),
+        ("y_string_nonCharacterInUTF-8_U+FFFF", "[\"\u{FFFF}\"]"),
+        ("y_string_null_escape", r#"["\u0000"]"#),
+        ("y_string_one-byte-utf-8", r#"["\u002c"]"#),
+        ("y_string_pi", r#"["π"]"#),
+        (
+            "y_string_reservedCharacterInUTF-8_U+1BFFF",
+            "[\"\u{1BFFF}\"]",
+        ),
+        ("y_string_simple_ascii", r#"["asd "]"#),
+        ("y_string_space", r#"" ""#),
+        (
+            "y_string_surrogates_U+1D11E_MUSICAL_SYMBOL_G_CLEF",
+            r#"["\uD834\uDd1e"]"#,
+        ),
+        ("y_string_three-byte-utf-8", r#"["\u0821"]"#),
+        ("y_string_two-byte-utf-8", r#"["\u0123"]"#),
+        ("y_string_u+2028_line_sep", "[\"\u{2028}\"]"),
+        ("y_string_u+2029_par_sep", "[\"\u{2029}\"]"),
+        ("y_string_uEscape", r#"["\u0061\u30af\u30EA\u30b9"]"#),
+        ("y_string_uescaped_newline", r#"["new\u000Aline"]"#),
+        ("y_string_unescaped_char_delete", "[\"\u{7f}\"]"),
+        ("y_string_unicode", r#"["\uA66D"]"#),
+        ("y_string_unicodeEscapedBackslash", r#"["\u005C"]"#),
+        ("y_string_unicode_2", r#"["⍂㈴⍂"]"#),
+        ("y_string_unicode_escaped_double_quote", r#"["\u0022"]"#),
+        ("y_string_unicode_U+10FFFE_nonchar", r#"["\uDBFF\uDFFE"]"#),
+        ("y_string_unicode_U+FDD0_nonchar", r#"["\uFDD0"]"#),
+        ("y_string_utf8", r#"["€𝄞"]"#),
+        ("y_string_with_del_character", "[\"a\u{7f}a\"]"),
+    ];
+
+    let rejected = [
+        ("n_string_1_surrogate_then_escape", r#"["\uD800\"]"#),
+        ("n_string_1_surrogate_then_escape_u", r#"["\uD800\u"]"#),
+        ("n_string_1_surrogate_then_escape_u1", r#"["\uD800\u1"]"#),
+        ("n_string_1_surrogate_then_escape_u1x", r#"["\uD800\u1x"]"#),
+        ("n_string_accentuated_char_no_quotes", "[é]"),
+        ("n_string_backslash_00", "[\"\\\u{0}\"]"),
+        ("n_string_escape_x", r#"["\x00"]"#),
+        ("n_string_escaped_backslash_bad", r#"["\\\"]"#),
+        ("n_string_escaped_ctrl_char_tab", "[\"\\\t\"]"),
+        ("n_string_escaped_emoji", r#"["\🌀"]"#),
+        ("n_string_incomplete_escape", r#"["\"]"#),
+        ("n_string_incomplete_escaped_character", r#"["\u00A"]"#),
+        ("n_string_incomplete_surrogate", r#"["\uD834\uDd"]"#),
+        (
+            "n_string_incomplete_surrogate_escape_invalid",
+            r#"["\uD800\uD800\x"]"#,
+        ),
+        ("n_string_invalid_backslash_esc", r#"["\a"]"#),
+        ("n_string_invalid_unicode_escape", r#"["\uqqqq"]"#),
+        ("n_string_leading_uescaped_thinspace", r#"[\u0020"asd"]"#),
+        ("n_string_no_quotes_with_bad_escape", r#"[\n]"#),
+        ("n_string_single_doublequote", r#"""#),
+        ("n_string_single_quote", "['single quote']"),
+        ("n_string_single_string_no_double_quotes", "abc"),
+        ("n_string_start_escape_unclosed", r#"["\"#),
+        ("n_string_unescaped_ctrl_char", "[\"a\u{0}a\"]"),
+        ("n_string_unescaped_newline", "[\"new\nline\"]"),
+        ("n_string_unescaped_tab", "[\"\t\"]"),
+        ("n_string_unicode_CapitalU", r#""\UA66D""#),
+        ("n_string_with_trailing_garbage", r#"""x"#),
+    ];
+
+    for (name, text) in accepted {
+        if let Err(error) = crate::parse_with(text, &options) {
+            panic!("{name} must be accepted, but got: {error}");
+        }
+    }
+
+    for (name, text) in rejected {
+        if let Ok(value) = crate::parse_with(text, &options) {
+            panic!("{name} must be rejected, but got: {value:?}");
+        }
+    }
+}
+
+#[test]
+fn error_control_char() {
+    let options = crate::ParseOptions::new().strict_strings(true);
+
+    let error = crate::parse_with("{\"key\": \"a\u{1}b\"}", &options).unwrap_err();
+    assert_eq!(
+        format!("{error:#}"),
+        "Expected value after key \"key\" unescaped control character U+0001 (Syntax) at line 1, column 11, in $.key",
+    );
+}
+
+#[test]
+fn borrowed_strings() {
+    use crate::Value;
+    use std::borrow::Cow;
+
+    let text = r#"{"plain": "value", "escaped\n": ["tab\t", "free"]}"#.to_owned();
+    let Ok(Value::Object(object)) = crate::parse(&text) else {
+        panic!("expected an object");
+    };
+
+    let keys: Vec<_> = object.keys().collect();
+    assert!(matches!(keys[0], Cow::Borrowed("plain")));
+    assert!(matches!(keys[1], Cow::Owned(key) if key == "escaped\n"));
+    assert!(matches!(
+        object["plain"],
+        Value::String(Cow::Borrowed("value"))
+    ));
+
+    let Value::Array(array) = &object["escaped\n"] else {
+        panic!("expected an array");
+    };
+    assert!(matches!(&array[0], Value::String(Cow::Owned(_))));
+    assert!(matches!(&array[1], Value::String(Cow::Borrowed("free"))));
+
+    // the owned copy outlives the text
+    let owned = Value::Object(object).into_owned();
+    drop(text);
+    assert_eq!(
+        format!("{owned:?}"),
+        r#"Object({"plain": String("value"), "escaped\n": Array([String("tab\t"), String("free")])})"#,
+    );
+}
+
+#[test]
+fn whitespace() {
+    assert_snapshot(" \t\r\n[ 1 ,\n\t2 ] \n", r#"Array([Number(1), Number(2)])"#);
+    assert_snapshot(
+        "[1,\u{a0}2]",
+        r#"Expected array value found '\u{a0}' (Syntax) at line 1, column 4, in $[1]"#,
+    );
+    assert_snapshot(
+        "\u{feff}null",
+        r#"Expected JSON object, array or literal - found '\u{feff}' (Syntax) at line 1, column 1"#,
+    );
+}
+
+#[test]
+fn error_api() {
+    use crate::{Expected, ParsingErrorKind, TokenKind, TokenizeError};
+
+    let error = crate::parse("{\"pony\": [1, 2,\n  3 \"four\"]}").unwrap_err();
+    assert_eq!(error.kind(), ParsingErrorKind::ExpectedCommaOrClosedBracket);
+    assert_eq!((error.line(), error.column()), (2, 10));
+    assert_eq!((error.offset(), error.span()), (20, 20..26));
+    assert_eq!(error.found(), Some(&TokenKind::String("four".into())));
+    assert_eq!(error.found().unwrap().to_string(), r#"string "four""#);
+    assert_eq!(error.expected(), [Expected::Comma, Expected::ClosedBracket]);
+
+    let error = crate::parse("[").unwrap_err();
+    assert_eq!(error.kind(), ParsingErrorKind::ExpectedValue);
+    assert_eq!((error.line(), error.column()), (1, 1));
+    assert_eq!((error.offset(), error.span()), (1, 1..1));
+    assert_eq!(error.found(), None);
+    assert_eq!(error.expected(), [Expected::Value, Expected::ClosedBracket]);
+
+    let error = crate::parse("{\"a\": 1,}").unwrap_err();
+    assert_eq!(error.kind(), ParsingErrorKind::TrailingComma);
+    assert_eq!(error.expected(), [Expected::String]);
+
+    let error = crate::parse("[1,]").unwrap_err();
+    assert_eq!(error.kind(), ParsingErrorKind::TrailingComma);
+    assert_eq!(error.expected(), [Expected::Value]);
+    assert_eq!(error.pointer(), "");
+
+    let error = crate::parse("{\"a\"").unwrap_err();
+    assert_eq!(error.kind(), ParsingErrorKind::ExpectedColon);
+    assert_eq!(error.expected(), [Expected::Colon]);
+
+    let error = crate::parse("[1] x").unwrap_err();
+    assert_eq!(error.kind(), ParsingErrorKind::ExpectedEndOfFile);
+    assert_eq!(
+        error.found(),
+        Some(&TokenKind::Invalid(TokenizeError::NoSuchToken('x')))
+    );
+    assert_eq!(error.expected(), [Expected::EndOfFile]);
+}
+
+fn assert_report(string: &str, expected: &str) {
+    let error = crate::parse(string).unwrap_err();
+    assert_eq!(error.report(string).to_string(), expected);
+}
+
+#[test]
+fn report() {
+    assert_report(
+        "{\"pony\": {\n  \"name\": \"Rarity\" \"cute\": true\n}}",
+        r#"error: Expected comma or closing curly, but found string "cute" unexpectedly
+ --> line 2, column 25, in $.pony
+  |
+2 |   "name": "Rarity" "cute": true
+  |                    ^^^^^^ expected comma or closing curly
+  = hint: add a comma before it
+"#,
+    );
+
+    assert_report(
+        "[1, 2,]",
+        r#"error: Expected array value but found trailing comma unexpectedly
+ --> line 1, column 7, in $
+  |
+1 | [1, 2,]
+  |       ^ expected value
+  = hint: remove the trailing comma before it
+"#,
+    );
+
+    assert_report(
+        "{\"a\": 1,\n\t\"b\": 2,\n}",
+        r#"error: Expected string but found trailing comma unexpectedly
+ --> line 3, column 1, in $
+  |
+3 | }
+  | ^ expected string
+  = hint: remove the trailing comma before it
+"#,
+    );
+
+    assert_report(
+        "{\"menu\": [1, {\"id\": \"unclosed}]",
+        r#"error: Expected value after key "id" missing double quote in: "unclosed}]"
+ --> line 1, column 31, in $.menu[1].id
+  |
+1 | {"menu": [1, {"id": "unclosed}]
+  |                     ^^^^^^^^^^^ expected value
+  = hint: close the string with a double quote
+"#,
+    );
+
+    assert_report(
+        "[\n  {\"a\": 1}\n\n",
+        r#"error: Expected comma or closed bracket, but the string ended unexpectedly
+ --> line 2, column 10, in $
+  |
+2 |   {"a": 1}
+  |           ^ expected comma or closing bracket
+"#,
+    );
+
+    assert_report(
+        "{'a': 1}",
+        r#"error: Expected string or closing curly, found '\''
+ --> line 1, column 2, in $
+  |
+1 | {'a': 1}
+  |  ^ expected string or closing curly
+  = hint: strings must be in double quotes
+"#,
+    );
+
+    // Tabs stay tabs, so the carets line up in a terminal.
+    assert_report(
+        "[\n\t\t1 2]",
+        "error: Expected comma or closed bracket, but found number 2 unexpectedly
+ --> line 2, column 5, in $
+  |
+2 | \t\t1 2]
+  | \t\t  ^ expected comma or closing bracket
+  = hint: add a comma before it
+",
+    );
+
+    // A closing bracket is a trailing comma only after array elements.
+    assert_report(
+        "{\"a\": ]",
+        r#"error: Expected value after key "a" but found closed bracket unexpectedly
+ --> line 1, column 7, in $.a
+  |
+1 | {"a": ]
+  |       ^ expected value
+"#,
+    );
+    assert_report(
+        "]",
+        r#"error: Expected JSON object, array or literal - but found closed bracket unexpectedly
+ --> line 1, column 1, in $
+  |
+1 | ]
+  | ^ expected value
+"#,
+    );
+}
+
+#[test]
+fn colored_report() {
+    let error = crate::parse("[1 2]").unwrap_err();
+    let report = error.report("[1 2]").colored(true).to_string();
+    assert!(report.starts_with("\u{1b}[1;31merror\u{1b}[0m"));
+    assert!(report
+        .contains("\u{1b}[1;31m^\u{1b}[0m \u{1b}[1;31mexpected comma or closing bracket\u{1b}[0m"));
+}
+
+#[test]
+fn error_location() {
+    let error = crate::parse(r#"{"menu": {"items": [1, 2, 3, {"id": tru}]}}"#).unwrap_err();
+    assert_eq!(error.path(), "$.menu.items[3].id");
+    assert_eq!(error.pointer(), "/menu/items/3/id");
+
+    let error = crate::parse(r#"{"a/b": {"m~n": [{"it's": }]}}"#).unwrap_err();
+    assert_eq!(error.path(), r#"$['a/b']['m~n'][0]['it\'s']"#);
+    assert_eq!(error.pointer(), "/a~1b/m~0n/0/it's");
+
+    // The member whose key was just read.
+    let error = crate::parse(r#"{"pony": {"name" "Rarity"}}"#).unwrap_err();
+    assert_eq!(error.path(), "$.pony.name");
+    assert_eq!(error.pointer(), "/pony/name");
+
+    // Between members, the container itself.
+    let error = crate::parse(r#"[{"a": 1 "b": 2}]"#).unwrap_err();
+    assert_eq!(error.path(), "$[0]");
+    assert_eq!(error.pointer(), "/0");
+
+    let error = crate::parse("[1] 2").unwrap_err();
+    assert_eq!(error.path(), "$");
+    assert_eq!(error.pointer(), "");
+    assert_eq!(
+        error.to_string(),
+        "Expected end of tokens, but found number 2 unexpectedly at line 1, column 5"
+    );
+}
+
+fn assert_recovered(
+    string: &str,
+    expected_value: &str,
+    expected_errors: &[&str],
+    expected_placeholders: &[&str],
+) {
+    let recovered = crate::parse_recovering(string, &crate::ParseOptions::new());
+    let errors: Vec<_> = recovered.errors.iter().map(ToString::to_string).collect();
+    assert_eq!(format!("{:?}", recovered.value), expected_value);
+    assert_eq!(errors, expected_errors);
+    assert_eq!(recovered.placeholders, expected_placeholders);
+}
+
+#[test]
+fn recovery() {
+    assert_recovered(
+        "[1, tru, 3]",
+        "Array([Number(1), Null, Number(3)])",
+        &[
+            r#"Expected array value expected 'true' literal, found "," at line 1, column 7, in $[1]"#,
+        ],
+        &["/1"],
+    );
+
+    assert_recovered(
+        "[1, , 3,]",
+        "Array([Number(1), Null, Number(3)])",
+        &[
+            "Expected array value but found comma unexpectedly at line 1, column 5, in $[1]",
+            "Expected array value but found trailing comma unexpectedly at line 1, column 9",
+        ],
+        &["/1"],
+    );
+
+    assert_recovered(
+        "[1 2]",
+        "Array([Number(1), Number(2)])",
+        &["Expected comma or closed bracket, but found number 2 unexpectedly at line 1, column 4"],
+        &[],
+    );
+
+    assert_recovered(
+        r#"{"a" 1, "b": , "c": 3 "d": 4,}"#,
+        r#"Object({"a": Number(1), "b": Null, "c": Number(3), "d": Number(4)})"#,
+        &[
+            r#"Expected colon after key "a", but found number 1 unexpectedly at line 1, column 6, in $.a"#,
+            r#"Expected value after key "b" but found comma unexpectedly at line 1, column 14, in $.b"#,
+            r#"Expected comma or closing curly, but found string "d" unexpectedly at line 1, column 25"#,
+            "Expected string but found trailing comma unexpectedly at line 1, column 30",
+        ],
+        &["/b"],
+    );
+
+    // Skipped up to the comma, with everything nested in between.
+    assert_recovered(
+        r#"{1: [2, {3}], "b": true}"#,
+        r#"Object({"b": Bool(true)})"#,
+        &["Expected string or closing curly, but found number 1 unexpectedly at line 1, column 2"],
+        &[],
+    );
+
+    // The bracket closes the array, so the object misses its curly.
+    assert_recovered(
+        r#"[{"a": 1], 2}"#,
+        r#"Array([Object({"a": Number(1)})])"#,
+        &[
+            "Expected comma or closing curly, but found closed bracket unexpectedly at line 1, column 9, in $[0]",
+            "Expected end of tokens, but found comma unexpectedly at line 1, column 10",
+        ],
+        &[],
+    );
+
+    assert_recovered(
+        r#"{"a": [1, {"b": 2"#,
+        r#"Object({"a": Array([Number(1), Object({"b": Number(2)})])})"#,
+        &["Expected comma or closing curly, but the string ended unexpectedly at the end, in $.a[1]"],
+        &[],
+    );
+
+    assert_recovered(
+        "[1] 2 3",
+        "Array([Number(1)])",
+        &["Expected end of tokens, but found number 2 unexpectedly at line 1, column 5"],
+        &[],
+    );
+
+    assert_recovered(
+        "",
+        "Null",
+        &["Expected JSON object, array or literal - but the string ended unexpectedly at the end"],
+        &[""],
+    );
+
+    assert_recovered(
+        r#"{"ok": [1, 2]}"#,
+        r#"Object({"ok": Array([Number(1), Number(2)])})"#,
+        &[],
+        &[],
+    );
+
+    // Only the made up nulls are listed, with keys escaped.
+    assert_recovered(
+        r#"{"a": null, "b/c": }"#,
+        r#"Object({"a": Null, "b/c": Null})"#,
+        &[
+            r#"Expected value after key "b/c" but found closed curly unexpectedly at line 1, column 20, in $['b/c']"#,
+        ],
+        &["/b~1c"],
+    );
+    assert_recovered(
+        r#"[{"a": 1, "b""#,
+        r#"Array([Object({"a": Number(1), "b": Null})])"#,
+        &[
+            r#"Expected colon after key "b", but the string ended unexpectedly at the end, in $[0].b"#,
+        ],
+        &["/0/b"],
+    );
+
+    // Positions are counted on from the previous error.
+    assert_recovered(
+        "[\n  tru,\n  1 2,\n  ]",
+        "Array([Null, Number(1), Number(2)])",
+        &[
+            r#"Expected array value or closing bracket, expected 'true' literal, found "," at line 2, column 5, in $[0]"#,
+            "Expected comma or closed bracket, but found number 2 unexpectedly at line 3, column 5",
+            "Expected array value but found trailing comma unexpectedly at line 4, column 3",
+        ],
+        &["/0"],
+    );
+
+    // The rest of a string over the limit is skipped with it.
+    let options = crate::ParseOptions::new().max_string_len(4);
+    let recovered = crate::parse_recovering(r#"["abcdefgh", 1, "ab\"cdefg\\h"]"#, &options);
+    assert_eq!(
+        format!("{:?}", recovered.value),
+        "Array([Null, Number(1), Null])"
+    );
+    let errors: Vec<_> = recovered.errors.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        errors,
+        [
+            "String is too long at line 1, column 7, in $[0]",
+            "String is too long at line 1, column 23, in $[2]",
+        ]
+    );
+
+    // The first key is counted before the error that points at it.
+    let options = crate::ParseOptions::new().duplicate_keys(crate::DuplicateKeys::Error);
+    let text = "{\n  \"a\": tru,\n  \"a\": 2\n}";
+    let recovered = crate::parse_recovering(text, &options);
+    let errors: Vec<_> = recovered.errors.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        errors,
+        [
+            r#"Expected value after key "a" expected 'true' literal, found "," at line 2, column 10, in $.a"#,
+            r#"Duplicate key "a" at line 3, column 5, first at line 2, column 5"#,
+        ]
+    );
+}
+
+#[test]
+fn limits() {
+    use crate::ParseOptions;
+
+    let nested = |depth| "[".repeat(depth) + &"]".repeat(depth);
+    assert!(crate::parse(&nested(128)).is_ok());
+    let error = crate::parse(&nested(129)).unwrap_err();
+    assert_eq!(error.kind(), crate::ParsingErrorKind::DepthLimitExceeded);
+    assert_eq!(error.offset(), 128);
+    assert_snapshot_with(
+        r#"{"a": [{"b": 1}]}"#,
+        &ParseOptions::new().max_depth(2),
+        r#"Nesting is too deep (DepthLimitExceeded) at line 1, column 8, in $.a[0]"#,
+    );
+    let error = crate::parse_with("[[1]]", &ParseOptions::new().max_depth(1)).unwrap_err();
+    assert!(error
+        .report("[[1]]")
+        .to_string()
+        .contains("  ^ nested too deep\n"));
+    assert!(crate::parse_with(&nested(1000), &ParseOptions::new().max_depth(1000)).is_ok());
+
+    let options = ParseOptions::new().max_string_len(5);
+    assert_snapshot_with(
+        r#"{"pony": "Rarity"}"#,
+        &options,
+        r#"String is too long (StringTooLong) at line 1, column 16, in $.pony"#,
+    );
+    assert_snapshot_with(
+        r#"{"unicorn": 1}"#,
+        &options,
+        r#"String is too long (StringTooLong) at line 1, column 8"#,
+    );
+    // Counted after unescaping.
+    assert_snapshot_with(r#""\u0041\u0042""#, &options, r#"String("AB")"#);
+    let text = r#"{"pony": "Rarity"}"#;
+    let error = crate::parse_with(text, &options).unwrap_err();
+    assert!(error
+        .report(text)
+        .to_string()
+        .contains("^^^^^^^ string too long\n"));
+
+    let options = ParseOptions::new().max_document_len(8);
+    assert_snapshot_with(
+        "[1, 2, 3]",
+        &options,
+        r#"Document is too large (DocumentTooLarge) at line 1, column 9"#,
+    );
+    // at the character which crosses the limit
+    let error = crate::parse_with("[\"\u{e9}\u{e9}\u{e9}\u{e9}\"]", &options).unwrap_err();
+    assert_eq!((error.span(), error.column()), (8..10, 6));
+    assert_snapshot_with("[1, 2]", &options, r#"Array([Number(1), Number(2)])"#);
+}
+
+#[test]
+fn dialect() {
+    use crate::ParseOptions;
+
+    let text = "{\n  // ponies\n  \"mane\": [6, /* or 7 */ 7,],\n  \"cute\": true, /* yes */\n}";
+    assert_snapshot(
+        text,
+        r#"Expected string or closing curly, found '/' (Syntax) at line 2, column 3"#,
+    );
+    assert_snapshot_with(
+        text,
+        &ParseOptions::new()
+            .allow_comments(true)
+            .allow_trailing_commas(true),
+        r#"Object({"mane": Array([Number(6), Number(7)]), "cute": Bool(true)})"#,
+    );
+    assert_snapshot_with(
+        text,
+        &ParseOptions::new().allow_comments(true),
+        r#"Expected array value but found trailing comma unexpectedly (TrailingComma) at line 3, column 28, in $.mane"#,
+    );
+    assert_snapshot_with(
+        "[1 /* 2",
+        &ParseOptions::new().allow_comments(true),
+        r#"Expected comma or closed bracket, unterminated comment (Syntax) at line 1, column 7"#,
+    );
+    assert_snapshot_with(
+        "[1, 2,]",
+        &ParseOptions::new().allow_trailing_commas(true),
+        r#"Array([Number(1), Number(2)])"#,
+    );
+    assert_snapshot_with(
+        r#"{"a": [1, 2,]}"#,
+        &ParseOptions::new(),
+        r#"Expected array value but found trailing comma unexpectedly (TrailingComma) at line 1, column 13, in $.a"#,
+    );
+    assert_snapshot_with(
+        "[,]",
+        &ParseOptions::new().allow_trailing_commas(true),
+        r#"Expected array value or closing bracket, but found comma unexpectedly (ExpectedValue) at line 1, column 2, in $[0]"#,
+    );
+}
+
+#[test]
+fn duplicate_keys() {
+    use crate::{DuplicateKeys, ParseOptions};
+
+    let text = r#"{"a": 1, "b": 2, "a": 3}"#;
+    assert_snapshot(text, r#"Object({"a": Number(3), "b": Number(2)})"#);
+    let options = ParseOptions::new().duplicate_keys(DuplicateKeys::FirstWins);
+    assert_snapshot_with(
+        text,
+        &options,
+        r#"Object({"a": Number(1), "b": Number(2)})"#,
+    );
+    let options = ParseOptions::new().duplicate_keys(DuplicateKeys::Error);
+    assert_snapshot_with(
+        text,
+        &options,
+        r#"Duplicate key "a" (DuplicateKey) at line 1, column 20, first at line 1, column 4"#,
+    );
+    let error = crate::parse_with(text, &options).unwrap_err();
+    assert_eq!(error.span(), 17..20);
+    assert_eq!(error.first_key_span(), Some(1..4));
+    assert_eq!(error.first_key_position(), Some((1, 4)));
+    assert_eq!(
+        error.report(text).to_string(),
+        r#"error: Duplicate key "a"
+ --> line 1, column 20, in $
+  |
+1 | {"a": 1, "b": 2, "a": 3}
+  |                  ^^^ duplicate key
+  = note: the key first appears at line 1, column 4
+"#
+    );
+
+    // Only keys of the same object clash.
+    assert_snapshot_with(
+        r#"{"a": {"a": 1}, "b": {"a": 2}}"#,
+        &options,
+        r#"Object({"a": Object({"a": Number(1)}), "b": Object({"a": Number(2)})})"#,
+    );
+    assert_snapshot_with(
+        r#"[{"a": 1}, {"b": 2, "a": {}, "a": 3}]"#,
+        &options,
+        r#"Duplicate key "a" (DuplicateKey) at line 1, column 32, first at line 1, column 23, in $[1]"#,
+    );
+
+    let options = ParseOptions::new().duplicate_keys(DuplicateKeys::Collect);
+    assert_snapshot_with(
+        r#"{"a": 1, "b": [2], "a": [3], "a": 4, "b": 5}"#,
+        &options,
+        r#"Object({"a": Number(1), "b": Array([Number(2)]), "a": Array([Number(3)]), "a": Number(4), "b": Number(5)})"#,
+    );
+    let object = |text| match crate::parse_with(text, &options).unwrap() {
+        crate::Value::Object(map) => map,
+        value => panic!("{value:?} isn't an object"),
+    };
+    // a repeated key isn't an array
+    let repeated = object(r#"{"a": 1, "a": 2}"#);
+    let array = object(r#"{"a": [1, 2]}"#);
+    assert!(repeated != array);
+    assert_eq!(repeated.get_all("a").collect::<Vec<_>>(), [&1, &2]);
+    assert_eq!(array.get_all("a").count(), 1);
+    assert!(array.duplicates().is_empty());
+    let map = object(r#"{"a": [1], "a": 2}"#);
+    assert_eq!(map["a"], crate::json!([1]));
+    assert_eq!(map.duplicates().len(), 1);
+    assert_eq!(map.duplicates()[0].0, "a");
+    assert_eq!(map.duplicates()[0].1, 2);
+}
+
+#[test]
+fn key_order() {
+    use crate::ParseOptions;
+
+    let text = r#"{"zebra": 1, "apple": {"y": 2, "x": 3}, "mango": 4, "apple": 5}"#;
+    // a replaced key keeps its first place
+    assert_snapshot(
+        text,
+        r#"Object({"zebra": Number(1), "apple": Number(5), "mango": Number(4)})"#,
+    );
+    assert_snapshot(
+        r#"{"zebra": 1, "apple": {"y": 2, "x": 3}}"#,
+        r#"Object({"zebra": Number(1), "apple": Object({"y": Number(2), "x": Number(3)})})"#,
+    );
+
+    let options = ParseOptions::new().sort_keys(true);
+    assert_snapshot_with(
+        text,
+        &options,
+        r#"Object({"apple": Number(5), "mango": Number(4), "zebra": Number(1)})"#,
+    );
+    assert_snapshot_with(
+        r#"[{"zebra": 1, "apple": {"y": 2, "x": 3}}]"#,
+        &options,
+        r#"Array([Object({"apple": Object({"x": Number(3), "y": Number(2)}), "zebra": Number(1)})])"#,
+    );
+}
+
+/// Events of `string` separated by spaces, up to the error if there is one.
+fn events_snapshot(string: &str, options: &crate::ParseOptions) -> String {
+    let mut events = Vec::new();
+    for event in crate::Events::with_options(string, options) {
+        match event {
+            Ok(event) => events.push(format!("{event:?}")),
+            Err(error) => events.push(format!("!{error:#}")),
+        }
+    }
+    events.join(" ")
+}
+
+#[test]
+fn events() {
+    use crate::{DuplicateKeys, Event, ParseOptions};
+
+    let options = ParseOptions::new();
+    assert_eq!(
+        events_snapshot(r#"{"a": [1, "two", null], "b": {}, "c\n": true}"#, &options),
+        r#"StartObject Key("a") StartArray Scalar(Number(1)) Scalar(String("two")) Scalar(Null) EndArray Key("b") StartObject EndObject Key("c\n") Scalar(Bool(true)) EndObject"#
+    );
+    assert_eq!(events_snapshot("false", &options), "Scalar(Bool(false))");
+
+    // the events before the error are handed out, then nothing after it
+    assert_eq!(
+        events_snapshot(r#"[{"a": 1} 2]"#, &options),
+        r#"StartArray StartObject Key("a") Scalar(Number(1)) EndObject !Expected comma or closed bracket, but found number 2 unexpectedly (ExpectedCommaOrClosedBracket) at line 1, column 11"#
+    );
+    assert_eq!(
+        events_snapshot("[[", &options),
+        "StartArray StartArray !Expected array value or closing bracket, but the string ended unexpectedly (ExpectedValue) at the end, in $[0][0]"
+    );
+
+    // policies which pick values are left to whoever reads the events
+    let text = r#"{"a": 1, "a": 2}"#;
+    let options = ParseOptions::new().duplicate_keys(DuplicateKeys::FirstWins);
+    assert_eq!(
+        events_snapshot(text, &options),
+        r#"StartObject Key("a") Scalar(Number(1)) Key("a") Scalar(Number(2)) EndObject"#
+    );
+    let options = ParseOptions::new().duplicate_keys(DuplicateKeys::Error);
+    assert_eq!(
+        events_snapshot(text, &options),
+        r#"StartObject Key("a") Scalar(Number(1)) !Duplicate key "a" (DuplicateKey) at line 1, column 12, first at line 1, column 4"#
+    );
+
+    // the push API sees the same events
+    let text = crate::parser::test::texts::menu_string();
+    let mut pushed = Vec::new();
+    crate::parse_events(&text, &ParseOptions::new(), |event| pushed.push(event)).unwrap();
+    let pulled: Vec<Event> = crate::Events::new(&text).map(Result::unwrap).collect();
+    assert_eq!(pushed, pulled);
+    assert_eq!(pushed.len(), 33);
+}
+
+fn documents_snapshot(string: &str, options: &crate::ParseOptions) -> Vec<String> {
+    crate::Documents::with_options(string, options)
+        .map(|document| match document {
+            Ok(value) => format!("{value:?}"),
+            Err(error) => format!("!{error:#}"),
+        })
+        .collect()
+}
+
+#[test]
+fn documents() {
+    use crate::{DuplicateKeys, ParseOptions};
+
+    let options = ParseOptions::new();
+    assert_eq!(documents_snapshot("", &options), Vec::<String>::new());
+    assert_eq!(documents_snapshot(" \n\n ", &options), Vec::<String>::new());
+    assert_eq!(
+        documents_snapshot("{\"a\": 1}\n[2]\n\"three\"\n", &options),
+        [
+            r#"Object({"a": Number(1)})"#,
+            "Array([Number(2)])",
+            r#"String("three")"#
+        ]
+    );
+    // concatenated, with or without whitespace
+    assert_eq!(
+        documents_snapshot(r#"{}[]"x"1 2 true null{"#, &options),
+        [
+            "Object({})",
+            "Array([])",
+            r#"String("x")"#,
+            "Number(1)",
+            "Number(2)",
+            "Bool(true)",
+            "Null",
+            "!Expected string or closing curly, but the string ended unexpectedly (ExpectedKey) at the end",
+        ]
+    );
+    // a pretty-printed value
+    assert_eq!(
+        documents_snapshot("{\n  \"a\": [\n    1\n  ]\n}\n{\n}", &options),
+        [r#"Object({"a": Array([Number(1)])})"#, "Object({})"]
+    );
+
+    // a value running into the end of the text fails on its own line
+    assert_eq!(
+        documents_snapshot("{\"a\": 1}\n{\"b\": \n[3]\n", &options),
+        [
+            r#"Object({"a": Number(1)})"#,
+            "!Expected value after key \"b\" but the string ended unexpectedly (ExpectedValue) at the end, in $.b",
+            "Array([Number(3)])",
+        ]
+    );
+
+    // bad lines are reported with their line numbers, the rest is read
+    let lines = [
+        r#"{"level": "info", "message": "started"}"#,
+        r#"{"level": "warn", "message": oops}"#,
+        r#"{"level": "info", "message": "cut"#,
+        r#"{"level": "error", "#,
+        r#"[1, 2]]"#,
+        r#"{"level": "info", "message": "done"}"#,
+    ]
+    .join("\n");
+    assert_eq!(
+        documents_snapshot(&lines, &options),
+        [
+            r#"Object({"level": String("info"), "message": String("started")})"#,
+            "!Expected value after key \"message\" found 'o' (Syntax) at line 2, column 30, in $.message",
+            "!Expected value after key \"message\" missing double quote in: \"cut\" (Syntax) at line 3, column 33, in $.message",
+            "!Expected string but found open bracket unexpectedly (ExpectedKey) at line 5, column 1",
+            "Array([Number(1), Number(2)])",
+            "!Expected JSON object, array or literal - but found closed bracket unexpectedly (ExpectedValue) at line 5, column 7",
+            r#"Object({"level": String("info"), "message": String("done")})"#,
+        ]
+    );
+
+    // an unterminated string doesn't take the quote of the next line
+    assert_eq!(
+        documents_snapshot("{\"a\": \"cut\n{\"b\": 1}\n\"x\ny\"", &options),
+        [
+            "!Expected value after key \"a\" missing double quote in: \"cut\" (Syntax) at line 1, column 10, in $.a",
+            r#"Object({"b": Number(1)})"#,
+            "!Expected JSON object, array or literal - missing double quote in: \"x\" (Syntax) at line 3, column 2",
+            "!Expected JSON object, array or literal - found 'y' (Syntax) at line 4, column 1",
+        ]
+    );
+    let strict = ParseOptions::new().strict_strings(true);
+    assert_eq!(
+        documents_snapshot("[\"cut\n\"]", &strict),
+        [
+            "!Expected array value or closing bracket, missing double quote in: \"cut\" (Syntax) at line 1, column 5, in $[0]",
+            "!Expected JSON object, array or literal - missing double quote in: \"]\" (Syntax) at line 2, column 2",
+        ]
+    );
+
+    // positions are counted on from one value to the next
+    let lines = "{\"a\": 1, \"é\": 2, \"a\": 3}\n".repeat(3) + "\u{1f984}";
+    let options = ParseOptions::new().duplicate_keys(DuplicateKeys::Error);
+    assert_eq!(
+        documents_snapshot(&lines, &options),
+        [
+            "!Duplicate key \"a\" (DuplicateKey) at line 1, column 20, first at line 1, column 4",
+            "!Duplicate key \"a\" (DuplicateKey) at line 2, column 20, first at line 2, column 4",
+            "!Duplicate key \"a\" (DuplicateKey) at line 3, column 20, first at line 3, column 4",
+            "!Expected JSON object, array or literal - found '\u{1f984}' (Syntax) at line 4, column 1",
+        ]
+    );
 }
@@ -0,0 +1,443 @@
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::mem;
+
+use crate::value::Kind;
+use crate::Value;
+
+/// Why a JSON Pointer (RFC 6901) couldn't be followed.
+///
+/// `path` is the part of the pointer which could be followed,
+/// as it's written in the pointer: empty for the root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PointerError {
+    /// The pointer isn't empty and doesn't start with `/`.
+    MissingSlash,
+    /// A `~` which isn't `~0` or `~1`, at this byte offset of the pointer.
+    InvalidEscape { offset: usize },
+    /// The object at `path` has no member with this key.
+    MissingKey { path: String, key: String },
+    /// The segment after `path` isn't an array index: a number without
+    /// leading zeros, or `-` for the end of the array.
+    InvalidIndex { path: String, segment: String },
+    /// The array at `path` has no element at `index`.
+    IndexOutOfRange {
+        path: String,
+        index: usize,
+        len: usize,
+    },
+    /// The value at `path` is a scalar, it has nothing inside.
+    NotAContainer { path: String, found: String },
+}
+
+impl fmt::Display for PointerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PointerError::MissingSlash => f.write_str("JSON pointer must start with '/'"),
+            PointerError::InvalidEscape { offset } => {
+                write!(f, "'~' must be followed by '0' or '1', at byte {offset}")
+            }
+            PointerError::MissingKey { path, key } => {
+                write!(f, "No member {key:?} in the object at {path:?}")
+            }
+            PointerError::InvalidIndex { path, segment } => {
+                write!(
+                    f,
+                    "Expected an index, found {segment:?} in the array at {path:?}"
+                )
+            }
+            PointerError::IndexOutOfRange { path, index, len } => write!(
+                f,
+                "Index {index} is out of range for the array of length {len} at {path:?}"
+            ),
+            PointerError::NotAContainer { path, found } => {
+                write!(
+                    f,
+                    "Expected an object or an array, found {found} at {path:?}"
+                )
+            }
+        }
+    }
+}
+
+impl Error for PointerError {}
+
+/// A reference token of a pointer, unescaped.
+struct Segment<'p> {
+    key: Cow<'p, str>,
+    /// Byte offset of the `/` before the segment, where its parent's path ends.
+    start: usize,
+}
+
+impl Segment<'_> {
+    fn parent<'p>(&self, pointer: &'p str) -> &'p str {
+        &pointer[..self.start]
+    }
+
+    /// The index the segment points at in an array of `len` elements,
+    /// which may be past its end.
+    fn index(&self, len: usize, pointer: &str) -> Result<usize, PointerError> {
+        let key = self.key.as_ref();
+        if key == "-" {
+            return Ok(len);
+        }
+        let digits = !key.is_empty() && key.bytes().all(|byte| byte.is_ascii_digit());
+        let leading_zero = key.len() > 1 && key.starts_with('0');
+        match key.parse() {
+            Ok(index) if digits && !leading_zero => Ok(index),
+            _ => Err(PointerError::InvalidIndex {
+                path: self.parent(pointer).to_owned(),
+                segment: key.to_owned(),
+            }),
+        }
+    }
+
+    fn out_of_range(&self, index: usize, len: usize, pointer: &str) -> PointerError {
+        PointerError::IndexOutOfRange {
+            path: self.parent(pointer).to_owned(),
+            index,
+            len,
+        }
+    }
+
+    fn missing_key(&self, pointer: &str) -> PointerError {
+        PointerError::MissingKey {
+            path: self.parent(pointer).to_owned(),
+            key: self.key.clone().into_owned(),
+        }
+    }
+
+    fn not_a_container(&self, value: &Value<'_>, pointer: &str) -> PointerError {
+        PointerError::NotAContainer {
+            path: self.parent(pointer).to_owned(),
+            found: Kind(value).to_string(),
+        }
+    }
+}
+
+/// Splits `pointer` into its segments, with `~1` and `~0` turned back into `/` and `~`.
+fn segments(pointer: &str) -> Result<Vec<Segment<'_>>, PointerError> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let Some(rest) = pointer.strip_prefix('/') else {
+        return Err(PointerError::MissingSlash);
+    };
+    let mut start = 0;
+    let mut segments = Vec::new();
+    for raw in rest.split('/') {
+        let key = if raw.contains('~') {
+            let mut key = String::with_capacity(raw.len());
+            let mut rest = raw;
+            while let Some(tilde) = rest.find('~') {
+                key.push_str(&rest[..tilde]);
+                match rest.as_bytes().get(tilde + 1) {
+                    Some(b'0') => key.push('~'),
+                    Some(b'1') => key.push('/'),
+                    _ => {
+                        let offset = start + 1 + (raw.len() - rest.len()) + tilde;
+                        return Err(PointerError::InvalidEscape { offset });
+                    }
+                }
+                rest = &rest[tilde + 2..];
+            }
+            key.push_str(rest);
+            Cow::Owned(key)
+        } else {
+            Cow::Borrowed(raw)
+        };
+        segments.push(Segment { key, start });
+        start += 1 + raw.len();
+    }
+    Ok(segments)
+}
+
+impl<'a> Value<'a> {
+    /// Looks up a value by JSON Pointer (RFC 6901), like `/images/0/tags`.
+    /// The empty pointer is the value itself, `~1` stands for `/`
+    /// in a key and `~0` for `~`.
+    ///
+    /// Returns `None` if the pointer is malformed or there is no such value,
+    /// [`pointer_remove`](Value::pointer_remove) and
+    /// [`pointer_insert`](Value::pointer_insert) tell why.
+    ///
+    /// ```
+    /// let value = json::json!({"a/b": [{"c": 1}, {"c": 2}]});
+    /// assert_eq!(value.pointer("/a~1b/1/c"), Some(&json::json!(2)));
+    /// assert_eq!(value.pointer("/a~1b/2"), None);
+    /// ```
+    pub fn pointer(&self, pointer: &str) -> Option<&Value<'a>> {
+        segments(pointer)
+            .ok()?
+            .iter()
+            .try_fold(self, |value, segment| child(value, segment, pointer).ok())
+    }
+
+    /// Like [`pointer`](Value::pointer), but the value can be changed in place.
+    /// Returns `None` if the pointer is malformed or there is no such value.
+    ///
+    /// ```
+    /// let mut value = json::json!({"ponies": [{"name": "Rarity"}]});
+    /// *value.pointer_mut("/ponies/0/name").unwrap() = "Applejack".into();
+    /// assert_eq!(value, json::json!({"ponies": [{"name": "Applejack"}]}));
+    /// assert!(value.pointer_mut("/ponies/1").is_none());
+    /// ```
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Value<'a>> {
+        segments(pointer)
+            .ok()?
+            .iter()
+            .try_fold(self, |value, segment| {
+                child_mut(value, segment, pointer).ok()
+            })
+    }
+
+    /// Puts `value` where `pointer` points, and returns the value it replaced.
+    ///
+    /// Everything up to the last segment must exist. A member is added
+    /// to an object or replaced. In an array the value is inserted before
+    /// the index, which may be the length of the array or `-` to append.
+    /// The empty pointer replaces the whole value.
+    ///
+    /// ```
+    /// let mut value = json::json!({"ponies": ["Rarity"]});
+    /// value.pointer_insert("/ponies/-", "Spike".into()).unwrap();
+    /// value.pointer_insert("/ponies/0", "Applejack".into()).unwrap();
+    /// value.pointer_insert("/count", 3.into()).unwrap();
+    /// assert_eq!(value, json::json!({"ponies": ["Applejack", "Rarity", "Spike"], "count": 3}));
+    /// ```
+    pub fn pointer_insert(
+        &mut self,
+        pointer: &str,
+        value: Value<'a>,
+    ) -> Result<Option<Value<'a>>, PointerError> {
+        let segments = segments(pointer)?;
+        let Some((last, parents)) = segments.split_last() else {
+            return Ok(Some(mem::replace(self, value)));
+        };
+        let parent = parents
+            .iter()
+            .try_fold(self, |parent, segment| child_mut(parent, segment, pointer))?;
+        match parent {
+            Value::Object(map) => Ok(map.insert(last.key.clone().into_owned(), value)),
+            Value::Array(array) => {
+                let index = last.index(array.len(), pointer)?;
+                if index > array.len() {
+                    return Err(last.out_of_range(index, array.len(), pointer));
+                }
+                array.insert(index, value);
+                Ok(None)
+            }
+            parent => Err(last.not_a_container(parent, pointer)),
+        }
+    }
+
+    /// Removes the value `pointer` points at and returns it.
+    /// Later members of an object and elements of an array move up.
+    /// The empty pointer takes the whole value, leaving `Null`.
+    pub fn pointer_remove(&mut self, pointer: &str) -> Result<Value<'a>, PointerError> {
+        let segments = segments(pointer)?;
+        let Some((last, parents)) = segments.split_last() else {
+            return Ok(self.take());
+        };
+        let parent = parents
+            .iter()
+            .try_fold(self, |parent, segment| child_mut(parent, segment, pointer))?;
+        match parent {
+            Value::Object(map) => map
+                .remove(&last.key)
+                .ok_or_else(|| last.missing_key(pointer)),
+            Value::Array(array) => {
+                let index = last.index(array.len(), pointer)?;
+                if index >= array.len() {
+                    return Err(last.out_of_range(index, array.len(), pointer));
+                }
+                Ok(array.remove(index))
+            }
+            parent => Err(last.not_a_container(parent, pointer)),
+        }
+    }
+}
+
+fn child<'v, 'a>(
+    value: &'v Value<'a>,
+    segment: &Segment<'_>,
+    pointer: &str,
+) -> Result<&'v Value<'a>, PointerError> {
+    match value {
+        Value::Object(map) => map
+            .get(&segment.key)
+            .ok_or_else(|| segment.missing_key(pointer)),
+        Value::Array(array) => {
+            let index = segment.index(array.len(), pointer)?;
+            array
+                .get(index)
+                .ok_or_else(|| segment.out_of_range(index, array.len(), pointer))
+        }
+        value => Err(segment.not_a_container(value, pointer)),
+    }
+}
+
+fn child_mut<'v, 'a>(
+    value: &'v mut Value<'a>,
+    segment: &Segment<'_>,
+    pointer: &str,
+) -> Result<&'v mut Value<'a>, PointerError> {
+    match value {
+        Value::Object(map) => map
+            .get_mut(&segment.key)
+            .ok_or_else(|| segment.missing_key(pointer)),
+        Value::Array(array) => {
+            let len = array.len();
+            let index = segment.index(len, pointer)?;
+            array
+                .get_mut(index)
+                .ok_or_else(|| segment.out_of_range(index, len, pointer))
+        }
+        value => Err(segment.not_a_container(value, pointer)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+
+    #[test]
+    fn lookup() {
+        // the example of RFC 6901
+        let value = json!({
+            "foo": ["bar", "baz"],
+            "": 0,
+            "a/b": 1,
+            "c%d": 2,
+            "e^f": 3,
+            "g|h": 4,
+            "i\\j": 5,
+            "k\"l": 6,
+            " ": 7,
+            "m~n": 8
+        });
+        assert_eq!(value.pointer(""), Some(&value));
+        assert_eq!(value.pointer("/foo"), Some(&json!(["bar", "baz"])));
+        assert_eq!(value.pointer("/foo/0"), Some(&json!("bar")));
+        for (pointer, expected) in [
+            ("/", 0),
+            ("/a~1b", 1),
+            ("/c%d", 2),
+            ("/e^f", 3),
+            ("/g|h", 4),
+            ("/i\\j", 5),
+            ("/k\"l", 6),
+            ("/ ", 7),
+            ("/m~0n", 8),
+        ] {
+            assert_eq!(value.pointer(pointer), Some(&json!(expected)), "{pointer}");
+        }
+
+        for pointer in [
+            "foo", "/foo/2", "/foo/-", "/foo/01", "/foo/+1", "/bar", "/a/b", "/m~2n", "/m~", "/ /x",
+        ] {
+            assert_eq!(value.pointer(pointer), None, "{pointer}");
+        }
+
+        let mut value = value;
+        *value.pointer_mut("/foo/1").unwrap() = json!("qux");
+        assert_eq!(value["foo"], json!(["bar", "qux"]));
+        // `~01` is `~1` unescaped, not `/`
+        *value.pointer_mut("/m~0n").unwrap() = json!({"~1": true});
+        assert_eq!(value.pointer("/m~0n/~01"), Some(&json!(true)));
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut value = json!({"a": {"b": [1, 2]}});
+        assert_eq!(value.pointer_insert("/a/c", json!(3)), Ok(None));
+        assert_eq!(value.pointer_insert("/a/c", json!(4)), Ok(Some(json!(3))));
+        assert_eq!(value.pointer_insert("/a/b/1", json!(5)), Ok(None));
+        assert_eq!(value.pointer_insert("/a/b/3", json!(6)), Ok(None));
+        assert_eq!(value.pointer_insert("/a/b/-", json!(7)), Ok(None));
+        assert_eq!(value.pointer_insert("/a~1b", json!(8)), Ok(None));
+        assert_eq!(
+            value,
+            json!({"a": {"b": [1, 5, 2, 6, 7], "c": 4}, "a/b": 8})
+        );
+
+        assert_eq!(value.pointer_remove("/a/b/0"), Ok(json!(1)));
+        assert_eq!(value.pointer_remove("/a/b/3"), Ok(json!(7)));
+        assert_eq!(value.pointer_remove("/a~1b"), Ok(json!(8)));
+        assert_eq!(value.pointer_remove("/a/b"), Ok(json!([5, 2, 6])));
+        assert_eq!(value, json!({"a": {"c": 4}}));
+
+        assert_eq!(
+            value.pointer_insert("", json!([])),
+            Ok(Some(json!({"a": {"c": 4}})))
+        );
+        assert_eq!(value.pointer_remove(""), Ok(json!([])));
+        assert_eq!(value, Value::Null);
+    }
+
+    #[test]
+    fn errors() {
+        let mut value = json!({"a": {"b": [1, 2], "c": "d"}});
+        let errors = [
+            (
+                value.pointer_remove("a"),
+                "JSON pointer must start with '/'",
+            ),
+            (
+                value.pointer_remove("/a/~2"),
+                "'~' must be followed by '0' or '1', at byte 3",
+            ),
+            (
+                value.pointer_remove("/a/x"),
+                "No member \"x\" in the object at \"/a\"",
+            ),
+            (
+                value.pointer_remove("/x/y"),
+                "No member \"x\" in the object at \"\"",
+            ),
+            (
+                value.pointer_remove("/a/b/x"),
+                "Expected an index, found \"x\" in the array at \"/a/b\"",
+            ),
+            (
+                value.pointer_remove("/a/b/00"),
+                "Expected an index, found \"00\" in the array at \"/a/b\"",
+            ),
+            (
+                value.pointer_remove("/a/b/2"),
+                "Index 2 is out of range for the array of length 2 at \"/a/b\"",
+            ),
+            (
+                value.pointer_remove("/a/b/-"),
+                "Index 2 is out of range for the array of length 2 at \"/a/b\"",
+            ),
+            (
+                value.pointer_remove("/a/c/0"),
+                "Expected an object or an array, found a string at \"/a/c\"",
+            ),
+        ];
+        for (result, expected) in errors {
+            assert_eq!(result.unwrap_err().to_string(), expected);
+        }
+
+        assert_eq!(
+            value.pointer_insert("/a/b/3", json!(0)),
+            Err(PointerError::IndexOutOfRange {
+                path: "/a/b".to_owned(),
+                index: 3,
+                len: 2
+            })
+        );
+        assert_eq!(
+            value.pointer_insert("/a/x/y", json!(0)),
+            Err(PointerError::MissingKey {
+                path: "/a".to_owned(),
+                key: "x".to_owned()
+            })
+        );
+        // nothing changed
+        assert_eq!(value, json!({"a": {"b": [1, 2], "c": "d"}}));
+    }
+}
@@ -0,0 +1,264 @@
+use std::io;
+use std::str;
+
+use crate::{Error, IncrementalParser, ParseOptions, Value};
+
+/// How many bytes are read at once.
+const CHUNK_LEN: usize = 8 * 1024;
+
+/// Parses JSON text from `reader`, a chunk at a time.
+///
+/// Only the unparsed end of the last chunk is kept, not the whole text,
+/// so strings and keys are always owned. Errors are the ones
+/// [`parse`](crate::parse) gives for the same text, at the same positions,
+/// limits included.
+/// It's an [`IncrementalParser`] fed with what the reader returns.
+///
+/// ```
+/// let text = "{\n  \"pony\": \"Rarity\",\n  \"pony\" 1\n}";
+/// let error = json::from_reader(text.as_bytes()).unwrap_err();
+/// assert_eq!((error.line(), error.column()), (Some(3), Some(10)));
+/// ```
+pub fn from_reader(reader: impl io::Read) -> Result<Value<'static>, Error> {
+    from_reader_with(reader, &ParseOptions::default())
+}
+
+/// Parses JSON text from `reader` with the given settings.
+///
+/// A text longer than `max_document_len` fails once the character which
+/// crosses the limit is read, where [`parse_with`](crate::parse_with) fails,
+/// and the rest isn't read. Text which isn't UTF-8 fails with an I/O error of
+/// [`io::ErrorKind::InvalidData`].
+pub fn from_reader_with(
+    mut reader: impl io::Read,
+    options: &ParseOptions,
+) -> Result<Value<'static>, Error> {
+    let mut parser = IncrementalParser::with_options(options);
+    let mut bytes = vec![0; CHUNK_LEN];
+    // the first bytes of a character split between two reads
+    let mut pending = 0;
+    // bytes decoded so far
+    let mut decoded = 0;
+    loop {
+        let count = match reader.read(&mut bytes[pending..]) {
+            Ok(0) => break,
+            Ok(count) => count,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(Error::io(error)),
+        };
+        let filled = pending + count;
+        let valid = match str::from_utf8(&bytes[..filled]) {
+            Ok(_) => filled,
+            Err(error) if error.error_len().is_none() => error.valid_up_to(),
+            Err(error) => return Err(invalid_utf8(decoded + error.valid_up_to())),
+        };
+        let text = str::from_utf8(&bytes[..valid]).expect("checked above");
+        parser.feed(text).map_err(Error::syntax)?;
+
+        bytes.copy_within(valid..filled, 0);
+        pending = filled - valid;
+        decoded += valid;
+    }
+    if pending > 0 {
+        return Err(invalid_utf8(decoded));
+    }
+    parser.finish().map_err(Error::syntax)
+}
+
+fn invalid_utf8(offset: usize) -> Error {
+    let message = format!("invalid UTF-8 at byte {offset}");
+    Error::io(io::Error::new(io::ErrorKind::InvalidData, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+    use crate::{parse_with, DuplicateKeys, ParsingErrorKind};
+
+    /// Hands out at most `step` bytes per read.
+    struct Trickle<'a> {
+        bytes: &'a [u8],
+        step: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let count = self.step.min(buf.len()).min(self.bytes.len());
+            buf[..count].copy_from_slice(&self.bytes[..count]);
+            self.bytes = &self.bytes[count..];
+            Ok(count)
+        }
+    }
+
+    /// Reads `text` in steps of every size up to 7 bytes,
+    /// and checks the result against the in-memory parser.
+    fn assert_same(text: &str, options: &ParseOptions) {
+        let expected = parse_with(text, options).map(Value::into_owned);
+        for step in 1..8 {
+            let reader = Trickle {
+                bytes: text.as_bytes(),
+                step,
+            };
+            let actual = from_reader_with(reader, options);
+            match (&expected, actual) {
+                (Ok(expected), Ok(actual)) => assert_eq!(expected, &actual, "{text:?} by {step}"),
+                (Err(expected), Err(actual)) => {
+                    let actual = actual.parsing_error().expect("a syntax error");
+                    let (expected, actual) = (format!("{expected:?}"), format!("{actual:?}"));
+                    assert_eq!(expected, actual, "{text:?} by {step}");
+                }
+                (expected, actual) => panic!("{text:?} by {step}: {expected:?} vs {actual:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn values() {
+        let texts = [
+            "null",
+            " true ",
+            "[false, null]",
+            "1234567890",
+            "-12.5e-3",
+            "\"\"",
+            r#"{"pony": "Rarity", "friends": ["Spike", "Applejack"], "age": 19}"#,
+            "\"caf\u{e9} \u{1f984} \u{4e2d}\u{6587}\"",
+            r#""🦄 é \n\t\"""#,
+            "[\n  1,\n  [2, {\"\u{e9}\": [3]}]\n]\n",
+        ];
+        for text in texts {
+            assert_same(text, &ParseOptions::default());
+        }
+        let options = ParseOptions::default()
+            .allow_comments(true)
+            .allow_trailing_commas(true);
+        assert_same("// ponies\n[1, /* two */ 2, 3,] // done", &options);
+        assert_same("/* \u{1f984} */ {\"a\": 1,}", &options);
+    }
+
+    #[test]
+    fn errors() {
+        let texts = [
+            "",
+            "   \n  ",
+            "[1, 2",
+            "[1, 2 \n\n",
+            "{\"a\" 1}",
+            "[1 2]",
+            "nul",
+            "[tru]",
+            "01",
+            "1.",
+            "1e400",
+            "\"abc",
+            r#""\ud83d""#,
+            r#""\ud83d\n""#,
+            r#""\x""#,
+            "[1] 2",
+            "{\"\u{e9}\": \u{1f984}}",
+            "[\n  1,\n  \"x\" \"y\"\n]",
+        ];
+        for text in texts {
+            assert_same(text, &ParseOptions::default());
+        }
+        let strict = ParseOptions::default().strict_strings(true);
+        assert_same("[\n  \"a\tb\"]", &strict);
+        assert_same("[\"\u{e9}\u{e9}\n\"]", &strict);
+        assert_same(
+            "/* unterminated",
+            &ParseOptions::default().allow_comments(true),
+        );
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        let options = ParseOptions::default().duplicate_keys(DuplicateKeys::Error);
+        let text = "{\n  \"a\": 1,\n  \"b\": {\"a\": 2, \"c\": [1, 2, 3]},\n  \"a\": 3\n}";
+        assert_same(text, &options);
+        assert_same("{\"a\": {\"b\": 1, \"b\": 2}}", &options);
+        assert_same("{\"a\": {\"b\": 1}, \"b\": 2}", &options);
+        let text = format!(
+            "{{\"a\": 1, \"pad\": \"{}\", \"a\": 2}}",
+            "x".repeat(3 * CHUNK_LEN)
+        );
+        assert_same(&text, &options);
+    }
+
+    #[test]
+    fn string_limit() {
+        let options = ParseOptions::default().max_string_len(4);
+        let texts = [
+            r#"["abcd", "abcdefgh"]"#,
+            "[\"abcdef\nx\"]",
+            r#"["abcdef\u12"]"#,
+            r#"{"k": "abcdé\x"}"#,
+            r#"{"abcde": 1}"#,
+            r#"["abc\u00e9"]"#,
+            r#"["ab\ud83e\udd84"]"#,
+            r#"["ab\ud83e\u0041"]"#,
+            r#"["ab\x", "abcdefgh"]"#,
+            "[\"ab\u{1f984}\"]",
+        ];
+        for text in texts {
+            assert_same(text, &options);
+        }
+        let lossy = options.lossy_unicode(true);
+        assert_same(r#"["abc\ud83e"]"#, &lossy);
+        assert_same(r#"["abc\ud83e\n"]"#, &lossy);
+        let strict = options.strict_strings(true);
+        assert_same("[\"abcdef\u{1}\"]", &strict);
+        assert_same("[\"abc\u{1}\"]", &strict);
+
+        let options = ParseOptions::default().max_string_len(CHUNK_LEN);
+        let text = format!("[\"{}\"]", "\u{e9}".repeat(CHUNK_LEN));
+        assert_same(&text, &options);
+    }
+
+    #[test]
+    fn long_text() {
+        let text = format!(
+            "[{}]",
+            (0..5000)
+                .map(|number| format!("{{\"n\": {number}, \"s\": \"\u{e9}{number}\"}}"))
+                .collect::<Vec<_>>()
+                .join(",\n")
+        );
+        let expected = parse_with(&text, &ParseOptions::default()).map(Value::into_owned);
+        assert_eq!(expected.ok(), from_reader(text.as_bytes()).ok());
+
+        let broken = format!("{}}}", &text[..text.len() - 1]);
+        let expected = parse_with(&broken, &ParseOptions::default()).unwrap_err();
+        let actual = from_reader(broken.as_bytes()).unwrap_err();
+        assert_eq!(
+            Some(format!("{expected:?}")),
+            actual.parsing_error().map(|error| format!("{error:?}"))
+        );
+    }
+
+    #[test]
+    fn too_large() {
+        let options = ParseOptions::default().max_document_len(10);
+        assert_same("[1, 2, 3]", &options);
+        assert_same("[1, 2, 3, 4]", &options);
+        assert_same("[\n  1,\n  22]", &options);
+        assert_same("[\"\u{e9}\u{e9}\u{e9}\u{e9}\u{1f984}\"]", &options);
+        assert_same("[\"abcdefghijklmnop", &options);
+        let error = from_reader_with("[1, 2, 3, 4, 5]".as_bytes(), &options).unwrap_err();
+        assert_eq!(
+            error.parsing_error().map(|error| error.kind()),
+            Some(ParsingErrorKind::DocumentTooLarge)
+        );
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let error = from_reader(&b"[\"\xff\"]"[..]).unwrap_err();
+        assert!(error.is_io());
+        assert_eq!(error.to_string(), "I/O error: invalid UTF-8 at byte 2");
+
+        let error = from_reader(&b"\"\xe2\x82"[..]).unwrap_err();
+        assert_eq!(error.to_string(), "I/O error: invalid UTF-8 at byte 1");
+    }
+}
@@ -0,0 +1,555 @@
+use std::borrow::Cow;
+use std::io;
+
+use serde::ser::{self, Serialize};
+
+#[cfg(feature = "arbitrary_precision")]
+use crate::number;
+use crate::value::Kind;
+use crate::{Error, Map, Number, Value, WriteOptions};
+
+/// Serializes `value` as compact JSON text.
+///
+/// ```
+/// #[derive(serde::Serialize)]
+/// struct Pony {
+///     name: &'static str,
+///     friends: Vec<&'static str>,
+/// }
+///
+/// let pony = Pony { name: "Rarity", friends: vec!["Spike"] };
+/// assert_eq!(json::to_string(&pony).unwrap(), r#"{"name":"Rarity","friends":["Spike"]}"#);
+/// ```
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, Error> {
+    to_string_with(value, &WriteOptions::new())
+}
+
+/// Serializes `value` as JSON text with the given settings.
+pub fn to_string_with<T: Serialize + ?Sized>(
+    value: &T,
+    options: &WriteOptions,
+) -> Result<String, Error> {
+    Ok(to_value(value)?.to_string_with(options))
+}
+
+/// Serializes `value` as compact JSON text into `writer`.
+pub fn to_writer<T: Serialize + ?Sized>(writer: impl io::Write, value: &T) -> Result<(), Error> {
+    to_writer_with(writer, value, &WriteOptions::new())
+}
+
+/// Serializes `value` as JSON text with the given settings into `writer`.
+pub fn to_writer_with<T: Serialize + ?Sized>(
+    writer: impl io::Write,
+    value: &T,
+    options: &WriteOptions,
+) -> Result<(), Error> {
+    to_value(value)?
+        .to_writer_with(writer, options)
+        .map_err(Error::io)
+}
+
+/// Converts `value` into a [`Value`] tree.
+///
+/// The text functions above go through the tree as well: sorting keys and
+/// keeping short arrays on one line need the whole container at hand.
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<Value<'static>, Error> {
+    value.serialize(Serializer)
+}
+
+impl Serialize for Value<'_> {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Null => serializer.serialize_unit(),
+            Value::Bool(bool) => serializer.serialize_bool(*bool),
+            Value::Number(number) => number.serialize(serializer),
+            Value::String(string) => serializer.serialize_str(string),
+            Value::Array(values) => serializer.collect_seq(values),
+            Value::Object(object) => object.serialize(serializer),
+        }
+    }
+}
+
+impl Serialize for Map<'_> {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self)
+    }
+}
+
+/// With the `arbitrary_precision` feature, numbers which aren't 64-bit
+/// integers keep their digits: they're a newtype struct holding them as
+/// a string, which [`to_value`] turns back into a number.
+impl Serialize for Number {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if let Some(num) = self.as_u64() {
+            serializer.serialize_u64(num)
+        } else if let Some(num) = self.as_i64() {
+            serializer.serialize_i64(num)
+        } else {
+            #[cfg(feature = "arbitrary_precision")]
+            return serializer.serialize_newtype_struct(number::TOKEN, self.as_str());
+            #[cfg(not(feature = "arbitrary_precision"))]
+            serializer.serialize_f64(self.as_f64())
+        }
+    }
+}
+
+/// Builds a [`Value`] out of any `Serialize` type, the way `serde_json` lays
+/// them out: a unit variant is its name, other variants are objects with
+/// one member named after the variant.
+struct Serializer;
+
+fn string(string: impl Into<String>) -> Value<'static> {
+    Value::String(Cow::Owned(string.into()))
+}
+
+fn variant(name: &'static str, value: Value<'static>) -> Value<'static> {
+    let mut object = Map::new();
+    object.insert(name, value);
+    Value::Object(object)
+}
+
+impl ser::Serializer for Serializer {
+    type Ok = Value<'static>;
+    type Error = Error;
+
+    type SerializeSeq = SerializeArray;
+    type SerializeTuple = SerializeArray;
+    type SerializeTupleStruct = SerializeArray;
+    type SerializeTupleVariant = SerializeArray;
+    type SerializeMap = SerializeObject;
+    type SerializeStruct = SerializeObject;
+    type SerializeStructVariant = SerializeObject;
+
+    fn serialize_bool(self, bool: bool) -> Result<Value<'static>, Error> {
+        Ok(Value::Bool(bool))
+    }
+
+    fn serialize_i8(self, num: i8) -> Result<Value<'static>, Error> {
+        Ok(Value::from(num))
+    }
+
+    fn serialize_i16(self, num: i16) -> Result<Value<'static>, Error> {
+        Ok(Value::from(num))
+    }
+
+    fn serialize_i32(self, num: i32) -> Result<Value<'static>, Error> {
+        Ok(Value::from(num))
+    }
+
+    fn serialize_i64(self, num: i64) -> Result<Value<'static>, Error> {
+        Ok(Value::from(num))
+    }
+
+    fn serialize_i128(self, num: i128) -> Result<Value<'static>, Error> {
+        if let Ok(num) = i64::try_from(num) {
+            Ok(Value::from(num))
+        } else if let Ok(num) = u64::try_from(num) {
+            Ok(Value::from(num))
+        } else {
+            Err(ser::Error::custom(format!("{num} doesn't fit in 64 bits")))
+        }
+    }
+
+    fn serialize_u8(self, num: u8) -> Result<Value<'static>, Error> {
+        Ok(Value::from(num))
+    }
+
+    fn serialize_u16(self, num: u16) -> Result<Value<'static>, Error> {
+        Ok(Value::from(num))
+    }
+
+    fn serialize_u32(self, num: u32) -> Result<Value<'static>, Error> {
+        Ok(Value::from(num))
+    }
+
+    fn serialize_u64(self, num: u64) -> Result<Value<'static>, Error> {
+        Ok(Value::from(num))
+    }
+
+    fn serialize_u128(self, num: u128) -> Result<Value<'static>, Error> {
+        match u64::try_from(num) {
+            Ok(num) => Ok(Value::from(num)),
+            Err(_) => Err(ser::Error::custom(format!("{num} doesn't fit in 64 bits"))),
+        }
+    }
+
+    /// NaN and infinities become `null`, as with `Value::from`.
+    fn serialize_f32(self, num: f32) -> Result<Value<'static>, Error> {
+        Ok(Value::from(num))
+    }
+
+    /// NaN and infinities become `null`, as with `Value::from`.
+    fn serialize_f64(self, num: f64) -> Result<Value<'static>, Error> {
+        Ok(Value::from(num))
+    }
+
+    fn serialize_char(self, char: char) -> Result<Value<'static>, Error> {
+        Ok(string(char))
+    }
+
+    fn serialize_str(self, str: &str) -> Result<Value<'static>, Error> {
+        Ok(string(str))
+    }
+
+    /// Bytes are an array of numbers.
+    fn serialize_bytes(self, bytes: &[u8]) -> Result<Value<'static>, Error> {
+        Ok(Value::Array(
+            bytes.iter().map(|&byte| Value::from(byte)).collect(),
+        ))
+    }
+
+    fn serialize_none(self) -> Result<Value<'static>, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Value<'static>, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value<'static>, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value<'static>, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<Value<'static>, Error> {
+        Ok(string(variant))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Value<'static>, Error> {
+        #[cfg(feature = "arbitrary_precision")]
+        if _name == number::TOKEN {
+            return match value.serialize(self)? {
+                Value::String(digits) => number::from_digits(&digits)
+                    .map(Value::Number)
+                    .ok_or_else(|| ser::Error::custom(format!("{digits} isn't a JSON number"))),
+                _ => Err(ser::Error::custom(
+                    "a number must be serialized as its digits",
+                )),
+            };
+        }
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Value<'static>, Error> {
+        Ok(variant(name, value.serialize(self)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeArray, Error> {
+        Ok(SerializeArray {
+            variant: None,
+            values: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeArray, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray, Error> {
+        Ok(SerializeArray {
+            variant: Some(variant),
+            values: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeObject, Error> {
+        Ok(SerializeObject {
+            variant: None,
+            object: Map::with_capacity(len.unwrap_or(0)),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SerializeObject, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeObject, Error> {
+        Ok(SerializeObject {
+            variant: Some(variant),
+            object: Map::with_capacity(len),
+            key: None,
+        })
+    }
+}
+
+/// Elements of a sequence, a tuple or a tuple variant.
+struct SerializeArray {
+    /// Name of the tuple variant, whose object wraps the array.
+    variant: Option<&'static str>,
+    values: Vec<Value<'static>>,
+}
+
+impl SerializeArray {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.values.push(value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Value<'static>, Error> {
+        let array = Value::Array(self.values);
+        Ok(match self.variant {
+            Some(name) => variant(name, array),
+            None => array,
+        })
+    }
+}
+
+impl ser::SerializeSeq for SerializeArray {
+    type Ok = Value<'static>;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value<'static>, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for SerializeArray {
+    type Ok = Value<'static>;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value<'static>, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeArray {
+    type Ok = Value<'static>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value<'static>, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeArray {
+    type Ok = Value<'static>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value<'static>, Error> {
+        self.finish()
+    }
+}
+
+/// Members of a map, a struct or a struct variant.
+struct SerializeObject {
+    /// Name of the struct variant, whose object wraps the members.
+    variant: Option<&'static str>,
+    object: Map<'static>,
+    /// Key waiting for its value.
+    key: Option<String>,
+}
+
+impl SerializeObject {
+    fn finish(self) -> Result<Value<'static>, Error> {
+        let object = Value::Object(self.object);
+        Ok(match self.variant {
+            Some(name) => variant(name, object),
+            None => object,
+        })
+    }
+}
+
+impl ser::SerializeMap for SerializeObject {
+    type Ok = Value<'static>;
+    type Error = Error;
+
+    /// Keys are strings. Numbers and booleans are written as strings,
+    /// unit variants by their name.
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        let key = match key.serialize(Serializer)? {
+            Value::String(string) => string.into_owned(),
+            Value::Number(number) => number.to_string(),
+            Value::Bool(bool) => bool.to_string(),
+            other => {
+                return Err(ser::Error::custom(format!(
+                    "object keys must be strings, found {}",
+                    Kind(&other)
+                )))
+            }
+        };
+        self.key = Some(key);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .expect("serialize_value is called after serialize_key");
+        self.object.insert(key, value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value<'static>, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for SerializeObject {
+    type Ok = Value<'static>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.object.insert(key, value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value<'static>, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeObject {
+    type Ok = Value<'static>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.object.insert(key, value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value<'static>, Error> {
+        self.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde::Serialize;
+
+    use crate::{from_str, json, parse, to_string, to_string_with, to_value, Value, WriteOptions};
+
+    #[derive(Serialize)]
+    struct Image {
+        tags: Vec<&'static str>,
+        size: i32,
+        animated: bool,
+        source: Option<String>,
+        shape: Shape,
+    }
+
+    #[derive(Serialize)]
+    enum Shape {
+        Point,
+        Circle(f64),
+        Line(i32, i32),
+        Rect { width: u32, height: u32 },
+    }
+
+    #[test]
+    fn serialize() {
+        let image = Image {
+            tags: vec!["safe", "pony \"quoted\""],
+            size: -1,
+            animated: true,
+            source: None,
+            shape: Shape::Point,
+        };
+        assert_eq!(
+            to_string(&image).unwrap(),
+            r#"{"tags":["safe","pony \"quoted\""],"size":-1,"animated":true,"source":null,"shape":"Point"}"#
+        );
+        assert_eq!(
+            to_string_with(&image, &WriteOptions::new().indent(1).sort_keys(true)).unwrap(),
+            "{\n \"animated\": true,\n \"shape\": \"Point\",\n \"size\": -1,\n \"source\": null,\n \"tags\": [\n  \"safe\",\n  \"pony \\\"quoted\\\"\"\n ]\n}"
+        );
+
+        let shapes = [
+            Shape::Circle(0.5),
+            Shape::Line(1, -1),
+            Shape::Rect {
+                width: 2,
+                height: 3,
+            },
+        ];
+        assert_eq!(
+            to_value(&shapes).unwrap(),
+            json!([{"Circle": 0.5}, {"Line": [1, -1]}, {"Rect": {"width": 2, "height": 3}}])
+        );
+
+        let map = BTreeMap::from([(1, f64::NAN), (2, 1.5)]);
+        assert_eq!(to_string(&map).unwrap(), r#"{"1":null,"2":1.5}"#);
+        let error = to_string(&BTreeMap::from([((), 1)])).unwrap_err();
+        assert_eq!(error.to_string(), "object keys must be strings, found null");
+        assert_eq!(error.line(), None);
+    }
+
+    #[test]
+    fn value_round_trip() {
+        let text = r#"{"name":"Rarity","tags":["pony",null,true],"stats":{"magic":9.5,"age":-19},"big":18446744073709551615}"#;
+        let value = parse(text).unwrap();
+        assert_eq!(to_string(&value).unwrap(), text);
+        assert_eq!(to_value(&value).unwrap(), value);
+        let value: Value = from_str(text).unwrap();
+        assert_eq!(to_string(&value).unwrap(), text);
+
+        let mut bytes = Vec::new();
+        crate::to_writer(&mut bytes, &value).unwrap();
+        assert_eq!(bytes, text.as_bytes());
+    }
+}
@@ -0,0 +1,497 @@
+use std::mem;
+use std::ops;
+
+use crate::{Map, Number, Value};
+
+/// What `value[...]` gives for a missing key or index, or the wrong kind of value.
+static NULL: Value<'static> = Value::Null;
+
+impl<'a> Value<'a> {
+    /// Looks up a key of an object or an index of an array. Returns `None`
+    /// if there is no such member or element, or the value isn't a container.
+    pub fn get(&self, index: impl ValueIndex) -> Option<&Value<'a>> {
+        index.index_into(self)
+    }
+
+    pub fn get_mut(&mut self, index: impl ValueIndex) -> Option<&mut Value<'a>> {
+        index.index_into_mut(self)
+    }
+
+    /// Replaces the value with `Null` and returns what it was.
+    pub fn take(&mut self) -> Value<'a> {
+        mem::replace(self, Value::Null)
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    pub fn is_bool(&self) -> bool {
+        matches!(self, Value::Bool(_))
+    }
+
+    pub fn is_number(&self) -> bool {
+        matches!(self, Value::Number(_))
+    }
+
+    /// See [`Number::is_i64`].
+    pub fn is_i64(&self) -> bool {
+        self.as_number().is_some_and(Number::is_i64)
+    }
+
+    /// See [`Number::is_u64`].
+    pub fn is_u64(&self) -> bool {
+        self.as_number().is_some_and(Number::is_u64)
+    }
+
+    /// See [`Number::is_f64`].
+    pub fn is_f64(&self) -> bool {
+        self.as_number().is_some_and(Number::is_f64)
+    }
+
+    pub fn is_string(&self) -> bool {
+        matches!(self, Value::String(_))
+    }
+
+    pub fn is_array(&self) -> bool {
+        matches!(self, Value::Array(_))
+    }
+
+    pub fn is_object(&self) -> bool {
+        matches!(self, Value::Object(_))
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(bool) => Some(*bool),
+            _ => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<&Number> {
+        match self {
+            Value::Number(number) => Some(number),
+            _ => None,
+        }
+    }
+
+    /// Any number, integers above 2^53 lose precision.
+    pub fn as_f64(&self) -> Option<f64> {
+        self.as_number().map(Number::as_f64)
+    }
+
+    /// Integers which fit in `i64`, `None` for anything else.
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_number()?.as_i64()
+    }
+
+    /// Integers which fit in `u64`, `None` for anything else.
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_number()?.as_u64()
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<Value<'a>>> {
+        match self {
+            Value::Array(array) => Some(array),
+            _ => None,
+        }
+    }
+
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<Value<'a>>> {
+        match self {
+            Value::Array(array) => Some(array),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&Map<'a>> {
+        match self {
+            Value::Object(object) => Some(object),
+            _ => None,
+        }
+    }
+
+    pub fn as_object_mut(&mut self) -> Option<&mut Map<'a>> {
+        match self {
+            Value::Object(object) => Some(object),
+            _ => None,
+        }
+    }
+}
+
+/// Keys and indices for [`Value::get`] and `value[...]`:
+/// `&str` and `String` look into objects, `usize` into arrays.
+///
+/// This trait is sealed, it can't be implemented outside of the crate.
+pub trait ValueIndex: private::Sealed {
+    #[doc(hidden)]
+    fn index_into<'v, 'a>(&self, value: &'v Value<'a>) -> Option<&'v Value<'a>>;
+
+    #[doc(hidden)]
+    fn index_into_mut<'v, 'a>(&self, value: &'v mut Value<'a>) -> Option<&'v mut Value<'a>>;
+
+    /// Like `index_into_mut`, but a missing key is inserted as `Null`,
+    /// and `Null` itself becomes an empty object first.
+    #[doc(hidden)]
+    fn index_or_insert<'v, 'a>(&self, value: &'v mut Value<'a>) -> &'v mut Value<'a>;
+}
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for usize {}
+    impl Sealed for str {}
+    impl Sealed for String {}
+    impl<T: Sealed + ?Sized> Sealed for &T {}
+}
+
+impl ValueIndex for usize {
+    fn index_into<'v, 'a>(&self, value: &'v Value<'a>) -> Option<&'v Value<'a>> {
+        value.as_array()?.get(*self)
+    }
+
+    fn index_into_mut<'v, 'a>(&self, value: &'v mut Value<'a>) -> Option<&'v mut Value<'a>> {
+        value.as_array_mut()?.get_mut(*self)
+    }
+
+    fn index_or_insert<'v, 'a>(&self, value: &'v mut Value<'a>) -> &'v mut Value<'a> {
+        match value {
+            Value::Array(array) => {
+                let len = array.len();
+                match array.get_mut(*self) {
+                    Some(element) => element,
+                    None => panic!("index {self} is out of bounds of an array of {len}"),
+                }
+            }
+            _ => panic!("can't index {} with {self}", Kind(value)),
+        }
+    }
+}
+
+impl ValueIndex for str {
+    fn index_into<'v, 'a>(&self, value: &'v Value<'a>) -> Option<&'v Value<'a>> {
+        value.as_object()?.get(self)
+    }
+
+    fn index_into_mut<'v, 'a>(&self, value: &'v mut Value<'a>) -> Option<&'v mut Value<'a>> {
+        value.as_object_mut()?.get_mut(self)
+    }
+
+    fn index_or_insert<'v, 'a>(&self, value: &'v mut Value<'a>) -> &'v mut Value<'a> {
+        if value.is_null() {
+            *value = Value::Object(Map::new());
+        }
+        match value {
+            Value::Object(object) => {
+                if !object.contains_key(self) {
+                    object.insert(self.to_owned(), Value::Null);
+                }
+                &mut object[self]
+            }
+            _ => panic!("can't index {} with {self:?}", Kind(value)),
+        }
+    }
+}
+
+impl ValueIndex for String {
+    fn index_into<'v, 'a>(&self, value: &'v Value<'a>) -> Option<&'v Value<'a>> {
+        self.as_str().index_into(value)
+    }
+
+    fn index_into_mut<'v, 'a>(&self, value: &'v mut Value<'a>) -> Option<&'v mut Value<'a>> {
+        self.as_str().index_into_mut(value)
+    }
+
+    fn index_or_insert<'v, 'a>(&self, value: &'v mut Value<'a>) -> &'v mut Value<'a> {
+        self.as_str().index_or_insert(value)
+    }
+}
+
+impl<T: ValueIndex + ?Sized> ValueIndex for &T {
+    fn index_into<'v, 'a>(&self, value: &'v Value<'a>) -> Option<&'v Value<'a>> {
+        (**self).index_into(value)
+    }
+
+    fn index_into_mut<'v, 'a>(&self, value: &'v mut Value<'a>) -> Option<&'v mut Value<'a>> {
+        (**self).index_into_mut(value)
+    }
+
+    fn index_or_insert<'v, 'a>(&self, value: &'v mut Value<'a>) -> &'v mut Value<'a> {
+        (**self).index_or_insert(value)
+    }
+}
+
+/// `value["key"]` and `value[0]` never panic: a missing member or element,
+/// or indexing into something which isn't a container, gives `Null`.
+impl<'a, I: ValueIndex> ops::Index<I> for Value<'a> {
+    type Output = Value<'a>;
+
+    fn index(&self, index: I) -> &Value<'a> {
+        index.index_into(self).unwrap_or(&NULL)
+    }
+}
+
+/// `value["key"] = ...` inserts the member if it's missing, and turns
+/// `Null` into an object first. An index past the end of an array,
+/// or a key or index into anything else panics.
+impl<I: ValueIndex> ops::IndexMut<I> for Value<'_> {
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
+        index.index_or_insert(self)
+    }
+}
+
+/// Names the kind of a value in panic messages.
+pub(crate) struct Kind<'v, 'a>(pub(crate) &'v Value<'a>);
+
+impl std::fmt::Display for Kind<'_, '_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self.0 {
+            Value::Null => "null",
+            Value::Bool(_) => "a boolean",
+            Value::Number(_) => "a number",
+            Value::String(_) => "a string",
+            Value::Array(_) => "an array",
+            Value::Object(_) => "an object",
+        };
+        f.write_str(kind)
+    }
+}
+
+impl PartialEq<str> for Value<'_> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == Some(other)
+    }
+}
+
+impl PartialEq<&str> for Value<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == Some(*other)
+    }
+}
+
+impl PartialEq<String> for Value<'_> {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == Some(other.as_str())
+    }
+}
+
+impl PartialEq<bool> for Value<'_> {
+    fn eq(&self, other: &bool) -> bool {
+        self.as_bool() == Some(*other)
+    }
+}
+
+impl PartialEq<Value<'_>> for str {
+    fn eq(&self, other: &Value<'_>) -> bool {
+        other == self
+    }
+}
+
+impl PartialEq<Value<'_>> for &str {
+    fn eq(&self, other: &Value<'_>) -> bool {
+        other == self
+    }
+}
+
+impl PartialEq<Value<'_>> for String {
+    fn eq(&self, other: &Value<'_>) -> bool {
+        other == self
+    }
+}
+
+impl PartialEq<Value<'_>> for bool {
+    fn eq(&self, other: &Value<'_>) -> bool {
+        other == self
+    }
+}
+
+/// Integers compare with integer numbers of the same value,
+/// floats with any number.
+macro_rules! partial_eq_numbers {
+    ($($as:ident -> $into:ty: $($ty:ty)*;)*) => {
+        $($(
+            impl PartialEq<$ty> for Value<'_> {
+                fn eq(&self, other: &$ty) -> bool {
+                    self.$as().is_some_and(|number| number == <$into>::from(*other))
+                }
+            }
+
+            impl PartialEq<Value<'_>> for $ty {
+                fn eq(&self, other: &Value<'_>) -> bool {
+                    other == self
+                }
+            }
+        )*)*
+    };
+}
+
+partial_eq_numbers! {
+    as_i64 -> i64: i8 i16 i32 i64;
+    as_u64 -> u64: u8 u16 u32 u64;
+    as_f64 -> f64: f32 f64;
+}
+
+impl PartialEq<usize> for Value<'_> {
+    fn eq(&self, other: &usize) -> bool {
+        u64::try_from(*other).is_ok_and(|other| *self == other)
+    }
+}
+
+impl PartialEq<Value<'_>> for usize {
+    fn eq(&self, other: &Value<'_>) -> bool {
+        other == self
+    }
+}
+
+impl PartialEq<isize> for Value<'_> {
+    fn eq(&self, other: &isize) -> bool {
+        i64::try_from(*other).is_ok_and(|other| *self == other)
+    }
+}
+
+impl PartialEq<Value<'_>> for isize {
+    fn eq(&self, other: &Value<'_>) -> bool {
+        other == self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{json, parse, Value};
+
+    const TEXT: &str = r#"{
+        "name": "Rarity",
+        "age": 19,
+        "balance": -250,
+        "height": 1.62,
+        "unicorn": true,
+        "friends": ["Twilight", "Applejack"],
+        "pet": {"name": "Opal", "species": "cat"},
+        "enemy": null
+    }"#;
+
+    #[test]
+    fn accessors() {
+        let value = parse(TEXT).unwrap();
+
+        assert_eq!(value["name"].as_str(), Some("Rarity"));
+        assert_eq!(value["age"].as_i64(), Some(19));
+        assert_eq!(value["age"].as_u64(), Some(19));
+        assert_eq!(value["age"].as_f64(), Some(19.0));
+        assert_eq!(value["balance"].as_u64(), None);
+        assert_eq!(value["height"].as_f64(), Some(1.62));
+        assert_eq!(value["height"].as_i64(), None);
+        assert_eq!(value["unicorn"].as_bool(), Some(true));
+        assert_eq!(value["friends"].as_array().map(Vec::len), Some(2));
+        assert_eq!(value["pet"].as_object().map(|pet| pet.len()), Some(2));
+        assert_eq!(value["name"].as_bool(), None);
+
+        assert!(value["enemy"].is_null());
+        assert!(value["unicorn"].is_bool());
+        assert!(value["age"].is_number() && value["age"].is_u64() && value["age"].is_i64());
+        assert!(value["balance"].is_i64() && !value["balance"].is_u64());
+        assert!(value["height"].is_f64());
+        assert!(value["name"].is_string());
+        assert!(value["friends"].is_array());
+        assert!(value.is_object() && !value.is_array());
+
+        assert_eq!(
+            value.get("pet").and_then(|pet| pet.get("name")),
+            Some(&json!("Opal"))
+        );
+        assert_eq!(
+            value["friends"].get(1).and_then(Value::as_str),
+            Some("Applejack")
+        );
+        assert_eq!(value.get(String::from("enemy")), Some(&Value::Null));
+        assert_eq!(value.get("missing"), None);
+        assert_eq!(value.get(0), None);
+        assert_eq!(value["friends"].get(2), None);
+    }
+
+    #[test]
+    fn missing_is_null() {
+        let value = parse(TEXT).unwrap();
+        assert!(value["missing"].is_null());
+        assert!(value["missing"]["deeper"][3].is_null());
+        assert!(value["friends"][7].is_null());
+        assert!(value["friends"]["name"].is_null());
+        assert!(value["name"][0].is_null());
+        assert_eq!(value["pet"]["name"], "Opal");
+    }
+
+    #[test]
+    fn mutation() {
+        let mut value = parse(TEXT).unwrap();
+
+        if let Some(age) = value.get_mut("age") {
+            *age = json!(false);
+        }
+        assert_eq!(value["age"], false);
+
+        value["pet"]["name"] = json!("Opalescence");
+        value["pet"]["lives"] = Value::Null;
+        value["enemy"]["name"] = json!("Gemstone");
+        value["friends"][0] = Value::Null;
+        assert_eq!(
+            value["pet"].to_string(),
+            r#"{"name":"Opalescence","species":"cat","lives":null}"#
+        );
+        assert_eq!(value["enemy"].to_string(), r#"{"name":"Gemstone"}"#);
+        assert_eq!(value["friends"].to_string(), r#"[null,"Applejack"]"#);
+
+        let friends = value["friends"].take();
+        assert!(value["friends"].is_null());
+        assert_eq!(friends.as_array().map(Vec::len), Some(2));
+        assert!(value.as_object().unwrap().contains_key("friends"));
+    }
+
+    #[test]
+    #[should_panic(expected = "index 2 is out of bounds of an array of 2")]
+    fn index_mut_past_the_end() {
+        let mut value = parse(TEXT).unwrap();
+        value["friends"][2] = Value::Null;
+    }
+
+    #[test]
+    #[should_panic(expected = "can't index a string with \"first\"")]
+    fn index_mut_into_scalar() {
+        let mut value = parse(TEXT).unwrap();
+        value["name"]["first"] = Value::Null;
+    }
+
+    #[test]
+    fn primitives() {
+        let value = parse(TEXT).unwrap();
+
+        assert_eq!(value["name"], "Rarity");
+        assert_eq!(value["name"], String::from("Rarity"));
+        assert_eq!("Rarity", value["name"]);
+        assert_ne!(value["name"], "Twilight");
+        assert_eq!(value["unicorn"], true);
+        assert_ne!(value["unicorn"], false);
+        assert_ne!(value["enemy"], false);
+
+        assert_eq!(value["age"], 19);
+        assert_eq!(value["age"], 19u8);
+        assert_eq!(value["age"], 19usize);
+        assert_eq!(value["age"], 19.0);
+        assert_eq!(19, value["age"]);
+        assert_eq!(value["balance"], -250i16);
+        assert_ne!(value["balance"], 250u32);
+        assert_eq!(value["height"], 1.62);
+        assert_ne!(value["height"], 1);
+        assert_ne!(value["name"], 0);
+
+        assert_eq!(
+            value["pet"],
+            parse(r#"{"species": "cat", "name": "Opal"}"#).unwrap()
+        );
+    }
+}
@@ -0,0 +1,329 @@
+use std::borrow::Cow;
+use std::fmt::{self, Write};
+use std::io;
+
+use crate::options::Indent;
+use crate::{Value, WriteOptions};
+
+impl Value<'_> {
+    /// Writes the value as JSON text with the given settings.
+    pub fn to_string_with(&self, options: &WriteOptions) -> String {
+        let mut string = String::new();
+        Writer::new(&mut string, options)
+            .write_value(self, 0)
+            .expect("writing to a String doesn't fail");
+        string
+    }
+
+    /// Writes the value as compact JSON text.
+    pub fn to_writer(&self, writer: impl io::Write) -> io::Result<()> {
+        self.to_writer_with(writer, &WriteOptions::new())
+    }
+
+    /// Writes the value as JSON text with the given settings.
+    /// Small writes are frequent, so a [`BufWriter`](io::BufWriter) helps with files and sockets.
+    pub fn to_writer_with(&self, writer: impl io::Write, options: &WriteOptions) -> io::Result<()> {
+        let mut adapter = IoAdapter {
+            inner: writer,
+            error: Ok(()),
+        };
+        match Writer::new(&mut adapter, options).write_value(self, 0) {
+            Ok(()) => Ok(()),
+            Err(fmt::Error) => match adapter.error {
+                Err(error) => Err(error),
+                Ok(()) => Err(io::Error::other("formatting failed")),
+            },
+        }
+    }
+}
+
+/// Compact JSON text, or pretty printed with `{:#}`.
+impl fmt::Display for Value<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let options = if f.alternate() {
+            WriteOptions::pretty()
+        } else {
+            WriteOptions::new()
+        };
+        Writer::new(f, &options).write_value(self, 0)
+    }
+}
+
+struct Writer<'o, W> {
+    out: W,
+    options: &'o WriteOptions,
+}
+
+impl<'o, W: Write> Writer<'o, W> {
+    fn new(out: W, options: &'o WriteOptions) -> Writer<'o, W> {
+        Writer { out, options }
+    }
+
+    fn write_value(&mut self, value: &Value<'_>, depth: usize) -> fmt::Result {
+        match value {
+            Value::Null => self.out.write_str("null"),
+            Value::Bool(true) => self.out.write_str("true"),
+            Value::Bool(false) => self.out.write_str("false"),
+            Value::Number(number) => write!(self.out, "{number}"),
+            Value::String(string) => self.write_string(string),
+            Value::Array(values) => self.write_array(values, depth),
+            Value::Object(object) => {
+                if self.options.sort_keys {
+                    let mut members: Vec<_> = object.iter().collect();
+                    members.sort_by_key(|&(key, _)| key);
+                    self.write_object(members, depth)
+                } else {
+                    self.write_object(object, depth)
+                }
+            }
+        }
+    }
+
+    fn write_array(&mut self, values: &[Value<'_>], depth: usize) -> fmt::Result {
+        if values.is_empty() {
+            return self.out.write_str("[]");
+        }
+        let one_line = self.options.indent == Indent::None
+            || self.options.compact_scalar_arrays
+                && values
+                    .iter()
+                    .all(|value| !matches!(value, Value::Array(_) | Value::Object(_)));
+
+        self.out.write_char('[')?;
+        for (index, value) in values.iter().enumerate() {
+            if index > 0 {
+                self.out.write_char(',')?;
+                if one_line && self.options.indent != Indent::None {
+                    self.out.write_char(' ')?;
+                }
+            }
+            if !one_line {
+                self.write_newline(depth + 1)?;
+            }
+            self.write_value(value, depth + 1)?;
+        }
+        if !one_line {
+            self.write_newline(depth)?;
+        }
+        self.out.write_char(']')
+    }
+
+    fn write_object<'m, 'a: 'm>(
+        &mut self,
+        members: impl IntoIterator<Item = (&'m Cow<'a, str>, &'m Value<'a>)>,
+        depth: usize,
+    ) -> fmt::Result {
+        let mut members = members.into_iter().peekable();
+        if members.peek().is_none() {
+            return self.out.write_str("{}");
+        }
+
+        self.out.write_char('{')?;
+        let mut first = true;
+        for (key, value) in members {
+            if !first {
+                self.out.write_char(',')?;
+            }
+            first = false;
+            self.write_newline(depth + 1)?;
+            self.write_string(key)?;
+            self.out.write_char(':')?;
+            if self.options.indent != Indent::None {
+                self.out.write_char(' ')?;
+            }
+            self.write_value(value, depth + 1)?;
+        }
+        self.write_newline(depth)?;
+        self.out.write_char('}')
+    }
+
+    /// Starts a new line indented for `depth`, if pretty printing.
+    fn write_newline(&mut self, depth: usize) -> fmt::Result {
+        match self.options.indent {
+            Indent::None => Ok(()),
+            Indent::Spaces(width) => write!(self.out, "\n{:1$}", "", width * depth),
+            Indent::Tab => {
+                self.out.write_char('\n')?;
+                (0..depth).try_for_each(|_| self.out.write_char('\t'))
+            }
+        }
+    }
+
+    fn write_string(&mut self, string: &str) -> fmt::Result {
+        self.out.write_char('"')?;
+        // Runs of characters which need no escaping are written at once.
+        let mut start = 0;
+        for (index, char) in string.char_indices() {
+            let escape = match char {
+                '"' => "\\\"",
+                '\\' => "\\\\",
+                '\n' => "\\n",
+                '\r' => "\\r",
+                '\t' => "\\t",
+                '\u{8}' => "\\b",
+                '\u{c}' => "\\f",
+                '\u{0}'..='\u{1f}' => "",
+                _ if char.is_ascii() || !self.options.ascii_only => continue,
+                _ => "",
+            };
+            self.out.write_str(&string[start..index])?;
+            if escape.is_empty() {
+                for unit in char.encode_utf16(&mut [0; 2]) {
+                    write!(self.out, "\\u{unit:04x}")?;
+                }
+            } else {
+                self.out.write_str(escape)?;
+            }
+            start = index + char.len_utf8();
+        }
+        self.out.write_str(&string[start..])?;
+        self.out.write_char('"')
+    }
+}
+
+/// Lets the writer, which produces `fmt` text, write to an `io` sink.
+/// `fmt::Error` carries nothing, so the `io` error is kept aside.
+struct IoAdapter<W> {
+    inner: W,
+    error: io::Result<()>,
+}
+
+impl<W: io::Write> Write for IoAdapter<W> {
+    fn write_str(&mut self, string: &str) -> fmt::Result {
+        self.inner.write_all(string.as_bytes()).map_err(|error| {
+            self.error = Err(error);
+            fmt::Error
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse, ParseOptions, WriteOptions};
+
+    const TEXT: &str = r#"{"name": "Rarity", "tags": ["pony", "unicorn"], "stats": {"magic": 9.5, "cute": true}, "friends": [], "home": {}, "pet": null, "crown": [[1, 2], {"a": -0.5}]}"#;
+
+    #[test]
+    fn compact() {
+        let value = parse(TEXT).unwrap();
+        assert_eq!(
+            value.to_string(),
+            r#"{"name":"Rarity","tags":["pony","unicorn"],"stats":{"magic":9.5,"cute":true},"friends":[],"home":{},"pet":null,"crown":[[1,2],{"a":-0.5}]}"#
+        );
+        let mut bytes = Vec::new();
+        value.to_writer(&mut bytes).unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(), value.to_string());
+    }
+
+    #[test]
+    fn pretty() {
+        let value = parse(TEXT).unwrap();
+        assert_eq!(
+            format!("{value:#}"),
+            r#"{
+  "name": "Rarity",
+  "tags": [
+    "pony",
+    "unicorn"
+  ],
+  "stats": {
+    "magic": 9.5,
+    "cute": true
+  },
+  "friends": [],
+  "home": {},
+  "pet": null,
+  "crown": [
+    [
+      1,
+      2
+    ],
+    {
+      "a": -0.5
+    }
+  ]
+}"#
+        );
+
+        let options = WriteOptions::new()
+            .tabs()
+            .sort_keys(true)
+            .compact_scalar_arrays(true);
+        assert_eq!(
+            value.to_string_with(&options),
+            "{
+\t\"crown\": [
+\t\t[1, 2],
+\t\t{
+\t\t\t\"a\": -0.5
+\t\t}
+\t],
+\t\"friends\": [],
+\t\"home\": {},
+\t\"name\": \"Rarity\",
+\t\"pet\": null,
+\t\"stats\": {
+\t\t\"cute\": true,
+\t\t\"magic\": 9.5
+\t},
+\t\"tags\": [\"pony\", \"unicorn\"]
+}"
+        );
+
+        let options = WriteOptions::new().indent(4);
+        assert_eq!(
+            parse(r#"{"a": [{}]}"#).unwrap().to_string_with(&options),
+            "{\n    \"a\": [\n        {}\n    ]\n}"
+        );
+    }
+
+    #[test]
+    fn escapes() {
+        let value = parse(r#"["quote \" slash \\ \/ \b\f\n\r\t \u0001 é 🦄"]"#).unwrap();
+        assert_eq!(
+            value.to_string(),
+            r#"["quote \" slash \\ / \b\f\n\r\t \u0001 é 🦄"]"#
+        );
+        let options = WriteOptions::new().ascii_only(true);
+        assert_eq!(
+            value.to_string_with(&options),
+            r#"["quote \" slash \\ / \b\f\n\r\t \u0001 \u00e9 \ud83e\udd84"]"#
+        );
+
+        // raw control characters are accepted by default, but never written
+        let value = parse("\"tab\tbell\u{7}\"").unwrap();
+        assert_eq!(value.to_string(), r#""tab\tbell\u0007""#);
+    }
+
+    #[test]
+    fn round_trip() {
+        let texts = [
+            TEXT,
+            "0",
+            "-1",
+            "18446744073709551615",
+            "-9223372036854775808",
+            "18446744073709551616",
+            "[1.0, 1e-7, 6.02E23, -100.000001, 5e-324, 1.7976931348623157e308]",
+            r#""🦄   \u0000""#,
+            r#"{"": {"": [[], {}, [null]]}, "key with \"quotes\"": false}"#,
+        ];
+        let menu = crate::parser::test::texts::menu_string();
+        let options = [
+            WriteOptions::new(),
+            WriteOptions::pretty(),
+            WriteOptions::new().tabs().compact_scalar_arrays(true),
+            WriteOptions::new().ascii_only(true).indent(0),
+        ];
+        for text in texts.iter().copied().chain([menu.as_str()]) {
+            let value = parse(text).unwrap();
+            for options in &options {
+                let written = value.to_string_with(options);
+                let reparsed =
+                    crate::parse_with(&written, &ParseOptions::new().strict_strings(true))
+                        .unwrap_or_else(|error| panic!("{written} doesn't parse: {error}"));
+                assert_eq!(format!("{reparsed:?}"), format!("{value:?}"), "{written}");
+            }
+        }
+    }
+}
@@ -7,7 +7,6 @@ use std::process::ExitCode;
 use camino::Utf8PathBuf;
 use clap::Parser;
 use serde::Deserialize;
-use serde_json;
 
 #[derive(serde::Serialize)]
 struct JsonOutput {
@@ -81,7 +80,7 @@ struct Cli {
 fn try_main() -> Result<(), Box<dyn Error>> {
     let args = Args::parse();
 
-    let derpi_data = match serde_json::from_str::<Response>(&match fs::read_to_string(args.path) {
+    let derpi_data = match json::from_str::<Response>(&match fs::read_to_string(args.path) {
         Ok(it) => it,
         Err(err) => return Err(Box::new(err)),
     }) {
@@ -334,9 +333,12 @@ fn sort_by_popularity(
     //     count += 1;
     // }
 
-    let json = serde_json::to_string_pretty(&JsonOutput {
-        tags_count: ranged_tags_count,
-    })?;
+    let json = json::to_string_with(
+        &JsonOutput {
+            tags_count: ranged_tags_count,
+        },
+        &json::WriteOptions::pretty(),
+    )?;
     println!("{}", &json);
     Ok(())
 }